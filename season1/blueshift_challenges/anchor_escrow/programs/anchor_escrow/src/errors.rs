@@ -10,4 +10,12 @@ pub enum EscrowError {
     InvalidMintA,
     #[msg("Invalid mint b")]
     InvalidMintB,
+    #[msg("Duplicate mint in basket")]
+    DuplicateMint,
+    #[msg("Invalid basket legs")]
+    InvalidBasketLegs,
+    #[msg("Invalid vault")]
+    InvalidVault,
+    #[msg("Unsupported mint extension")]
+    UnsupportedMint,
 }
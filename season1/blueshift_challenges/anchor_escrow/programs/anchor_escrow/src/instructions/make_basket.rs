@@ -0,0 +1,179 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::{
+    create, get_associated_token_address_with_program_id, AssociatedToken, Create,
+};
+use anchor_spl::token_interface::{transfer_checked, Mint, TokenAccount, TransferChecked};
+
+use crate::errors::EscrowError;
+use crate::instructions::token_interface::check_mint_extensions;
+use crate::state::{Basket, BasketLeg};
+
+/// Remaining accounts per deposit leg: mint, maker_ata, vault, token_program
+pub const MAKE_DEPOSIT_LEG_ACCOUNTS: usize = 4;
+
+#[derive(Accounts)]
+#[instruction(seed: u64)]
+pub struct MakeBasket<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+    #[account(
+        init,
+        payer = maker,
+        space = Basket::INIT_SPACE + Basket::DISCRIMINATOR.len(),
+        seeds = [b"basket", maker.key().as_ref(), seed.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub basket: Account<'info, Basket>,
+
+    /// Programs
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+/// The mint must belong to `token_program`, which must be SPL Token or Token-2022
+pub fn check_token_program(mint: &AccountInfo, token_program: &AccountInfo) -> Result<()> {
+    require!(
+        token_program.key() == anchor_spl::token::ID
+            || token_program.key() == anchor_spl::token_2022::ID,
+        ErrorCode::InvalidProgramId
+    );
+    require_keys_eq!(*mint.owner, token_program.key(), ErrorCode::InvalidProgramId);
+    Ok(())
+}
+
+/// The vault must be the basket's ATA for `mint` under `token_program`
+pub fn check_vault(
+    vault: &AccountInfo,
+    basket: &Pubkey,
+    mint: &AccountInfo,
+    token_program: &AccountInfo,
+) -> Result<()> {
+    let expected =
+        get_associated_token_address_with_program_id(basket, mint.key, token_program.key);
+    require_keys_eq!(vault.key(), expected, EscrowError::InvalidVault);
+    Ok(())
+}
+
+fn has_duplicate_mint(mints: &[Pubkey]) -> bool {
+    mints
+        .iter()
+        .enumerate()
+        .any(|(i, mint)| mints[i + 1..].contains(mint))
+}
+
+impl<'info> MakeBasket<'info> {
+    /// # Create a vault per deposited mint and fill it
+    fn deposit_leg(&mut self, leg: &'info [AccountInfo<'info>], amount: u64) -> Result<()> {
+        let [mint, maker_ata, vault, token_program] = leg else {
+            return err!(EscrowError::InvalidBasketLegs);
+        };
+        check_token_program(mint, token_program)?;
+        check_vault(vault, &self.basket.key(), mint, token_program)?;
+        check_mint_extensions(mint)?;
+
+        let mint_account = InterfaceAccount::<Mint>::try_from(mint)?;
+        let maker_account = InterfaceAccount::<TokenAccount>::try_from(maker_ata)?;
+        require_keys_eq!(maker_account.owner, self.maker.key(), ErrorCode::ConstraintTokenOwner);
+        require_keys_eq!(maker_account.mint, mint.key(), ErrorCode::ConstraintTokenMint);
+
+        create(CpiContext::new(
+            self.associated_token_program.to_account_info(),
+            Create {
+                payer: self.maker.to_account_info(),
+                associated_token: vault.clone(),
+                authority: self.basket.to_account_info(),
+                mint: mint.clone(),
+                system_program: self.system_program.to_account_info(),
+                token_program: token_program.clone(),
+            },
+        ))?;
+
+        transfer_checked(
+            CpiContext::new(
+                token_program.clone(),
+                TransferChecked {
+                    from: maker_ata.clone(),
+                    mint: mint.clone(),
+                    to: vault.clone(),
+                    authority: self.maker.to_account_info(),
+                },
+            ),
+            amount,
+            mint_account.decimals,
+        )?;
+
+        // Record what actually arrived: transfer fees are withheld in the vault
+        let received = InterfaceAccount::<TokenAccount>::try_from(vault)?.amount;
+        self.basket.deposits.push(BasketLeg {
+            mint: mint.key(),
+            amount: received,
+        });
+
+        Ok(())
+    }
+}
+
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, MakeBasket<'info>>,
+    seed: u64,
+    deposit_amounts: Vec<u64>,
+    ask_amounts: Vec<u64>,
+) -> Result<()> {
+    // Validate the legs
+    require!(
+        (1..=Basket::MAX_DEPOSITS).contains(&deposit_amounts.len())
+            && (1..=Basket::MAX_ASKS).contains(&ask_amounts.len()),
+        EscrowError::InvalidBasketLegs
+    );
+    require!(
+        deposit_amounts.iter().chain(ask_amounts.iter()).all(|amount| *amount > 0),
+        EscrowError::InvalidAmount
+    );
+
+    let deposit_len = deposit_amounts.len() * MAKE_DEPOSIT_LEG_ACCOUNTS;
+    require_eq!(
+        ctx.remaining_accounts.len(),
+        deposit_len + ask_amounts.len(),
+        EscrowError::InvalidBasketLegs
+    );
+    let (deposit_legs, ask_mints) = ctx.remaining_accounts.split_at(deposit_len);
+
+    let deposit_mints: Vec<Pubkey> = deposit_legs
+        .chunks_exact(MAKE_DEPOSIT_LEG_ACCOUNTS)
+        .map(|leg| leg[0].key())
+        .collect();
+    let ask_mint_keys: Vec<Pubkey> = ask_mints.iter().map(|mint| mint.key()).collect();
+    require!(
+        !has_duplicate_mint(&deposit_mints) && !has_duplicate_mint(&ask_mint_keys),
+        EscrowError::DuplicateMint
+    );
+
+    // Save the Basket Data
+    ctx.accounts.basket.set_inner(Basket {
+        seed,
+        maker: ctx.accounts.maker.key(),
+        deposits: Vec::with_capacity(deposit_amounts.len()),
+        asks: Vec::with_capacity(ask_amounts.len()),
+        bump: ctx.bumps.basket,
+    });
+
+    // Deposit Tokens
+    for (leg, amount) in deposit_legs
+        .chunks_exact(MAKE_DEPOSIT_LEG_ACCOUNTS)
+        .zip(deposit_amounts)
+    {
+        ctx.accounts.deposit_leg(leg, amount)?;
+    }
+
+    // Record the asked mints
+    for (mint, amount) in ask_mints.iter().zip(ask_amounts) {
+        InterfaceAccount::<Mint>::try_from(mint)?;
+        check_mint_extensions(mint)?;
+        ctx.accounts.basket.asks.push(BasketLeg {
+            mint: mint.key(),
+            amount,
+        });
+    }
+
+    Ok(())
+}
@@ -1,7 +1,14 @@
 pub mod make;
+pub mod make_basket;
 pub mod refund;
+pub mod refund_basket;
 pub mod take;
+pub mod take_basket;
+pub mod token_interface;
 
 pub use make::*;
+pub use make_basket::*;
 pub use refund::*;
+pub use refund_basket::*;
 pub use take::*;
+pub use take_basket::*;
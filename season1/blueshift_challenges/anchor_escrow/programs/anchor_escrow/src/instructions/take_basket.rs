@@ -0,0 +1,166 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::{create_idempotent, AssociatedToken, Create};
+use anchor_spl::token_interface::{transfer_checked, Mint, TokenAccount, TransferChecked};
+
+use crate::errors::EscrowError;
+use crate::instructions::make_basket::{check_token_program, check_vault};
+use crate::instructions::token_interface::{close_vault, gross_amount};
+use crate::state::Basket;
+
+/// Remaining accounts per deposit leg: mint (writable for transfer-fee mints), vault, taker_ata, token_program
+pub const TAKE_DEPOSIT_LEG_ACCOUNTS: usize = 4;
+/// Remaining accounts per ask leg: mint, taker_ata, maker_ata, token_program
+pub const TAKE_ASK_LEG_ACCOUNTS: usize = 4;
+
+#[derive(Accounts)]
+pub struct TakeBasket<'info> {
+    #[account(mut)]
+    pub taker: Signer<'info>,
+    #[account(mut)]
+    pub maker: SystemAccount<'info>,
+    #[account(
+        mut,
+        close = maker,
+        seeds = [b"basket", maker.key().as_ref(), basket.seed.to_le_bytes().as_ref()],
+        bump = basket.bump,
+        has_one = maker @ EscrowError::InvalidMaker,
+    )]
+    pub basket: Box<Account<'info, Basket>>,
+
+    /// Programs
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> TakeBasket<'info> {
+    /// # Pay one asked mint to the maker, grossed up so the maker nets `amount` after transfer fees
+    fn pay_ask(&self, leg: &'info [AccountInfo<'info>], amount: u64) -> Result<()> {
+        let [mint, taker_ata, maker_ata, token_program] = leg else {
+            return err!(EscrowError::InvalidBasketLegs);
+        };
+        let mint_account = InterfaceAccount::<Mint>::try_from(mint)?;
+
+        create_idempotent(CpiContext::new(
+            self.associated_token_program.to_account_info(),
+            Create {
+                payer: self.taker.to_account_info(),
+                associated_token: maker_ata.clone(),
+                authority: self.maker.to_account_info(),
+                mint: mint.clone(),
+                system_program: self.system_program.to_account_info(),
+                token_program: token_program.clone(),
+            },
+        ))?;
+
+        transfer_checked(
+            CpiContext::new(
+                token_program.clone(),
+                TransferChecked {
+                    from: taker_ata.clone(),
+                    mint: mint.clone(),
+                    to: maker_ata.clone(),
+                    authority: self.taker.to_account_info(),
+                },
+            ),
+            gross_amount(mint, amount)?,
+            mint_account.decimals,
+        )?;
+
+        Ok(())
+    }
+
+    /// # Empty one vault to the taker and close it
+    fn withdraw_leg(&self, leg: &'info [AccountInfo<'info>]) -> Result<()> {
+        let [mint, vault, taker_ata, token_program] = leg else {
+            return err!(EscrowError::InvalidBasketLegs);
+        };
+        let mint_account = InterfaceAccount::<Mint>::try_from(mint)?;
+        let vault_amount = InterfaceAccount::<TokenAccount>::try_from(vault)?.amount;
+
+        // Create the signer seeds for the Basket
+        let signer_seeds: [&[&[u8]]; 1] = [&[
+            b"basket",
+            self.maker.to_account_info().key.as_ref(),
+            &self.basket.seed.to_le_bytes()[..],
+            &[self.basket.bump],
+        ]];
+
+        create_idempotent(CpiContext::new(
+            self.associated_token_program.to_account_info(),
+            Create {
+                payer: self.taker.to_account_info(),
+                associated_token: taker_ata.clone(),
+                authority: self.taker.to_account_info(),
+                mint: mint.clone(),
+                system_program: self.system_program.to_account_info(),
+                token_program: token_program.clone(),
+            },
+        ))?;
+
+        // Transfer the vault (Vault -> Taker)
+        transfer_checked(
+            CpiContext::new_with_signer(
+                token_program.clone(),
+                TransferChecked {
+                    from: vault.clone(),
+                    mint: mint.clone(),
+                    to: taker_ata.clone(),
+                    authority: self.basket.to_account_info(),
+                },
+                &signer_seeds,
+            ),
+            vault_amount,
+            mint_account.decimals,
+        )?;
+
+        // Harvest withheld transfer fees, then close the Vault
+        close_vault(
+            token_program,
+            mint,
+            vault,
+            self.maker.to_account_info(),
+            self.basket.to_account_info(),
+            &signer_seeds,
+        )?;
+
+        Ok(())
+    }
+}
+
+pub fn handler<'info>(ctx: Context<'_, '_, 'info, 'info, TakeBasket<'info>>) -> Result<()> {
+    let basket = &ctx.accounts.basket;
+    let deposit_len = basket.deposits.len() * TAKE_DEPOSIT_LEG_ACCOUNTS;
+    require_eq!(
+        ctx.remaining_accounts.len(),
+        deposit_len + basket.asks.len() * TAKE_ASK_LEG_ACCOUNTS,
+        EscrowError::InvalidBasketLegs
+    );
+    let (deposit_legs, ask_legs) = ctx.remaining_accounts.split_at(deposit_len);
+
+    // Validate every leg against the basket before moving anything
+    for (leg, deposit) in deposit_legs
+        .chunks_exact(TAKE_DEPOSIT_LEG_ACCOUNTS)
+        .zip(basket.deposits.iter())
+    {
+        require_keys_eq!(leg[0].key(), deposit.mint, EscrowError::InvalidMintA);
+        check_token_program(&leg[0], &leg[3])?;
+        check_vault(&leg[1], &basket.key(), &leg[0], &leg[3])?;
+    }
+    for (leg, ask) in ask_legs.chunks_exact(TAKE_ASK_LEG_ACCOUNTS).zip(basket.asks.iter()) {
+        require_keys_eq!(leg[0].key(), ask.mint, EscrowError::InvalidMintB);
+        check_token_program(&leg[0], &leg[3])?;
+    }
+
+    // Transfer every asked mint to Maker
+    let asks: Vec<u64> = basket.asks.iter().map(|ask| ask.amount).collect();
+    for (leg, amount) in ask_legs.chunks_exact(TAKE_ASK_LEG_ACCOUNTS).zip(asks) {
+        ctx.accounts.pay_ask(leg, amount)?;
+    }
+
+    // Withdraw and close every Vault
+    for leg in deposit_legs.chunks_exact(TAKE_DEPOSIT_LEG_ACCOUNTS) {
+        ctx.accounts.withdraw_leg(leg)?;
+    }
+
+    Ok(())
+}
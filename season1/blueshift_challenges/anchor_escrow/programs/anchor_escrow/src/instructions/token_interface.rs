@@ -0,0 +1,96 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::spl_token_2022::extension::non_transferable::NonTransferable;
+use anchor_spl::token_2022::spl_token_2022::extension::permanent_delegate::PermanentDelegate;
+use anchor_spl::token_2022::spl_token_2022::extension::transfer_fee::TransferFeeConfig;
+use anchor_spl::token_2022::spl_token_2022::extension::transfer_hook::TransferHook;
+use anchor_spl::token_2022::spl_token_2022::extension::{
+    BaseStateWithExtensions, StateWithExtensions,
+};
+use anchor_spl::token_2022::spl_token_2022::state::Mint as Token2022Mint;
+use anchor_spl::token_2022_extensions::transfer_fee::{
+    harvest_withheld_tokens_to_mint, HarvestWithheldTokensToMint,
+};
+use anchor_spl::token_interface::{close_account, CloseAccount};
+
+use crate::errors::EscrowError;
+
+/// Transfer fee config of a Token-2022 mint; `None` for legacy mints or without the extension
+fn with_transfer_fee<T>(
+    mint: &AccountInfo,
+    f: impl FnOnce(&TransferFeeConfig) -> T,
+) -> Result<Option<T>> {
+    if *mint.owner != anchor_spl::token_2022::ID {
+        return Ok(None);
+    }
+    let data = mint.try_borrow_data()?;
+    let state = StateWithExtensions::<Token2022Mint>::unpack(&data)?;
+    Ok(state.get_extension::<TransferFeeConfig>().ok().map(f))
+}
+
+/// Reject Token-2022 mints the basket cannot honour: non-transferable tokens,
+/// transfer hooks (need extra accounts) and permanent delegates (can drain the vault)
+pub fn check_mint_extensions(mint: &AccountInfo) -> Result<()> {
+    if *mint.owner != anchor_spl::token_2022::ID {
+        return Ok(());
+    }
+    let data = mint.try_borrow_data()?;
+    let state = StateWithExtensions::<Token2022Mint>::unpack(&data)?;
+    require!(
+        state.get_extension::<NonTransferable>().is_err()
+            && state.get_extension::<PermanentDelegate>().is_err(),
+        EscrowError::UnsupportedMint
+    );
+    // An unset hook program id is harmless
+    if let Ok(hook) = state.get_extension::<TransferHook>() {
+        require!(
+            Option::<Pubkey>::from(hook.program_id).is_none(),
+            EscrowError::UnsupportedMint
+        );
+    }
+    Ok(())
+}
+
+/// Smallest amount to send so that at least `net` arrives after the mint's transfer fee
+pub fn gross_amount(mint: &AccountInfo, net: u64) -> Result<u64> {
+    let epoch = Clock::get()?.epoch;
+    match with_transfer_fee(mint, |config| {
+        config.get_epoch_fee(epoch).calculate_pre_fee_amount(net)
+    })? {
+        None => Ok(net),
+        Some(gross) => gross.ok_or_else(|| ProgramError::ArithmeticOverflow.into()),
+    }
+}
+
+/// Close a vault that was emptied by a transfer, harvesting withheld
+/// transfer fees to the mint first (Token-2022 refuses to close otherwise)
+pub fn close_vault<'info>(
+    token_program: &AccountInfo<'info>,
+    mint: &AccountInfo<'info>,
+    vault: &AccountInfo<'info>,
+    destination: AccountInfo<'info>,
+    authority: AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    if with_transfer_fee(mint, |_| ())?.is_some() {
+        harvest_withheld_tokens_to_mint(
+            CpiContext::new(
+                token_program.clone(),
+                HarvestWithheldTokensToMint {
+                    token_program_id: token_program.clone(),
+                    mint: mint.clone(),
+                },
+            ),
+            vec![vault.clone()],
+        )?;
+    }
+
+    close_account(CpiContext::new_with_signer(
+        token_program.clone(),
+        CloseAccount {
+            account: vault.clone(),
+            authority,
+            destination,
+        },
+        signer_seeds,
+    ))
+}
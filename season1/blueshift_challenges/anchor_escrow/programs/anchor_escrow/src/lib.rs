@@ -25,4 +25,27 @@ pub mod anchor_escrow {
     pub fn refund(ctx: Context<Refund>) -> Result<()> {
         instructions::refund::handler(ctx)
     }
+
+    // 4/5/6 match the basket instructions of pinocchio_escrow (3 is its RefundExpired)
+    #[instruction(discriminator = 4)]
+    pub fn make_basket<'info>(
+        ctx: Context<'_, '_, 'info, 'info, MakeBasket<'info>>,
+        seed: u64,
+        deposit_amounts: Vec<u64>,
+        ask_amounts: Vec<u64>,
+    ) -> Result<()> {
+        instructions::make_basket::handler(ctx, seed, deposit_amounts, ask_amounts)
+    }
+
+    #[instruction(discriminator = 5)]
+    pub fn take_basket<'info>(ctx: Context<'_, '_, 'info, 'info, TakeBasket<'info>>) -> Result<()> {
+        instructions::take_basket::handler(ctx)
+    }
+
+    #[instruction(discriminator = 6)]
+    pub fn refund_basket<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RefundBasket<'info>>,
+    ) -> Result<()> {
+        instructions::refund_basket::handler(ctx)
+    }
 }
@@ -10,3 +10,26 @@ pub struct Escrow {
     pub receive: u64,
     pub bump: u8,
 }
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct BasketLeg {
+    pub mint: Pubkey,
+    pub amount: u64,
+}
+
+#[derive(InitSpace)]
+#[account(discriminator = 2)]
+pub struct Basket {
+    pub seed: u64,
+    pub maker: Pubkey,
+    #[max_len(4)]
+    pub deposits: Vec<BasketLeg>,
+    #[max_len(4)]
+    pub asks: Vec<BasketLeg>,
+    pub bump: u8,
+}
+
+impl Basket {
+    pub const MAX_DEPOSITS: usize = 4;
+    pub const MAX_ASKS: usize = 4;
+}
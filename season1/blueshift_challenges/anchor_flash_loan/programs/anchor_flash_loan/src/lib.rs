@@ -1,88 +1,232 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{
-  token::{Token, TokenAccount, Mint, Transfer, transfer}, 
+  token::{Token, TokenAccount, Mint, Transfer, transfer, MintTo, mint_to, Burn, burn}, 
   associated_token::AssociatedToken
 }; 
-use anchor_lang::solana_program::sysvar::instructions::{
-    ID as INSTRUCTIONS_SYSVAR_ID,
-    load_instruction_at_checked,
-    load_current_index_checked
+use anchor_lang::{
+  Discriminator,
+  solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program::invoke,
+    sysvar::instructions::{
+      ID as INSTRUCTIONS_SYSVAR_ID,
+      load_instruction_at_checked,
+      load_current_index_checked
+    }
+  }
 };
-use sha2::{Sha256, Digest};
 
 declare_id!("22222222222222222222222222222222222222222222");
 
-// Helper function to compute instruction discriminator
-// Anchor uses "global:<function_name>" as the preimage for discriminator
-fn compute_instruction_discriminator(name: &str) -> [u8; 8] {
-    let mut hasher = Sha256::new();
-    hasher.update(format!("global:{}", name).as_bytes());
-    let hash = hasher.finalize();
-    let mut discriminator = [0u8; 8];
-    discriminator.copy_from_slice(&hash[..8]);
-    discriminator
+/// Positions in `Loan` compared between a borrow and its repay
+const MINT_INDEX: usize = 3;
+const BORROWER_ATA_INDEX: usize = 4;
+const PROTOCOL_ATA_INDEX: usize = 5;
+
+/// Basis-point denominator for `fee_bps` and `max_borrow_bps`
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+/// Virtual shares and assets added to every pool when pricing shares. The
+/// first deposit still mints 1 share per token, but a donation to the pool
+/// is split with the virtual share, so inflating the share price to round
+/// later deposits down costs the attacker more than it takes from them.
+pub const VIRTUAL_SHARES: u64 = 1;
+pub const VIRTUAL_ASSETS: u64 = 1;
+
+/// `sha256("global:on_flash_loan")[..8]`, so an Anchor receiver only has to
+/// declare `on_flash_loan(amount: u64, fee: u64, data: Vec<u8>)`
+pub const ON_FLASH_LOAN_DISCRIMINATOR: [u8; 8] = [195, 212, 238, 236, 80, 204, 73, 167];
+
+/// Arguments of the callback `flash_loan` CPIs into the receiver program.
+///
+/// Instruction data is `ON_FLASH_LOAN_DISCRIMINATOR` followed by this struct
+/// in borsh. Accounts, in order: borrower (signer), receiver_ata (writable),
+/// protocol_ata (writable), mint, token_program, then the remaining accounts
+/// of `flash_loan` as passed. By the time the callback returns the receiver
+/// must have sent `amount + fee` to protocol_ata.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct OnFlashLoan {
+  pub amount: u64,
+  pub fee: u64,
+  /// Opaque to the protocol, forwarded from `flash_loan`
+  pub data: Vec<u8>,
 }
 
 #[program]
 pub mod blueshift_anchor_flash_loan {
   use super::*;
 
-  pub fn borrow(ctx: Context<Loan>, borrow_amount: u64) -> Result<()> {
+  /// Create the protocol config. The first caller becomes the admin, so
+  /// deployments should initialize it right away.
+  pub fn initialize_config(
+    ctx: Context<InitializeConfig>,
+    fee_bps: u16,
+    max_borrow_bps: u16,
+  ) -> Result<()> {
+    validate_config(fee_bps, max_borrow_bps)?;
+    ctx.accounts.config.set_inner(ProtocolConfig {
+      admin: ctx.accounts.admin.key(),
+      fee_bps,
+      max_borrow_bps,
+      paused: false,
+      bump: ctx.bumps.config,
+    });
+    Ok(())
+  }
+
+  pub fn update_config(ctx: Context<AdminConfig>, fee_bps: u16, max_borrow_bps: u16) -> Result<()> {
+    validate_config(fee_bps, max_borrow_bps)?;
+    let config = &mut ctx.accounts.config;
+    config.fee_bps = fee_bps;
+    config.max_borrow_bps = max_borrow_bps;
+    Ok(())
+  }
+
+  /// Pausing only stops new borrows; open loans can still be repaid
+  pub fn set_paused(ctx: Context<AdminConfig>, paused: bool) -> Result<()> {
+    ctx.accounts.config.paused = paused;
+    Ok(())
+  }
+
+  pub fn transfer_admin(ctx: Context<AdminConfig>, new_admin: Pubkey) -> Result<()> {
+    ctx.accounts.config.admin = new_admin;
+    Ok(())
+  }
+
+  /// Open a lending pool for `mint`: its liquidity is the protocol ATA and
+  /// lenders hold shares minted by the protocol PDA. The ATA must be empty,
+  /// or its tokens would be claimed by whoever deposits first.
+  pub fn create_pool(ctx: Context<CreatePool>) -> Result<()> {
+    require!(ctx.accounts.protocol_ata.amount == 0, ProtocolError::PoolNotEmpty);
+    ctx.accounts.pool.set_inner(Pool {
+      mint: ctx.accounts.mint.key(),
+      share_mint: ctx.accounts.share_mint.key(),
+      borrowed: 0,
+      bump: ctx.bumps.pool,
+    });
+    Ok(())
+  }
+
+  /// Deposit `amount` into the pool for shares at the current share price
+  pub fn deposit_liquidity(ctx: Context<Liquidity>, amount: u64) -> Result<()> {
+    require!(amount > 0, ProtocolError::InvalidAmount);
+    ctx.accounts.check_idle()?;
+
+    let shares = shares_for_deposit(
+      amount,
+      ctx.accounts.protocol_ata.amount,
+      ctx.accounts.share_mint.supply,
+    )?;
+    require!(shares > 0, ProtocolError::InvalidAmount);
+
+    transfer(
+      CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+          from: ctx.accounts.lender_ata.to_account_info(),
+          to: ctx.accounts.protocol_ata.to_account_info(),
+          authority: ctx.accounts.lender.to_account_info(),
+        },
+      ),
+      amount,
+    )?;
+
+    let seeds = &[b"protocol".as_ref(), &[ctx.bumps.protocol]];
+    mint_to(
+      CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        MintTo {
+          mint: ctx.accounts.share_mint.to_account_info(),
+          to: ctx.accounts.lender_share_ata.to_account_info(),
+          authority: ctx.accounts.protocol.to_account_info(),
+        },
+        &[&seeds[..]],
+      ),
+      shares,
+    )
+  }
+
+  /// Burn `shares` for their part of the pool, fees included
+  pub fn withdraw_liquidity(ctx: Context<Liquidity>, shares: u64) -> Result<()> {
+    require!(shares > 0, ProtocolError::InvalidAmount);
+    ctx.accounts.check_idle()?;
+
+    let amount = amount_for_shares(
+      shares,
+      ctx.accounts.protocol_ata.amount,
+      ctx.accounts.share_mint.supply,
+    )?;
+    require!(amount > 0, ProtocolError::InvalidAmount);
+
+    burn(
+      CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        Burn {
+          mint: ctx.accounts.share_mint.to_account_info(),
+          from: ctx.accounts.lender_share_ata.to_account_info(),
+          authority: ctx.accounts.lender.to_account_info(),
+        },
+      ),
+      shares,
+    )?;
+
+    let seeds = &[b"protocol".as_ref(), &[ctx.bumps.protocol]];
+    transfer(
+      CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+          from: ctx.accounts.protocol_ata.to_account_info(),
+          to: ctx.accounts.lender_ata.to_account_info(),
+          authority: ctx.accounts.protocol.to_account_info(),
+        },
+        &[&seeds[..]],
+      ),
+      amount,
+    )
+  }
+
+  /// `repay_index` is the transaction index of the `repay` closing this loan
+  pub fn borrow(ctx: Context<Loan>, borrow_amount: u64, repay_index: u16) -> Result<()> {
     msg!("Borrow: borrow_amount = {}", borrow_amount);
     
-    // Validate borrow amount
-    require!(borrow_amount > 0, ProtocolError::InvalidAmount);
-    
-    // Check if protocol has enough funds
+    check_loan_amount(
+      &ctx.accounts.config,
+      ctx.accounts.protocol_ata.amount,
+      borrow_amount,
+    )?;
+
+    // The paired repay must sit exactly at `repay_index`, on the same mint and
+    // token accounts, with no other borrow or repay of this pool in between.
+    // Loans from other pools may interleave.
+    let instructions = ctx.accounts.instructions.to_account_info();
+    let current_ix_index = current_loan_index(&instructions)?;
+    let repay_index = repay_index as usize;
     require!(
-      ctx.accounts.protocol_ata.amount >= borrow_amount,
-      ProtocolError::NotEnoughFunds
+      repay_index > current_ix_index,
+      ProtocolError::InvalidInstructionIndex
     );
 
-    // Get the current instruction index
-    let current_ix_index = load_current_index_checked(
-      &ctx.accounts.instructions.to_account_info()
-    )? as usize;
-    
-    msg!("Borrow: current_ix_index = {}", current_ix_index);
-    
-    // Check if there's a repay instruction after this borrow instruction
-    let mut found_repay = false;
-    let repay_discriminator = compute_instruction_discriminator("repay");
-    msg!("Borrow: Looking for repay instruction, discriminator = {:?}", repay_discriminator);
-    msg!("Borrow: Our program_id = {}", ctx.program_id);
-    
-    let mut i = current_ix_index + 1;
-    loop {
-      match load_instruction_at_checked(i, &ctx.accounts.instructions.to_account_info()) {
-        Ok(ix) => {
-          msg!("Borrow: Checking instruction at index {}, program_id = {}, data_len = {}", 
-               i, ix.program_id, ix.data.len());
-          
-          // Check if this is a repay instruction to our program
-          if ix.program_id == *ctx.program_id && ix.data.len() >= 8 {
-            let discriminator = &ix.data[..8];
-            msg!("Borrow: Instruction discriminator = {:?}", discriminator);
-            if discriminator == repay_discriminator.as_ref() {
-              msg!("Borrow: Found matching repay instruction at index {}", i);
-              found_repay = true;
-              break;
-            } else {
-              msg!("Borrow: Discriminator mismatch - expected {:?}, got {:?}", 
-                   repay_discriminator, discriminator);
-            }
-          }
-          i += 1;
-        }
-        Err(_) => {
-          msg!("Borrow: Reached end of instructions at index {}", i);
-          break; // Reached end of instructions
-        }
-      }
+    let repay_ix = load_instruction_at_checked(repay_index, &instructions)
+      .map_err(|_| ProtocolError::MissingRepayIx)?;
+    require_keys_eq!(repay_ix.program_id, crate::ID, ProtocolError::ProgramMismatch);
+    require!(
+      repay_ix.data.starts_with(instruction::Repay::DISCRIMINATOR),
+      ProtocolError::MissingRepayIx
+    );
+    ctx.accounts.check_paired(&repay_ix)?;
+
+    for i in current_ix_index + 1..repay_index {
+      let ix = load_instruction_at_checked(i, &instructions)?;
+      require!(!ctx.accounts.is_pool_loan_ix(&ix), ProtocolError::NestedLoan);
     }
-    
-    msg!("Borrow: found_repay = {}", found_repay);
-    require!(found_repay, ProtocolError::MissingRepayIx);
+
+    let pool = &mut ctx.accounts.pool;
+    pool.borrowed = pool
+      .borrowed
+      .checked_add(borrow_amount)
+      .ok_or(ProtocolError::Overflow)?;
+
+    msg!("Borrow: paired with repay at index {}", repay_index);
 
     // Transfer tokens from protocol to borrower
     let seeds = &[b"protocol".as_ref(), &[ctx.bumps.protocol]];
@@ -106,85 +250,47 @@ pub mod blueshift_anchor_flash_loan {
   }
 
   pub fn repay(ctx: Context<Loan>) -> Result<()> {
-    // Get the current instruction index
-    let current_ix_index = load_current_index_checked(
-      &ctx.accounts.instructions.to_account_info()
-    )? as usize;
-    
-    msg!("Repay: current_ix_index = {}", current_ix_index);
-    
-    // Check if there's a borrow instruction before this repay instruction
-    let mut found_borrow = false;
-    let mut borrow_amount = 0u64;
-    let borrow_discriminator = compute_instruction_discriminator("borrow");
-    
-    msg!("Repay: Looking for borrow instruction, discriminator = {:?}", borrow_discriminator);
-    msg!("Repay: Our program_id = {}", ctx.program_id);
-    
-    // If current_ix_index is 0, we need to search from the beginning
-    // Otherwise, search from 0 to current_ix_index (exclusive)
-    // But since we're in a CPI context, we need to search all instructions
-    // Let's try searching from 0 up to a reasonable limit
-    let mut i = 0;
-    let max_index = current_ix_index.max(10); // Search at least 10 instructions or until current_ix_index
-    
-    while i < max_index {
-      match load_instruction_at_checked(i, &ctx.accounts.instructions.to_account_info()) {
-        Ok(ix) => {
-          msg!("Repay: Checking instruction at index {}, program_id = {}, data_len = {}", 
-               i, ix.program_id, ix.data.len());
-          
-          // Check if this is a borrow instruction to our program
-          if ix.program_id == *ctx.program_id {
-            msg!("Repay: Found instruction from our program at index {}", i);
-            if ix.data.len() >= 16 {
-              let discriminator = &ix.data[..8];
-              msg!("Repay: Instruction discriminator = {:?}", discriminator);
-              if discriminator == borrow_discriminator.as_ref() {
-                msg!("Repay: Found matching borrow instruction at index {}", i);
-                found_borrow = true;
-                // Extract borrow_amount from instruction data (after discriminator)
-                borrow_amount = u64::from_le_bytes(
-                  ix.data[8..16].try_into().map_err(|_| ProtocolError::InvalidAmount)?
-                );
-                msg!("Repay: Extracted borrow_amount = {}", borrow_amount);
-                break;
-              } else {
-                msg!("Repay: Discriminator mismatch - expected {:?}, got {:?}", 
-                     borrow_discriminator, discriminator);
-              }
-            } else {
-              msg!("Repay: Instruction data too short: {} < 16", ix.data.len());
-            }
-          } else {
-            msg!("Repay: Program ID mismatch - expected {}, got {}", 
-                 ctx.program_id, ix.program_id);
-          }
-          i += 1;
-        }
-        Err(e) => {
-          msg!("Repay: Error loading instruction at index {}: {:?}", i, e);
-          // If we can't load an instruction, it might be invalid, but continue searching
-          i += 1;
-          if i >= max_index {
-            break;
-          }
-        }
+    // The closest earlier borrow / repay of this pool must be the borrow
+    // that names this instruction as its repay
+    let instructions = ctx.accounts.instructions.to_account_info();
+    let current_ix_index = current_loan_index(&instructions)?;
+    let mut borrow_ix = None;
+    for i in (0..current_ix_index).rev() {
+      let ix = load_instruction_at_checked(i, &instructions)?;
+      if ctx.accounts.is_pool_loan_ix(&ix) {
+        borrow_ix = Some(ix);
+        break;
       }
     }
-    
-    msg!("Repay: found_borrow = {}", found_borrow);
-    require!(found_borrow, ProtocolError::MissingBorrowIx);
+    let borrow_ix = borrow_ix
+      .filter(|ix| ix.data.starts_with(instruction::Borrow::DISCRIMINATOR))
+      .ok_or(ProtocolError::MissingBorrowIx)?;
+
+    let borrow_args = instruction::Borrow::try_from_slice(
+      &borrow_ix.data[instruction::Borrow::DISCRIMINATOR.len()..],
+    )
+    .map_err(|_| ProtocolError::InvalidAmount)?;
+    require!(
+      borrow_args.repay_index as usize == current_ix_index,
+      ProtocolError::MissingBorrowIx
+    );
+    ctx.accounts.check_paired(&borrow_ix)?;
+
+    let borrow_amount = borrow_args.borrow_amount;
     require!(borrow_amount > 0, ProtocolError::InvalidAmount);
 
-    // Calculate repayment amount with 5% fee
-    // repay_amount = borrow_amount * 105 / 100
-    let repay_amount = borrow_amount
-      .checked_mul(105)
-      .and_then(|v| v.checked_div(100))
+    let pool = &mut ctx.accounts.pool;
+    pool.borrowed = pool
+      .borrowed
+      .checked_sub(borrow_amount)
       .ok_or(ProtocolError::Overflow)?;
+
+    // Calculate repayment amount with the configured fee
+    // repay_amount = borrow_amount + borrow_amount * fee_bps / 10_000
+    let fee_bps = ctx.accounts.config.fee_bps;
+    let repay_amount = repay_amount(borrow_amount, fee_bps)?;
     
-    msg!("Repay: borrow_amount = {}, repay_amount (with 5% fee) = {}", borrow_amount, repay_amount);
+    msg!("Repay: borrow_amount = {}, repay_amount (fee {} bps) = {}", borrow_amount, fee_bps, repay_amount);
 
     // Check if borrower has enough funds to repay
     require!(
@@ -208,6 +314,363 @@ pub mod blueshift_anchor_flash_loan {
 
     Ok(())
   }
+
+  /// Single-instruction loan: lend `amount` to `receiver_ata`, call the
+  /// receiver program's `on_flash_loan`, then require the pool to be back
+  /// with at least the fee on top
+  pub fn flash_loan<'info>(
+    ctx: Context<'_, '_, '_, 'info, FlashLoan<'info>>,
+    amount: u64,
+    data: Vec<u8>,
+  ) -> Result<()> {
+    msg!("FlashLoan: amount = {}", amount);
+
+    check_loan_amount(&ctx.accounts.config, ctx.accounts.protocol_ata.amount, amount)?;
+
+    // A borrow of this pool whose repay comes later in the transaction is
+    // still open. The receiver can't re-enter this program: the runtime
+    // rejects A -> B -> A reentrancy.
+    require!(ctx.accounts.pool.borrowed == 0, ProtocolError::PoolBusy);
+
+    let fee = repay_amount(amount, ctx.accounts.config.fee_bps)? - amount;
+    let expected = ctx
+      .accounts
+      .protocol_ata
+      .amount
+      .checked_add(fee)
+      .ok_or(ProtocolError::Overflow)?;
+
+    // Persist the open loan before handing control to the receiver, like a
+    // borrow would; nothing can write the pool until the CPI returns
+    ctx.accounts.pool.borrowed = amount;
+    ctx.accounts.pool.exit(&crate::ID)?;
+
+    let seeds = &[b"protocol".as_ref(), &[ctx.bumps.protocol]];
+    transfer(
+      CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+          from: ctx.accounts.protocol_ata.to_account_info(),
+          to: ctx.accounts.receiver_ata.to_account_info(),
+          authority: ctx.accounts.protocol.to_account_info(),
+        },
+        &[&seeds[..]],
+      ),
+      amount,
+    )?;
+
+    ctx.accounts.call_receiver(ctx.remaining_accounts, OnFlashLoan { amount, fee, data })?;
+
+    ctx.accounts.protocol_ata.reload()?;
+    msg!(
+      "FlashLoan: pool balance {}, expected at least {}",
+      ctx.accounts.protocol_ata.amount,
+      expected
+    );
+    require!(
+      ctx.accounts.protocol_ata.amount >= expected,
+      ProtocolError::FlashLoanNotRepaid
+    );
+
+    let pool = &mut ctx.accounts.pool;
+    pool.borrowed = pool
+      .borrowed
+      .checked_sub(amount)
+      .ok_or(ProtocolError::Overflow)?;
+    require!(pool.borrowed == 0, ProtocolError::PoolBusy);
+    Ok(())
+  }
+}
+
+/// Index of the executing instruction, which must be a top-level instruction
+/// of this program: under CPI the sysvar points at the caller's instruction
+fn current_loan_index(instructions: &AccountInfo) -> Result<usize> {
+  let current_ix_index = load_current_index_checked(instructions)? as usize;
+  let current_ix = load_instruction_at_checked(current_ix_index, instructions)?;
+  require_keys_eq!(current_ix.program_id, crate::ID, ProtocolError::InvalidProgram);
+  Ok(current_ix_index)
+}
+
+impl Loan<'_> {
+  /// Whether `ix` is a borrow or repay of this program on the same pool
+  fn is_pool_loan_ix(&self, ix: &Instruction) -> bool {
+    ix.program_id == crate::ID
+      && (ix.data.starts_with(instruction::Borrow::DISCRIMINATOR)
+        || ix.data.starts_with(instruction::Repay::DISCRIMINATOR))
+      && ix
+        .accounts
+        .get(PROTOCOL_ATA_INDEX)
+        .is_some_and(|meta| meta.pubkey == self.protocol_ata.key())
+  }
+
+  /// A borrow and its repay must use the same mint and token accounts
+  fn check_paired(&self, ix: &Instruction) -> Result<()> {
+    let key_at = |index: usize| ix.accounts.get(index).map(|meta| meta.pubkey);
+    require!(
+      key_at(MINT_INDEX) == Some(self.mint.key())
+        && key_at(BORROWER_ATA_INDEX) == Some(self.borrower_ata.key())
+        && key_at(PROTOCOL_ATA_INDEX) == Some(self.protocol_ata.key()),
+      ProtocolError::LoanMismatch
+    );
+    Ok(())
+  }
+}
+
+impl<'info> FlashLoan<'info> {
+  /// CPI the receiver's `on_flash_loan`; see [`OnFlashLoan`] for the layout
+  fn call_receiver(
+    &self,
+    remaining_accounts: &[AccountInfo<'info>],
+    args: OnFlashLoan,
+  ) -> Result<()> {
+    let mut data = ON_FLASH_LOAN_DISCRIMINATOR.to_vec();
+    args.serialize(&mut data)?;
+
+    let mut accounts = vec![
+      AccountMeta::new(self.borrower.key(), true),
+      AccountMeta::new(self.receiver_ata.key(), false),
+      AccountMeta::new(self.protocol_ata.key(), false),
+      AccountMeta::new_readonly(self.mint.key(), false),
+      AccountMeta::new_readonly(self.token_program.key(), false),
+    ];
+    let mut infos = vec![
+      self.borrower.to_account_info(),
+      self.receiver_ata.to_account_info(),
+      self.protocol_ata.to_account_info(),
+      self.mint.to_account_info(),
+      self.token_program.to_account_info(),
+    ];
+    for account in remaining_accounts {
+      accounts.push(AccountMeta {
+        pubkey: account.key(),
+        is_signer: account.is_signer,
+        is_writable: account.is_writable,
+      });
+      infos.push(account.clone());
+    }
+    infos.push(self.receiver_program.to_account_info());
+
+    let ix = Instruction {
+      program_id: self.receiver_program.key(),
+      accounts,
+      data,
+    };
+    invoke(&ix, &infos)?;
+    Ok(())
+  }
+}
+
+impl Liquidity<'_> {
+  /// Shares are priced from the protocol ATA balance, which is short while a
+  /// loan of this pool is open: no deposits or withdrawals until it's repaid
+  fn check_idle(&self) -> Result<()> {
+    require!(self.pool.borrowed == 0, ProtocolError::PoolBusy);
+    Ok(())
+  }
+}
+
+/// Shares minted for depositing `amount` into a pool holding `assets` with
+/// `supply` shares outstanding, priced with the virtual offsets so an empty
+/// pool starts at 1 share = 1 token. Rounds down, in the pool's favor.
+pub fn shares_for_deposit(amount: u64, assets: u64, supply: u64) -> Result<u64> {
+  mul_div(
+    amount,
+    supply as u128 + VIRTUAL_SHARES as u128,
+    assets as u128 + VIRTUAL_ASSETS as u128,
+  )
+}
+
+/// Tokens paid out for burning `shares`; rounds down, in the pool's favor
+pub fn amount_for_shares(shares: u64, assets: u64, supply: u64) -> Result<u64> {
+  require!(shares <= supply, ProtocolError::InvalidAmount);
+  mul_div(
+    shares,
+    assets as u128 + VIRTUAL_ASSETS as u128,
+    supply as u128 + VIRTUAL_SHARES as u128,
+  )
+}
+
+fn mul_div(value: u64, numerator: u128, denominator: u128) -> Result<u64> {
+  let result = value as u128 * numerator / denominator;
+  u64::try_from(result).map_err(|_| error!(ProtocolError::Overflow))
+}
+
+fn validate_config(fee_bps: u16, max_borrow_bps: u16) -> Result<()> {
+  require!(fee_bps as u64 <= BPS_DENOMINATOR, ProtocolError::InvalidFee);
+  require!(
+    max_borrow_bps > 0 && max_borrow_bps as u64 <= BPS_DENOMINATOR,
+    ProtocolError::InvalidBorrowFraction
+  );
+  Ok(())
+}
+
+/// Protocol not paused, and `amount` non-zero and within both the pool's
+/// `available` balance and `max_borrow_bps` of it
+fn check_loan_amount(config: &ProtocolConfig, available: u64, amount: u64) -> Result<()> {
+  require!(!config.paused, ProtocolError::ProtocolPaused);
+
+  // Validate borrow amount
+  require!(amount > 0, ProtocolError::InvalidAmount);
+
+  // Check if protocol has enough funds
+  require!(available >= amount, ProtocolError::NotEnoughFunds);
+
+  // At most `max_borrow_bps` of the pool per loan
+  let max_borrow = (available as u128)
+    .checked_mul(config.max_borrow_bps as u128)
+    .map(|max| max / BPS_DENOMINATOR as u128)
+    .ok_or(ProtocolError::Overflow)?;
+  require!(amount as u128 <= max_borrow, ProtocolError::ExceedsMaxBorrow);
+  Ok(())
+}
+
+/// Principal plus `fee_bps` of it, rounded down
+pub fn repay_amount(borrow_amount: u64, fee_bps: u16) -> Result<u64> {
+  let fee = borrow_amount
+    .checked_mul(fee_bps as u64)
+    .map(|fee| fee / BPS_DENOMINATOR)
+    .ok_or(ProtocolError::Overflow)?;
+  Ok(borrow_amount.checked_add(fee).ok_or(ProtocolError::Overflow)?)
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct ProtocolConfig {
+  pub admin: Pubkey,
+  /// Loan fee in basis points of the borrowed amount
+  pub fee_bps: u16,
+  /// Largest loan as basis points of the protocol ATA balance
+  pub max_borrow_bps: u16,
+  /// Blocks new borrows
+  pub paused: bool,
+  pub bump: u8,
+}
+
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+  #[account(mut)]
+  pub admin: Signer<'info>,
+  #[account(
+    init,
+    payer = admin,
+    space = 8 + ProtocolConfig::INIT_SPACE,
+    seeds = [b"config".as_ref()],
+    bump,
+  )]
+  pub config: Account<'info, ProtocolConfig>,
+  pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AdminConfig<'info> {
+  pub admin: Signer<'info>,
+  #[account(
+    mut,
+    seeds = [b"config".as_ref()],
+    bump = config.bump,
+    has_one = admin @ ProtocolError::Unauthorized,
+  )]
+  pub config: Account<'info, ProtocolConfig>,
+}
+
+/// Per-mint lending pool; its liquidity is the protocol ATA for `mint`
+#[account]
+#[derive(InitSpace)]
+pub struct Pool {
+  pub mint: Pubkey,
+  /// Share token, minted and burned by the protocol PDA
+  pub share_mint: Pubkey,
+  /// Lent out by a borrow whose repay hasn't run yet
+  pub borrowed: u64,
+  pub bump: u8,
+}
+
+#[derive(Accounts)]
+pub struct CreatePool<'info> {
+  #[account(mut)]
+  pub admin: Signer<'info>,
+  #[account(
+    seeds = [b"config".as_ref()],
+    bump = config.bump,
+    has_one = admin @ ProtocolError::Unauthorized,
+  )]
+  pub config: Account<'info, ProtocolConfig>,
+  #[account(
+    seeds = [b"protocol".as_ref()],
+    bump,
+  )]
+  pub protocol: SystemAccount<'info>,
+  pub mint: Account<'info, Mint>,
+  #[account(
+    init,
+    payer = admin,
+    space = 8 + Pool::INIT_SPACE,
+    seeds = [b"pool".as_ref(), mint.key().as_ref()],
+    bump,
+  )]
+  pub pool: Account<'info, Pool>,
+  #[account(
+    init,
+    payer = admin,
+    seeds = [b"shares".as_ref(), mint.key().as_ref()],
+    bump,
+    mint::decimals = mint.decimals,
+    mint::authority = protocol,
+  )]
+  pub share_mint: Account<'info, Mint>,
+  #[account(
+    init_if_needed,
+    payer = admin,
+    associated_token::mint = mint,
+    associated_token::authority = protocol,
+  )]
+  pub protocol_ata: Account<'info, TokenAccount>,
+  pub token_program: Program<'info, Token>,
+  pub associated_token_program: Program<'info, AssociatedToken>,
+  pub system_program: Program<'info, System>
+}
+
+#[derive(Accounts)]
+pub struct Liquidity<'info> {
+  #[account(mut)]
+  pub lender: Signer<'info>,
+  #[account(
+    seeds = [b"protocol".as_ref()],
+    bump,
+  )]
+  pub protocol: SystemAccount<'info>,
+  pub mint: Account<'info, Mint>,
+  #[account(
+    seeds = [b"pool".as_ref(), mint.key().as_ref()],
+    bump = pool.bump,
+    has_one = mint,
+    has_one = share_mint,
+  )]
+  pub pool: Account<'info, Pool>,
+  #[account(mut)]
+  pub share_mint: Account<'info, Mint>,
+  #[account(
+    mut,
+    associated_token::mint = mint,
+    associated_token::authority = lender,
+  )]
+  pub lender_ata: Account<'info, TokenAccount>,
+  #[account(
+    init_if_needed,
+    payer = lender,
+    associated_token::mint = share_mint,
+    associated_token::authority = lender,
+  )]
+  pub lender_share_ata: Account<'info, TokenAccount>,
+  #[account(
+    mut,
+    associated_token::mint = mint,
+    associated_token::authority = protocol,
+  )]
+  pub protocol_ata: Account<'info, TokenAccount>,
+  pub token_program: Program<'info, Token>,
+  pub associated_token_program: Program<'info, AssociatedToken>,
+  pub system_program: Program<'info, System>
 }
 
 #[derive(Accounts)]
@@ -219,6 +682,11 @@ pub struct Loan<'info> {
     bump,
   )]
   pub protocol: SystemAccount<'info>,
+  #[account(
+    seeds = [b"config".as_ref()],
+    bump = config.bump,
+  )]
+  pub config: Account<'info, ProtocolConfig>,
 
   pub mint: Account<'info, Mint>,
   #[account(
@@ -234,6 +702,13 @@ pub struct Loan<'info> {
     associated_token::authority = protocol,
   )]
   pub protocol_ata: Account<'info, TokenAccount>,
+  #[account(
+    mut,
+    seeds = [b"pool".as_ref(), mint.key().as_ref()],
+    bump = pool.bump,
+    has_one = mint,
+  )]
+  pub pool: Account<'info, Pool>,
 
   #[account(address = INSTRUCTIONS_SYSVAR_ID)]
   /// CHECK: InstructionsSysvar account
@@ -243,6 +718,50 @@ pub struct Loan<'info> {
   pub system_program: Program<'info, System>
 }
 
+#[derive(Accounts)]
+pub struct FlashLoan<'info> {
+  #[account(mut)]
+  pub borrower: Signer<'info>,
+  #[account(
+    seeds = [b"protocol".as_ref()],
+    bump,
+  )]
+  pub protocol: SystemAccount<'info>,
+  #[account(
+    seeds = [b"config".as_ref()],
+    bump = config.bump,
+  )]
+  pub config: Account<'info, ProtocolConfig>,
+  pub mint: Account<'info, Mint>,
+  #[account(
+    mut,
+    seeds = [b"pool".as_ref(), mint.key().as_ref()],
+    bump = pool.bump,
+    has_one = mint,
+  )]
+  pub pool: Account<'info, Pool>,
+  /// Receives the loan; any token account of `mint` the receiver can spend from
+  #[account(
+    mut,
+    token::mint = mint,
+  )]
+  pub receiver_ata: Account<'info, TokenAccount>,
+  #[account(
+    mut,
+    associated_token::mint = mint,
+    associated_token::authority = protocol,
+  )]
+  pub protocol_ata: Account<'info, TokenAccount>,
+  /// CHECK: any program but this one; it only gets the accounts listed in
+  /// `OnFlashLoan` and the remaining accounts
+  #[account(
+    executable,
+    constraint = receiver_program.key() != crate::ID @ ProtocolError::InvalidProgram,
+  )]
+  pub receiver_program: UncheckedAccount<'info>,
+  pub token_program: Program<'info, Token>,
+}
+
 #[error_code]
 pub enum ProtocolError {
     #[msg("Invalid instruction")]
@@ -267,4 +786,24 @@ pub enum ProtocolError {
     MissingBorrowIx,
     #[msg("Overflow")]
     Overflow,
+    #[msg("Protocol is paused")]
+    ProtocolPaused,
+    #[msg("Fee must be at most 10000 bps")]
+    InvalidFee,
+    #[msg("Max borrow must be between 1 and 10000 bps")]
+    InvalidBorrowFraction,
+    #[msg("Borrow exceeds the max borrow fraction of the pool")]
+    ExceedsMaxBorrow,
+    #[msg("Signer is not the protocol admin")]
+    Unauthorized,
+    #[msg("Another borrow or repay sits between a borrow and its repay")]
+    NestedLoan,
+    #[msg("Borrow and repay use different mint or token accounts")]
+    LoanMismatch,
+    #[msg("Pool has an open loan")]
+    PoolBusy,
+    #[msg("Flash loan receiver did not repay principal plus fee")]
+    FlashLoanNotRepaid,
+    #[msg("Protocol ATA already holds tokens")]
+    PoolNotEmpty,
 }
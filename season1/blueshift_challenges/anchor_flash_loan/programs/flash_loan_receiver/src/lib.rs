@@ -0,0 +1,54 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount, Mint, Transfer, transfer};
+
+declare_id!("55iixe5x6bwYDPZC549VdTUyZEm7tf4EsTUWZdDrC4Pv");
+
+/// Minimal receiver for `anchor_flash_loan::flash_loan`: does nothing with
+/// the funds and pays the loan back from the borrower's token account
+#[program]
+pub mod flash_loan_receiver {
+  use super::*;
+
+  /// Callback invoked by `flash_loan`. Repays `amount + fee`, or the
+  /// little-endian u64 in `data` when it's given (lets tests under-repay).
+  pub fn on_flash_loan(ctx: Context<OnFlashLoan>, amount: u64, fee: u64, data: Vec<u8>) -> Result<()> {
+    let repay = match data.get(..8) {
+      Some(bytes) => u64::from_le_bytes(bytes.try_into().unwrap()),
+      None => amount.checked_add(fee).ok_or(ProgramError::ArithmeticOverflow)?,
+    };
+    msg!("OnFlashLoan: amount = {}, fee = {}, repaying {}", amount, fee, repay);
+
+    // The borrower signed `flash_loan`, and that signature carries into
+    // this CPI
+    transfer(
+      CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+          from: ctx.accounts.receiver_ata.to_account_info(),
+          to: ctx.accounts.protocol_ata.to_account_info(),
+          authority: ctx.accounts.borrower.to_account_info(),
+        },
+      ),
+      repay,
+    )
+  }
+}
+
+/// Account order fixed by `anchor_flash_loan::OnFlashLoan`
+#[derive(Accounts)]
+pub struct OnFlashLoan<'info> {
+  pub borrower: Signer<'info>,
+  #[account(
+    mut,
+    token::mint = mint,
+    token::authority = borrower,
+  )]
+  pub receiver_ata: Account<'info, TokenAccount>,
+  #[account(
+    mut,
+    token::mint = mint,
+  )]
+  pub protocol_ata: Account<'info, TokenAccount>,
+  pub mint: Account<'info, Mint>,
+  pub token_program: Program<'info, Token>,
+}
@@ -6,15 +6,26 @@ pub enum CurveError {
     Overflow,
     Underflow,
     ZeroBalance,
+    ZeroOutput,
     SlippageExceeded,
 }
 
 const PRECISION: u32 = 1_000_000;
 
-/// Calculate amount of Y to withdraw when depositing X (swap X for Y)
-/// delta_y = y - k/(x + amount_in)
+/// Output and fee of a single swap
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SwapAmounts {
+    /// Amount withdrawn from the opposite reserve
+    pub amount_out: u64,
+    /// Part of `amount_in` kept by the pool: amount_in - amount_in * (10000 - fee_bps) / 10000
+    pub fee: u64,
+}
+
+/// Swap X for Y: amount of Y to withdraw when depositing `amount_in` X, and the fee taken
+/// delta_y = y - k/(x + amount_in - fee)
 /// Amount after fee: amount_in * (10000 - fee_bps) / 10000
-pub fn delta_y_from_x_swap(x: u64, y: u64, amount_in: u64, fee_bps: u16) -> Result<u64, CurveError> {
+/// A swap that would pay out nothing fails with `ZeroOutput`
+pub fn swap_x_for_y(x: u64, y: u64, amount_in: u64, fee_bps: u16) -> Result<SwapAmounts, CurveError> {
     if x == 0 || y == 0 {
         return Err(CurveError::ZeroBalance);
     }
@@ -23,19 +34,66 @@ pub fn delta_y_from_x_swap(x: u64, y: u64, amount_in: u64, fee_bps: u16) -> Resu
         .ok_or(CurveError::Overflow)?
         .checked_div(10_000)
         .ok_or(CurveError::Overflow)? as u64;
+    let fee = amount_in
+        .checked_sub(amount_after_fee)
+        .ok_or(CurveError::Underflow)?;
 
     let k = (x as u128).checked_mul(y as u128).ok_or(CurveError::Overflow)?;
     let x_new = (x as u128)
         .checked_add(amount_after_fee as u128)
         .ok_or(CurveError::Overflow)?;
     let y_new = k.checked_div(x_new).ok_or(CurveError::Overflow)? as u64;
-    let delta_y = y.checked_sub(y_new).ok_or(CurveError::Underflow)?;
-    Ok(delta_y)
+    let amount_out = y.checked_sub(y_new).ok_or(CurveError::Underflow)?;
+    if amount_out == 0 {
+        return Err(CurveError::ZeroOutput);
+    }
+    Ok(SwapAmounts { amount_out, fee })
+}
+
+/// Swap Y for X: amount of X to withdraw when depositing `amount_in` Y, and the fee taken
+pub fn swap_y_for_x(x: u64, y: u64, amount_in: u64, fee_bps: u16) -> Result<SwapAmounts, CurveError> {
+    swap_x_for_y(y, x, amount_in, fee_bps)
+}
+
+/// Calculate amount of Y to withdraw when depositing X (swap X for Y)
+pub fn delta_y_from_x_swap(x: u64, y: u64, amount_in: u64, fee_bps: u16) -> Result<u64, CurveError> {
+    swap_x_for_y(x, y, amount_in, fee_bps).map(|swap| swap.amount_out)
 }
 
 /// Calculate amount of X to withdraw when depositing Y (swap Y for X)
 pub fn delta_x_from_y_swap(x: u64, y: u64, amount_in: u64, fee_bps: u16) -> Result<u64, CurveError> {
-    delta_y_from_x_swap(y, x, amount_in, fee_bps)
+    swap_y_for_x(x, y, amount_in, fee_bps).map(|swap| swap.amount_out)
+}
+
+/// Price impact of a swap in basis points, relative to the spot price before the trade.
+/// `fee` and `amount_out` are the [`SwapAmounts`] of the same swap.
+/// spot_out = (amount_in - fee) * y / x
+/// impact = (spot_out - amount_out) * 10000 / spot_out
+pub fn price_impact_bps(
+    x: u64,
+    y: u64,
+    amount_in: u64,
+    fee: u64,
+    amount_out: u64,
+) -> Result<u16, CurveError> {
+    if x == 0 || y == 0 {
+        return Err(CurveError::ZeroBalance);
+    }
+    let spot_out = (amount_in.checked_sub(fee).ok_or(CurveError::Underflow)? as u128)
+        .checked_mul(y as u128)
+        .ok_or(CurveError::Overflow)?
+        .checked_div(x as u128)
+        .ok_or(CurveError::Overflow)?;
+    if spot_out == 0 {
+        return Ok(0);
+    }
+    let impact = spot_out
+        .saturating_sub(amount_out as u128)
+        .checked_mul(10_000)
+        .ok_or(CurveError::Overflow)?
+        .checked_div(spot_out)
+        .ok_or(CurveError::Overflow)?;
+    Ok(impact as u16)
 }
 
 /// Calculate amounts of X and Y to deposit for minting `lp_amount` LP tokens
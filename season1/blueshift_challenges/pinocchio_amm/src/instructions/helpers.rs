@@ -1,15 +1,9 @@
-use pinocchio::{AccountView, Address};
-use pinocchio_associated_token_account::ID as ATA_ID;
-use pinocchio_token::ID as TOKEN_ID;
-
-/// Get the associated token address for a wallet and mint
-pub fn get_associated_token_address(wallet: &Address, mint: &Address) -> Address {
-    let (address, _) = Address::find_program_address(
-        &[wallet.as_ref(), TOKEN_ID.as_ref(), mint.as_ref()],
-        &ATA_ID,
-    );
-    address
-}
+use core::ops::Deref;
+
+use pinocchio::{error::ProgramError, AccountView, Address};
+
+use crate::state::{AmmState, Config};
+use crate::ID;
 
 /// SPL Token 
 //  layout: amount at offset 64
@@ -28,9 +22,8 @@ pub fn get_token_account_amount(
     Ok(u64::from_le_bytes(bytes))
 }
 
-/// SPL Mint layout: supply at offset 36, decimals at offset 44
+/// SPL Mint layout: supply at offset 36
 const MINT_SUPPLY_OFFSET: usize = 36;
-const MINT_DECIMALS_OFFSET: usize = 44;
 
 /// Read mint supply from AccountView (SPL Token layout)
 pub fn get_mint_supply(account: &AccountView) -> Result<u64, pinocchio::error::ProgramError> {
@@ -43,11 +36,44 @@ pub fn get_mint_supply(account: &AccountView) -> Result<u64, pinocchio::error::P
     Ok(u64::from_le_bytes(bytes))
 }
 
-/// Read mint decimals from AccountView (SPL Token layout)
-pub fn get_mint_decimals(account: &AccountView) -> Result<u8, pinocchio::error::ProgramError> {
-    let data = account.try_borrow()?;
-    if data.len() < MINT_DECIMALS_OFFSET + 1 {
-        return Err(pinocchio::error::ProgramError::InvalidAccountData);
+/// Check that `config` is an initialized pool owned by this program and that `vault_x` /
+/// `vault_y` are its associated token accounts for `mint_x` / `mint_y`
+pub fn check_pool(
+    config: &AccountView,
+    vault_x: &AccountView,
+    vault_y: &AccountView,
+) -> Result<(), ProgramError> {
+    if !config.owned_by(&ID) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+    let config_data = config.try_borrow()?;
+    let state = Config::load(config_data.deref())?;
+    if state.state() != AmmState::Initialized as u8 {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    check_vault(vault_x, config.address(), state.mint_x())?;
+    check_vault(vault_y, config.address(), state.mint_y())
+}
+
+/// Check that `vault` is the `authority`'s associated token account for `mint`
+fn check_vault(
+    vault: &AccountView,
+    authority: &Address,
+    mint: &Address,
+) -> Result<(), ProgramError> {
+    if !vault.owned_by(&pinocchio_token::ID) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+    let (ata, _) = Address::find_program_address(
+        &[
+            authority.as_ref(),
+            pinocchio_token::ID.as_ref(),
+            mint.as_ref(),
+        ],
+        &pinocchio_associated_token_account::ID,
+    );
+    if vault.address() != &ata {
+        return Err(ProgramError::InvalidAccountData);
     }
-    Ok(data[MINT_DECIMALS_OFFSET])
+    Ok(())
 }
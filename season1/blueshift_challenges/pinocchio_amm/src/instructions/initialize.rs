@@ -1,5 +1,3 @@
-use core::mem::size_of;
-
 use pinocchio::{
     cpi::{Seed, Signer},
     error::ProgramError,
@@ -142,12 +140,12 @@ impl<'a> Initialize<'a> {
         // 2. Initialize config state
         {
             let mut config_data = self.accounts.config.try_borrow_mut()?;
-            let config = Config::load_mut(&mut *config_data)?;
+            let config = Config::load_mut(&mut config_data)?;
             config.set_inner(
                 self.instruction_data.seed,
-                self.instruction_data.authority.clone(),
-                self.instruction_data.mint_x.clone(),
-                self.instruction_data.mint_y.clone(),
+                self.instruction_data.authority,
+                self.instruction_data.mint_x,
+                self.instruction_data.mint_y,
                 self.instruction_data.fee,
                 self.instruction_data.config_bump,
             )?;
@@ -1,10 +1,12 @@
 mod deposit;
 mod helpers;
 mod initialize;
+mod quote;
 mod swap;
 mod withdraw;
 
 pub use deposit::*;
 pub use initialize::*;
+pub use quote::*;
 pub use swap::*;
 pub use withdraw::*;
@@ -0,0 +1,115 @@
+use core::ops::Deref;
+
+use pinocchio::{cpi::set_return_data, error::ProgramError, AccountView, ProgramResult};
+
+use crate::curve;
+use crate::instructions::helpers::{check_pool, get_token_account_amount};
+use crate::instructions::swap::{map_curve_error, swap_amounts};
+use crate::state::Config;
+
+/// 只读报价: 3 个账户 - vault_x, vault_y, config（不需要签名，不发生转账）
+pub struct QuoteAccounts<'a> {
+    pub vault_x: &'a AccountView,
+    pub vault_y: &'a AccountView,
+    pub config: &'a AccountView,
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for QuoteAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let [vault_x, vault_y, config] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        Ok(Self {
+            vault_x,
+            vault_y,
+            config,
+        })
+    }
+}
+
+/// Instruction data: is_x(1) + amount(8) = 9 bytes
+pub struct QuoteInstructionData {
+    pub is_x: bool,
+    pub amount: u64,
+}
+
+impl<'a> TryFrom<&'a [u8]> for QuoteInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        if data.len() < 9 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let is_x = data[0] != 0;
+        let amount = u64::from_le_bytes(data[1..9].try_into().unwrap());
+
+        if amount == 0 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Ok(Self { is_x, amount })
+    }
+}
+
+/// Return data: amount_out(8) + fee(8) + price_impact_bps(2) = 18 bytes
+pub const QUOTE_RETURN_DATA_LEN: usize = 18;
+
+pub struct Quote<'a> {
+    pub accounts: QuoteAccounts<'a>,
+    pub instruction_data: QuoteInstructionData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountView])> for Quote<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountView])) -> Result<Self, Self::Error> {
+        let accounts = QuoteAccounts::try_from(accounts)?;
+        let instruction_data = QuoteInstructionData::try_from(data)?;
+
+        // 与 Swap 相同的 config / vault 检查，CPI 调用方拿到的报价来自真实的池子
+        check_pool(accounts.config, accounts.vault_x, accounts.vault_y)?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> Quote<'a> {
+    pub const DISCRIMINATOR: u8 = 4;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let config_data = self.accounts.config.try_borrow()?;
+        let config = Config::load(config_data.deref())?;
+
+        let x = get_token_account_amount(self.accounts.vault_x)?;
+        let y = get_token_account_amount(self.accounts.vault_y)?;
+        let fee_bps = config.fee();
+        let amount = self.instruction_data.amount;
+
+        // 与 Swap 共用同一次计算：输出与手续费一致，输出为 0 时错误码也一致
+        let swap = swap_amounts(x, y, self.instruction_data.is_x, amount, fee_bps)?;
+
+        let (reserve_in, reserve_out) = if self.instruction_data.is_x {
+            (x, y)
+        } else {
+            (y, x)
+        };
+        let price_impact_bps =
+            curve::price_impact_bps(reserve_in, reserve_out, amount, swap.fee, swap.amount_out)
+                .map_err(map_curve_error)?;
+
+        let mut return_data = [0u8; QUOTE_RETURN_DATA_LEN];
+        return_data[0..8].copy_from_slice(&swap.amount_out.to_le_bytes());
+        return_data[8..16].copy_from_slice(&swap.fee.to_le_bytes());
+        return_data[16..18].copy_from_slice(&price_impact_bps.to_le_bytes());
+        set_return_data(&return_data);
+
+        Ok(())
+    }
+}
@@ -7,10 +7,9 @@ use pinocchio::{
 };
 use pinocchio_token::instructions::Transfer;
 
-use crate::curve::{self, CurveError};
-use crate::instructions::helpers::get_token_account_amount;
-use crate::state::{AmmState, Config};
-use crate::ID;
+use crate::curve::{self, CurveError, SwapAmounts};
+use crate::instructions::helpers::{check_pool, get_token_account_amount};
+use crate::state::Config;
 
 const CONFIG_SEED: &[u8] = b"config";
 
@@ -96,12 +95,8 @@ impl<'a> TryFrom<(&'a [u8], &'a [AccountView])> for Swap<'a> {
         let accounts = SwapAccounts::try_from(accounts)?;
         let instruction_data = SwapInstructionData::try_from(data)?;
 
-        let config_data = accounts.config.try_borrow()?;
-        let config = Config::load(config_data.deref())?;
-
-        if config.state() != AmmState::Initialized as u8 {
-            return Err(ProgramError::InvalidAccountData);
-        }
+        // Vault 必须是 config 的 ATA：Quote 走同一组检查，报价与成交读取同一对余额
+        check_pool(accounts.config, accounts.vault_x, accounts.vault_y)?;
 
         Ok(Self {
             accounts,
@@ -110,15 +105,33 @@ impl<'a> TryFrom<(&'a [u8], &'a [AccountView])> for Swap<'a> {
     }
 }
 
-fn map_curve_error(e: CurveError) -> ProgramError {
+pub(crate) fn map_curve_error(e: CurveError) -> ProgramError {
     match e {
         CurveError::Overflow => ProgramError::InvalidInstructionData,
         CurveError::Underflow => ProgramError::InvalidInstructionData,
         CurveError::ZeroBalance => ProgramError::InvalidAccountData,
+        CurveError::ZeroOutput => ProgramError::InvalidArgument,
         CurveError::SlippageExceeded => ProgramError::Custom(1),
     }
 }
 
+/// Amount withdrawn from the opposite vault, and the fee kept, for `amount` deposited on
+/// the `is_x` side. Shared by `Swap` and `Quote` so simulated quotes always match execution.
+pub(crate) fn swap_amounts(
+    x: u64,
+    y: u64,
+    is_x: bool,
+    amount: u64,
+    fee: u16,
+) -> Result<SwapAmounts, ProgramError> {
+    if is_x {
+        curve::swap_x_for_y(x, y, amount, fee)
+    } else {
+        curve::swap_y_for_x(x, y, amount, fee)
+    }
+    .map_err(map_curve_error)
+}
+
 impl<'a> Swap<'a> {
     pub const DISCRIMINATOR: u8 = 3;
 
@@ -130,34 +143,15 @@ impl<'a> Swap<'a> {
         let y = get_token_account_amount(self.accounts.vault_y)?;
         let fee = config.fee();
 
-        let (deposit_amount, withdraw_amount) = if self.instruction_data.is_x {
-            let withdraw = curve::delta_y_from_x_swap(
-                x,
-                y,
-                self.instruction_data.amount,
-                fee,
-            )
-            .map_err(map_curve_error)?;
-            (self.instruction_data.amount, withdraw)
-        } else {
-            let withdraw = curve::delta_x_from_y_swap(
-                x,
-                y,
-                self.instruction_data.amount,
-                fee,
-            )
-            .map_err(map_curve_error)?;
-            (self.instruction_data.amount, withdraw)
-        };
+        let deposit_amount = self.instruction_data.amount;
+        // 输出为 0 时 swap_amounts 返回 InvalidArgument
+        let withdraw_amount =
+            swap_amounts(x, y, self.instruction_data.is_x, deposit_amount, fee)?.amount_out;
 
         if withdraw_amount < self.instruction_data.min {
             return Err(ProgramError::Custom(1));
         }
 
-        if deposit_amount == 0 || withdraw_amount == 0 {
-            return Err(ProgramError::InvalidArgument);
-        }
-
         let seed_bytes = config.seed().to_le_bytes();
         let config_bump = config.config_bump();
         let config_seeds = [
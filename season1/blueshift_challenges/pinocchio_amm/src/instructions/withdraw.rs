@@ -10,7 +10,6 @@ use pinocchio_token::instructions::{Burn, Transfer};
 use crate::curve::{self, CurveError};
 use crate::instructions::helpers::{get_mint_supply, get_token_account_amount};
 use crate::state::{AmmState, Config};
-use crate::ID;
 
 const CONFIG_SEED: &[u8] = b"config";
 
@@ -129,6 +128,7 @@ fn map_curve_error(e: CurveError) -> ProgramError {
         CurveError::Overflow => ProgramError::InvalidInstructionData,
         CurveError::Underflow => ProgramError::InvalidInstructionData,
         CurveError::ZeroBalance => ProgramError::InvalidAccountData,
+        CurveError::ZeroOutput => ProgramError::InvalidArgument,
         CurveError::SlippageExceeded => ProgramError::Custom(1),
     }
 }
@@ -42,6 +42,7 @@ fn process_instruction(
             Withdraw::try_from((data, accounts))?.process()
         }
         Some((&Swap::DISCRIMINATOR, data)) => Swap::try_from((data, accounts))?.process(),
+        Some((&Quote::DISCRIMINATOR, data)) => Quote::try_from((data, accounts))?.process(),
         _ => Err(ProgramError::InvalidInstructionData),
     }
 }
@@ -40,11 +40,17 @@ impl Config {
         Ok(unsafe { Self::from_bytes_unchecked_mut(bytes) })
     }
 
+    /// # Safety
+    ///
+    /// `bytes` must be at least `Config::LEN` long.
     #[inline(always)]
     pub unsafe fn from_bytes_unchecked(bytes: &[u8]) -> &Self {
         &*(bytes.as_ptr() as *const Config)
     }
 
+    /// # Safety
+    ///
+    /// `bytes` must be at least `Config::LEN` long.
     #[inline(always)]
     pub unsafe fn from_bytes_unchecked_mut(bytes: &mut [u8]) -> &mut Self {
         &mut *(bytes.as_mut_ptr() as *mut Config)
@@ -153,7 +159,7 @@ impl Config {
         let bytes = self.authority.as_ref();
         let chunks: &[u64; 4] = unsafe { &*(bytes.as_ptr() as *const [u64; 4]) };
         if chunks.iter().any(|&x| x != 0) {
-            Some(self.authority.clone())
+            Some(self.authority)
         } else {
             None
         }
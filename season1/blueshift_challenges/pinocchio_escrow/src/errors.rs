@@ -6,6 +6,18 @@ pub enum EscrowError {
     InvalidMaker = 6001,
     InvalidMintA = 6002,
     InvalidMintB = 6003,
+    InvalidExpiry = 6004,
+    EscrowExpired = 6005,
+    EscrowNotExpired = 6006,
+    UnsupportedMint = 6007,
+    UnauthorizedTaker = 6008,
+    DuplicateMint = 6009,
+    SequenceMismatch = 6010,
+    InvalidAuction = 6011,
+    PriceExceeded = 6012,
+    BookPageFull = 6013,
+    NotInBook = 6014,
+    InvalidRentPayer = 6015,
 }
 
 impl From<EscrowError> for ProgramError {
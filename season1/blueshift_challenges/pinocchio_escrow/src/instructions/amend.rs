@@ -0,0 +1,210 @@
+use core::ops::Deref;
+
+use pinocchio::{
+    cpi::{Seed, Signer},
+    error::ProgramError,
+    AccountView, ProgramResult,
+};
+
+use crate::errors::EscrowError;
+use crate::instructions::helpers::{
+    check_program_pda, check_vault, get_mint_decimals, get_token_account_amount,
+    get_token_account_owner,
+};
+use crate::instructions::token_interface::{
+    check_token_program, transfer_from_user, TransferChecked,
+};
+use crate::state::Escrow;
+
+const ESCROW_SEED: &[u8] = b"escrow";
+
+pub struct AmendAccounts<'a> {
+    pub maker: &'a AccountView,
+    pub escrow: &'a AccountView,
+    pub mint_a: &'a AccountView,
+    pub maker_ata_a: &'a AccountView,
+    pub vault: &'a AccountView,
+    pub token_program: &'a AccountView,
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for AmendAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let [maker, escrow, mint_a, maker_ata_a, vault, token_program] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        if !maker.is_signer() {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        let escrow_data = escrow.try_borrow()?;
+        let escrow_state = Escrow::load(escrow_data.deref())?;
+
+        if escrow_state.maker != *maker.address() {
+            return Err(EscrowError::InvalidMaker.into());
+        }
+        if escrow_state.mint_a != *mint_a.address() {
+            return Err(EscrowError::InvalidMintA.into());
+        }
+
+        // The escrow must be this program's PDA for (maker, seed), and the vault its ATA
+        check_program_pda(
+            escrow,
+            &[
+                ESCROW_SEED,
+                maker.address().as_ref(),
+                &escrow_state.seed.to_le_bytes(),
+                &escrow_state.bump,
+            ],
+        )?;
+        check_vault(vault, escrow, mint_a, token_program)?;
+        check_token_program(mint_a, token_program)?;
+        if get_token_account_owner(maker_ata_a)? != *maker.address() {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+
+        Ok(Self {
+            maker,
+            escrow,
+            mint_a,
+            maker_ata_a,
+            vault,
+            token_program,
+        })
+    }
+}
+
+pub struct AmendInstructionData {
+    /// New token B price for the whole remaining offer (the start price of an
+    /// auction); 0 keeps the current one
+    pub receive: u64,
+    /// Token A to add to the vault
+    pub deposit: u64,
+    /// Token A to take back from the vault
+    pub withdraw: u64,
+}
+
+impl<'a> TryFrom<&'a [u8]> for AmendInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        // receive (8) + deposit (8) + withdraw (8) = 24 bytes
+        if data.len() < 24 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let receive = u64::from_le_bytes(data[0..8].try_into().unwrap());
+        let deposit = u64::from_le_bytes(data[8..16].try_into().unwrap());
+        let withdraw = u64::from_le_bytes(data[16..24].try_into().unwrap());
+
+        // Either top up or withdraw, and change something
+        if (deposit != 0 && withdraw != 0) || (receive == 0 && deposit == 0 && withdraw == 0) {
+            return Err(EscrowError::InvalidAmount.into());
+        }
+
+        Ok(Self {
+            receive,
+            deposit,
+            withdraw,
+        })
+    }
+}
+
+pub struct Amend<'a> {
+    pub accounts: AmendAccounts<'a>,
+    pub instruction_data: AmendInstructionData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountView])> for Amend<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountView])) -> Result<Self, Self::Error> {
+        let accounts = AmendAccounts::try_from(accounts)?;
+        let instruction_data = AmendInstructionData::try_from(data)?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> Amend<'a> {
+    pub const DISCRIMINATOR: u8 = 7;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let (seed, bump) = {
+            let escrow_data = self.accounts.escrow.try_borrow()?;
+            let escrow = Escrow::load(escrow_data.deref())?;
+            (escrow.seed, escrow.bump[0])
+        };
+
+        let seed_bytes = seed.to_le_bytes();
+        let bump_array = [bump];
+        let seeds = [
+            Seed::from(ESCROW_SEED),
+            Seed::from(self.accounts.maker.address().as_ref()),
+            Seed::from(&seed_bytes[..]),
+            Seed::from(&bump_array[..]),
+        ];
+        let signers = [Signer::from(&seeds)];
+
+        let decimals = get_mint_decimals(self.accounts.mint_a)?;
+
+        // 1. Top up the vault (escrow PDA as delegate under CPI guard)
+        if self.instruction_data.deposit != 0 {
+            transfer_from_user(
+                TransferChecked {
+                    token_program: self.accounts.token_program,
+                    from: self.accounts.maker_ata_a,
+                    mint: self.accounts.mint_a,
+                    to: self.accounts.vault,
+                    authority: self.accounts.maker,
+                    amount: self.instruction_data.deposit,
+                    decimals,
+                },
+                self.accounts.escrow,
+                &signers,
+            )?;
+        }
+
+        // 2. Withdraw part of the vault; use Refund to take everything back
+        if self.instruction_data.withdraw != 0 {
+            if self.instruction_data.withdraw >= get_token_account_amount(self.accounts.vault)? {
+                return Err(EscrowError::InvalidAmount.into());
+            }
+            TransferChecked {
+                token_program: self.accounts.token_program,
+                from: self.accounts.vault,
+                mint: self.accounts.mint_a,
+                to: self.accounts.maker_ata_a,
+                authority: self.accounts.escrow,
+                amount: self.instruction_data.withdraw,
+                decimals,
+            }
+            .invoke_signed(&signers)?;
+        }
+
+        // 3. Update the offer and bump the sequence so pinned takes fail
+        let remaining = get_token_account_amount(self.accounts.vault)?;
+        let mut escrow_data = self.accounts.escrow.try_borrow_mut()?;
+        let escrow = Escrow::load_mut(&mut escrow_data)?;
+        if self.instruction_data.receive != 0 {
+            // A new auction start price must stay above the floor
+            if escrow.is_auction() && self.instruction_data.receive <= escrow.end_receive {
+                return Err(EscrowError::InvalidAuction.into());
+            }
+            escrow.set_receive(self.instruction_data.receive);
+        }
+        escrow.set_remaining(remaining);
+        escrow.set_sequence(
+            escrow
+                .sequence
+                .checked_add(1)
+                .ok_or(ProgramError::ArithmeticOverflow)?,
+        );
+
+        Ok(())
+    }
+}
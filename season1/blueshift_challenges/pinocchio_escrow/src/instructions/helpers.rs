@@ -1,14 +1,21 @@
-use pinocchio::{AccountView, Address};
-use pinocchio_associated_token_account::ID as ATA_ID;
-use pinocchio_token::ID as TOKEN_ID;
-
-/// Get the associated token address for a wallet and mint
-pub fn get_associated_token_address(wallet: &Address, mint: &Address) -> Address {
-    let (address, _) = Address::find_program_address(
-        &[wallet.as_ref(), TOKEN_ID.as_ref(), mint.as_ref()],
-        &ATA_ID,
-    );
-    address
+use pinocchio::{error::ProgramError, AccountView, Address};
+
+use crate::ID;
+
+/// SPL Token account layout: owner at offset 32
+const TOKEN_ACCOUNT_OWNER_OFFSET: usize = 32;
+
+/// Read token account owner from AccountView (SPL Token layout, shared by Token-2022)
+pub fn get_token_account_owner(
+    account: &AccountView,
+) -> Result<Address, ProgramError> {
+    let data = account.try_borrow()?;
+    if data.len() < TOKEN_ACCOUNT_OWNER_OFFSET + 32 {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    let mut bytes = [0u8; 32];
+    bytes.copy_from_slice(&data[TOKEN_ACCOUNT_OWNER_OFFSET..TOKEN_ACCOUNT_OWNER_OFFSET + 32]);
+    Ok(Address::new_from_array(bytes))
 }
 
 /// SPL Token account layout: amount at offset 64
@@ -17,10 +24,10 @@ const TOKEN_ACCOUNT_AMOUNT_OFFSET: usize = 64;
 /// Read token account amount from AccountView (SPL Token layout)
 pub fn get_token_account_amount(
     account: &AccountView,
-) -> Result<u64, pinocchio::error::ProgramError> {
+) -> Result<u64, ProgramError> {
     let data = account.try_borrow()?;
     if data.len() < TOKEN_ACCOUNT_AMOUNT_OFFSET + 8 {
-        return Err(pinocchio::error::ProgramError::InvalidAccountData);
+        return Err(ProgramError::InvalidAccountData);
     }
     let mut bytes = [0u8; 8];
     bytes.copy_from_slice(&data[TOKEN_ACCOUNT_AMOUNT_OFFSET..TOKEN_ACCOUNT_AMOUNT_OFFSET + 8]);
@@ -31,10 +38,70 @@ pub fn get_token_account_amount(
 const MINT_DECIMALS_OFFSET: usize = 44;
 
 /// Read mint decimals from AccountView (SPL Token layout)
-pub fn get_mint_decimals(account: &AccountView) -> Result<u8, pinocchio::error::ProgramError> {
+pub fn get_mint_decimals(account: &AccountView) -> Result<u8, ProgramError> {
     let data = account.try_borrow()?;
     if data.len() < MINT_DECIMALS_OFFSET + 1 {
-        return Err(pinocchio::error::ProgramError::InvalidAccountData);
+        return Err(ProgramError::InvalidAccountData);
     }
     Ok(data[MINT_DECIMALS_OFFSET])
 }
+
+/// Check that `account` is the program-owned PDA for `seeds` (bump included)
+pub fn check_program_pda(account: &AccountView, seeds: &[&[u8]]) -> Result<(), ProgramError> {
+    if !account.owned_by(&ID) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+    let pda = Address::create_program_address(seeds, &ID).map_err(|_| ProgramError::InvalidSeeds)?;
+    if account.address() != &pda {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    Ok(())
+}
+
+/// Check that `vault` is the `authority`'s associated token account for `mint`
+pub fn check_vault(
+    vault: &AccountView,
+    authority: &AccountView,
+    mint: &AccountView,
+    token_program: &AccountView,
+) -> Result<(), ProgramError> {
+    if !vault.owned_by(token_program.address()) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+    let (ata, _) = Address::find_program_address(
+        &[
+            authority.address().as_ref(),
+            token_program.address().as_ref(),
+            mint.address().as_ref(),
+        ],
+        &pinocchio_associated_token_account::ID,
+    );
+    if vault.address() != &ata {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    Ok(())
+}
+
+/// Check that a program account is the expected program
+pub fn check_program_id(program: &AccountView, id: &Address) -> Result<(), ProgramError> {
+    if program.address() != id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    Ok(())
+}
+
+/// Move all lamports of a program-owned account to `destination` and close it,
+/// so it can't be reused later in the same transaction
+pub fn close_program_account(
+    account: &AccountView,
+    destination: &AccountView,
+) -> Result<(), ProgramError> {
+    destination.set_lamports(
+        destination
+            .lamports()
+            .checked_add(account.lamports())
+            .ok_or(ProgramError::ArithmeticOverflow)?,
+    );
+    account.set_lamports(0);
+    account.close()
+}
@@ -1,15 +1,23 @@
 use pinocchio::{
     cpi::{Seed, Signer},
     error::ProgramError,
+    sysvars::{clock::Clock, Sysvar},
     AccountView, Address, ProgramResult,
 };
 use pinocchio_associated_token_account::instructions::Create;
 use pinocchio_system::create_account_with_minimum_balance_signed;
-use pinocchio_token::instructions::TransferChecked;
 
 use crate::errors::EscrowError;
+use crate::instructions::helpers::{
+    check_program_id, get_mint_decimals, get_token_account_amount, get_token_account_owner,
+};
+use crate::instructions::order_book::append_to_book;
+use crate::instructions::token_interface::{
+    check_mint_extensions, check_token_mint, check_token_program, transfer_from_user,
+    TransferChecked,
+};
 use crate::state::Escrow;
-use crate::{instructions::helpers::get_mint_decimals, ID};
+use crate::ID;
 
 const ESCROW_SEED: &[u8] = b"escrow";
 
@@ -23,6 +31,11 @@ pub struct MakeAccounts<'a> {
     pub associated_token_program: &'a AccountView,
     pub token_program: &'a AccountView,
     pub system_program: &'a AccountView,
+    /// Funds the escrow and vault rent (e.g. a relayer); optional trailing
+    /// signer, defaults to `maker`
+    pub rent_payer: &'a AccountView,
+    /// Order-book page to list the escrow in; trailing account after `rent_payer`
+    pub book_page: Option<&'a AccountView>,
 }
 
 impl<'a> TryFrom<&'a [AccountView]> for MakeAccounts<'a> {
@@ -39,15 +52,30 @@ impl<'a> TryFrom<&'a [AccountView]> for MakeAccounts<'a> {
             associated_token_program,
             token_program,
             system_program,
+            rest @ ..,
         ] = accounts
         else {
             return Err(ProgramError::NotEnoughAccountKeys);
         };
 
-        if !maker.is_signer() {
+        let rent_payer = rest.first().unwrap_or(maker);
+        if !maker.is_signer() || !rent_payer.is_signer() {
             return Err(ProgramError::MissingRequiredSignature);
         }
 
+        check_program_id(associated_token_program, &pinocchio_associated_token_account::ID)?;
+        check_program_id(system_program, &pinocchio_system::ID)?;
+
+        // Token program is picked per mint: SPL Token or Token-2022
+        check_token_program(mint_a, token_program)?;
+        check_token_mint(mint_b)?;
+        check_mint_extensions(mint_a)?;
+        check_mint_extensions(mint_b)?;
+
+        if get_token_account_owner(maker_ata_a)? != *maker.address() {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+
         Ok(Self {
             maker,
             escrow,
@@ -58,6 +86,8 @@ impl<'a> TryFrom<&'a [AccountView]> for MakeAccounts<'a> {
             associated_token_program,
             token_program,
             system_program,
+            rent_payer,
+            book_page: rest.get(1),
         })
     }
 }
@@ -66,6 +96,19 @@ pub struct MakeInstructionData {
     pub seed: u64,
     pub receive: u64,
     pub amount: u64,
+    pub expiry: i64,
+    /// `Escrow::TAKER_ANY`, `TAKER_ADDRESS` or `TAKER_MERKLE`
+    pub taker_mode: u8,
+    /// Designated taker or merkle root, depending on `taker_mode`
+    pub taker: Address,
+    /// `Escrow::AUCTION_NONE`, `AUCTION_LINEAR` or `AUCTION_EXPONENTIAL`
+    pub auction_mode: u8,
+    /// Dutch auctions: `receive` is the start price, `end_receive` the floor
+    pub end_receive: u64,
+    pub auction_start: i64,
+    pub auction_end: i64,
+    /// Order-book page index to list the escrow in
+    pub book_page: Option<u32>,
 }
 
 impl<'a> TryFrom<&'a [u8]> for MakeInstructionData {
@@ -80,15 +123,73 @@ impl<'a> TryFrom<&'a [u8]> for MakeInstructionData {
         let seed = u64::from_le_bytes(data[0..8].try_into().unwrap());
         let receive = u64::from_le_bytes(data[8..16].try_into().unwrap());
         let amount = u64::from_le_bytes(data[16..24].try_into().unwrap());
+        // Optional expiry (8): unix timestamp, omitted or 0 = never expires
+        let expiry = if data.len() >= 32 {
+            i64::from_le_bytes(data[24..32].try_into().unwrap())
+        } else {
+            0
+        };
+        // Optional taker restriction: mode (1) + taker address or merkle root (32)
+        let (taker_mode, taker) = if data.len() >= 65 {
+            (
+                data[32],
+                Address::new_from_array(data[33..65].try_into().unwrap()),
+            )
+        } else {
+            (Escrow::TAKER_ANY, Address::default())
+        };
+        // Optional dutch auction: mode (1) + end_receive (8) + start (8) + end (8)
+        let (auction_mode, end_receive, auction_start, auction_end) = if data.len() >= 90 {
+            (
+                data[65],
+                u64::from_le_bytes(data[66..74].try_into().unwrap()),
+                i64::from_le_bytes(data[74..82].try_into().unwrap()),
+                i64::from_le_bytes(data[82..90].try_into().unwrap()),
+            )
+        } else {
+            (Escrow::AUCTION_NONE, receive, 0, 0)
+        };
+        // Optional order-book page index (4)
+        let book_page = if data.len() >= 94 {
+            Some(u32::from_le_bytes(data[90..94].try_into().unwrap()))
+        } else {
+            None
+        };
 
         if receive == 0 || amount == 0 {
             return Err(EscrowError::InvalidAmount.into());
         }
 
+        if expiry < 0 {
+            return Err(EscrowError::InvalidExpiry.into());
+        }
+
+        if taker_mode > Escrow::TAKER_MERKLE
+            || auction_mode > Escrow::AUCTION_EXPONENTIAL
+            || book_page == Some(Escrow::NO_BOOK)
+        {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        // The price falls from `receive` to a non-zero floor over a non-empty window
+        if auction_mode != Escrow::AUCTION_NONE
+            && (end_receive == 0 || end_receive >= receive || auction_start >= auction_end)
+        {
+            return Err(EscrowError::InvalidAuction.into());
+        }
+
         Ok(Self {
             seed,
             receive,
             amount,
+            expiry,
+            taker_mode,
+            taker,
+            auction_mode,
+            end_receive,
+            auction_start,
+            auction_end,
+            book_page,
         })
     }
 }
@@ -118,6 +219,24 @@ impl<'a> TryFrom<(&'a [u8], &'a [AccountView])> for Make<'a> {
             return Err(ProgramError::InvalidArgument);
         }
 
+        // A page index comes with its page account
+        match (instruction_data.book_page, accounts.book_page) {
+            (Some(_), None) => return Err(ProgramError::NotEnoughAccountKeys),
+            (None, Some(_)) => return Err(ProgramError::InvalidInstructionData),
+            _ => {}
+        }
+
+        // An expiry must lie in the future, and so must the end of an auction
+        let now = Clock::get()?.unix_timestamp;
+        if instruction_data.expiry != 0 && instruction_data.expiry <= now {
+            return Err(EscrowError::InvalidExpiry.into());
+        }
+        if instruction_data.auction_mode != Escrow::AUCTION_NONE
+            && instruction_data.auction_end <= now
+        {
+            return Err(EscrowError::InvalidAuction.into());
+        }
+
         Ok(Self {
             accounts,
             instruction_data,
@@ -153,7 +272,7 @@ impl<'a> Make<'a> {
             self.accounts.escrow,
             Escrow::LEN,
             &ID,
-            self.accounts.maker,
+            self.accounts.rent_payer,
             None,
             &signers,
         )?;
@@ -161,20 +280,31 @@ impl<'a> Make<'a> {
         // 2. Initialize escrow state
         {
             let mut escrow_data = self.accounts.escrow.try_borrow_mut()?;
-            let escrow = Escrow::load_mut(&mut *escrow_data)?;
+            let escrow = Escrow::init(&mut escrow_data)?;
             escrow.set_inner(
                 self.instruction_data.seed,
-                self.accounts.maker.address().clone(),
-                self.accounts.mint_a.address().clone(),
-                self.accounts.mint_b.address().clone(),
+                *self.accounts.maker.address(),
+                *self.accounts.mint_a.address(),
+                *self.accounts.mint_b.address(),
                 self.instruction_data.receive,
                 [bump],
             );
+            escrow.set_expiry(self.instruction_data.expiry);
+            escrow.set_taker(self.instruction_data.taker_mode, self.instruction_data.taker);
+            escrow.set_sequence(0);
+            escrow.set_auction(
+                self.instruction_data.auction_mode,
+                self.instruction_data.end_receive,
+                self.instruction_data.auction_start,
+                self.instruction_data.auction_end,
+            );
+            escrow.set_rent_payer(*self.accounts.rent_payer.address());
+            escrow.set_book_page(self.instruction_data.book_page.unwrap_or(Escrow::NO_BOOK));
         }
 
         // 3. Create vault ATA
         Create {
-            funding_account: self.accounts.maker,
+            funding_account: self.accounts.rent_payer,
             account: self.accounts.vault,
             wallet: self.accounts.escrow,
             mint: self.accounts.mint_a,
@@ -183,17 +313,43 @@ impl<'a> Make<'a> {
         }
         .invoke()?;
 
-        // 4. Transfer tokens from maker to vault
+        // 4. Transfer tokens from maker to vault (escrow PDA as delegate under CPI guard)
         let decimals = get_mint_decimals(self.accounts.mint_a)?;
-        TransferChecked {
-            from: self.accounts.maker_ata_a,
-            mint: self.accounts.mint_a,
-            to: self.accounts.vault,
-            authority: self.accounts.maker,
-            amount: self.instruction_data.amount,
-            decimals,
+        transfer_from_user(
+            TransferChecked {
+                token_program: self.accounts.token_program,
+                from: self.accounts.maker_ata_a,
+                mint: self.accounts.mint_a,
+                to: self.accounts.vault,
+                authority: self.accounts.maker,
+                amount: self.instruction_data.amount,
+                decimals,
+            },
+            self.accounts.escrow,
+            &signers,
+        )?;
+
+        // 5. Offer what actually arrived: transfer fees are withheld in the vault
+        let received = get_token_account_amount(self.accounts.vault)?;
+        {
+            let mut escrow_data = self.accounts.escrow.try_borrow_mut()?;
+            Escrow::load_mut(&mut escrow_data)?.set_remaining(received);
+        }
+
+        // 6. List the escrow in the pair's order book
+        if let (Some(page), Some(index)) =
+            (self.accounts.book_page, self.instruction_data.book_page)
+        {
+            append_to_book(
+                page,
+                self.accounts.rent_payer,
+                self.accounts.mint_a.address(),
+                self.accounts.mint_b.address(),
+                index,
+                self.accounts.escrow.address(),
+                self.accounts.maker.address(),
+            )?;
         }
-        .invoke()?;
 
         Ok(())
     }
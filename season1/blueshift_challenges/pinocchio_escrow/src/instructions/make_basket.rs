@@ -0,0 +1,280 @@
+use pinocchio::{
+    cpi::{Seed, Signer},
+    error::ProgramError,
+    AccountView, Address, ProgramResult,
+};
+use pinocchio_associated_token_account::instructions::Create;
+use pinocchio_system::create_account_with_minimum_balance_signed;
+
+use crate::errors::EscrowError;
+use crate::instructions::helpers::{
+    check_program_id, get_mint_decimals, get_token_account_amount, get_token_account_owner,
+};
+use crate::instructions::token_interface::{
+    check_mint_extensions, check_token_mint, check_token_program, transfer_from_user,
+    TransferChecked,
+};
+use crate::state::Basket;
+use crate::ID;
+
+pub const BASKET_SEED: &[u8] = b"basket";
+
+/// Accounts per deposit leg: mint, maker_ata, vault, token_program
+pub const MAKE_DEPOSIT_LEG_ACCOUNTS: usize = 4;
+
+pub struct MakeBasketAccounts<'a> {
+    pub maker: &'a AccountView,
+    pub basket: &'a AccountView,
+    pub associated_token_program: &'a AccountView,
+    pub system_program: &'a AccountView,
+    /// `deposit_count` groups of [mint, maker_ata, vault, token_program]
+    pub deposit_legs: &'a [AccountView],
+    /// `ask_count` asked mints
+    pub ask_mints: &'a [AccountView],
+}
+
+impl<'a> MakeBasketAccounts<'a> {
+    fn try_from_with_counts(
+        accounts: &'a [AccountView],
+        deposit_count: usize,
+        ask_count: usize,
+    ) -> Result<Self, ProgramError> {
+        let [maker, basket, associated_token_program, system_program, legs @ ..] = accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        if !maker.is_signer() {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        check_program_id(associated_token_program, &pinocchio_associated_token_account::ID)?;
+        check_program_id(system_program, &pinocchio_system::ID)?;
+
+        let deposit_len = deposit_count * MAKE_DEPOSIT_LEG_ACCOUNTS;
+        if legs.len() != deposit_len + ask_count {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        }
+        let (deposit_legs, ask_mints) = legs.split_at(deposit_len);
+
+        for leg in deposit_legs.chunks_exact(MAKE_DEPOSIT_LEG_ACCOUNTS) {
+            let [mint, maker_ata, _vault, token_program] = leg else {
+                unreachable!()
+            };
+            check_token_program(mint, token_program)?;
+            check_mint_extensions(mint)?;
+            if get_token_account_owner(maker_ata)? != *maker.address() {
+                return Err(ProgramError::InvalidAccountOwner);
+            }
+        }
+        for mint in ask_mints {
+            check_token_mint(mint)?;
+            check_mint_extensions(mint)?;
+        }
+
+        // One vault per deposited mint; each asked mint is paid once
+        if has_duplicate_mint(deposit_legs.iter().step_by(MAKE_DEPOSIT_LEG_ACCOUNTS))
+            || has_duplicate_mint(ask_mints.iter())
+        {
+            return Err(EscrowError::DuplicateMint.into());
+        }
+
+        Ok(Self {
+            maker,
+            basket,
+            associated_token_program,
+            system_program,
+            deposit_legs,
+            ask_mints,
+        })
+    }
+}
+
+fn has_duplicate_mint<'a>(mints: impl Iterator<Item = &'a AccountView> + Clone) -> bool {
+    mints.clone().enumerate().any(|(i, mint)| {
+        mints
+            .clone()
+            .skip(i + 1)
+            .any(|other| other.address() == mint.address())
+    })
+}
+
+pub struct MakeBasketInstructionData<'a> {
+    pub seed: u64,
+    /// Amount deposited per deposit leg (u64 LE each)
+    pub deposit_amounts: &'a [u8],
+    /// Amount asked per ask leg (u64 LE each)
+    pub ask_amounts: &'a [u8],
+}
+
+impl<'a> TryFrom<&'a [u8]> for MakeBasketInstructionData<'a> {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        // seed (8) + deposit_count (1) + ask_count (1)
+        //   + deposit amounts (8 * deposit_count) + ask amounts (8 * ask_count)
+        if data.len() < 10 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let seed = u64::from_le_bytes(data[0..8].try_into().unwrap());
+        let deposit_count = data[8] as usize;
+        let ask_count = data[9] as usize;
+
+        if !(1..=Basket::MAX_DEPOSITS).contains(&deposit_count)
+            || !(1..=Basket::MAX_ASKS).contains(&ask_count)
+        {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        if data.len() != 10 + 8 * (deposit_count + ask_count) {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let (deposit_amounts, ask_amounts) = data[10..].split_at(8 * deposit_count);
+        if deposit_amounts
+            .chunks_exact(8)
+            .chain(ask_amounts.chunks_exact(8))
+            .any(|amount| u64::from_le_bytes(amount.try_into().unwrap()) == 0)
+        {
+            return Err(EscrowError::InvalidAmount.into());
+        }
+
+        Ok(Self {
+            seed,
+            deposit_amounts,
+            ask_amounts,
+        })
+    }
+}
+
+pub struct MakeBasket<'a> {
+    pub accounts: MakeBasketAccounts<'a>,
+    pub instruction_data: MakeBasketInstructionData<'a>,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountView])> for MakeBasket<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountView])) -> Result<Self, Self::Error> {
+        let instruction_data = MakeBasketInstructionData::try_from(data)?;
+        let accounts = MakeBasketAccounts::try_from_with_counts(
+            accounts,
+            instruction_data.deposit_amounts.len() / 8,
+            instruction_data.ask_amounts.len() / 8,
+        )?;
+
+        // Verify basket PDA
+        let (basket_pda, _bump) = Address::find_program_address(
+            &[
+                BASKET_SEED,
+                accounts.maker.address().as_ref(),
+                &instruction_data.seed.to_le_bytes()[..],
+            ],
+            &ID,
+        );
+        if accounts.basket.address() != &basket_pda {
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> MakeBasket<'a> {
+    pub const DISCRIMINATOR: u8 = 4;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let (_, bump) = Address::find_program_address(
+            &[
+                BASKET_SEED,
+                self.accounts.maker.address().as_ref(),
+                &self.instruction_data.seed.to_le_bytes()[..],
+            ],
+            &ID,
+        );
+
+        // 1. Create basket PDA account
+        let seed_bytes = self.instruction_data.seed.to_le_bytes();
+        let bump_array = [bump];
+        let seeds = [
+            Seed::from(BASKET_SEED),
+            Seed::from(self.accounts.maker.address().as_ref()),
+            Seed::from(&seed_bytes[..]),
+            Seed::from(&bump_array[..]),
+        ];
+        let signers = [Signer::from(&seeds)];
+
+        create_account_with_minimum_balance_signed(
+            self.accounts.basket,
+            Basket::LEN,
+            &ID,
+            self.accounts.maker,
+            None,
+            &signers,
+        )?;
+
+        {
+            let mut basket_data = self.accounts.basket.try_borrow_mut()?;
+            let basket = Basket::init(&mut basket_data)?;
+            basket.set_inner(self.instruction_data.seed, *self.accounts.maker.address(), [bump]);
+        }
+
+        // 2. Create a vault ATA per deposited mint and fill it
+        for (leg, amount) in self
+            .accounts
+            .deposit_legs
+            .chunks_exact(MAKE_DEPOSIT_LEG_ACCOUNTS)
+            .zip(self.instruction_data.deposit_amounts.chunks_exact(8))
+        {
+            let [mint, maker_ata, vault, token_program] = leg else {
+                unreachable!()
+            };
+
+            Create {
+                funding_account: self.accounts.maker,
+                account: vault,
+                wallet: self.accounts.basket,
+                mint,
+                system_program: self.accounts.system_program,
+                token_program,
+            }
+            .invoke()?;
+
+            transfer_from_user(
+                TransferChecked {
+                    token_program,
+                    from: maker_ata,
+                    mint,
+                    to: vault,
+                    authority: self.accounts.maker,
+                    amount: u64::from_le_bytes(amount.try_into().unwrap()),
+                    decimals: get_mint_decimals(mint)?,
+                },
+                self.accounts.basket,
+                &signers,
+            )?;
+
+            // Record what actually arrived: transfer fees are withheld in the vault
+            let received = get_token_account_amount(vault)?;
+            let mut basket_data = self.accounts.basket.try_borrow_mut()?;
+            Basket::load_mut(&mut basket_data)?.push_deposit(*mint.address(), received)?;
+        }
+
+        // 3. Record the asked mints
+        let mut basket_data = self.accounts.basket.try_borrow_mut()?;
+        let basket = Basket::load_mut(&mut basket_data)?;
+        for (mint, amount) in self
+            .accounts
+            .ask_mints
+            .iter()
+            .zip(self.instruction_data.ask_amounts.chunks_exact(8))
+        {
+            basket.push_ask(*mint.address(), u64::from_le_bytes(amount.try_into().unwrap()))?;
+        }
+
+        Ok(())
+    }
+}
@@ -1,8 +1,20 @@
+mod amend;
 mod helpers;
 mod make;
+mod make_basket;
+mod order_book;
 mod refund;
+mod refund_basket;
+mod refund_expired;
 mod take;
+mod take_basket;
+mod token_interface;
 
+pub use amend::*;
 pub use make::*;
+pub use make_basket::*;
 pub use refund::*;
+pub use refund_basket::*;
+pub use refund_expired::*;
 pub use take::*;
+pub use take_basket::*;
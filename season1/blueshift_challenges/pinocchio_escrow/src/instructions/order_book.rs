@@ -0,0 +1,131 @@
+use core::ops::Deref;
+
+use pinocchio::{
+    cpi::{Seed, Signer},
+    error::ProgramError,
+    sysvars::{rent::Rent, Sysvar},
+    AccountView, Address,
+};
+use pinocchio_system::{create_account_with_minimum_balance_signed, instructions::Transfer};
+
+use crate::instructions::helpers::check_program_pda;
+use crate::state::{BookPage, Escrow};
+use crate::ID;
+
+pub const BOOK_SEED: &[u8] = b"book";
+
+/// Check that `page` is the initialized order-book page the escrow is listed in
+pub fn check_book_page(page: &AccountView, escrow: &Escrow) -> Result<(), ProgramError> {
+    let data = page.try_borrow()?;
+    let (book, _) = BookPage::load(data.deref())?;
+    if book.page != escrow.book_page {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    check_program_pda(
+        page,
+        &[
+            BOOK_SEED,
+            escrow.mint_a.as_ref(),
+            escrow.mint_b.as_ref(),
+            &escrow.book_page.to_le_bytes(),
+            &book.bump,
+        ],
+    )
+}
+
+/// List `escrow` in page `index` of the (mint_a, mint_b) book. Creates the page
+/// on first use and grows a full page by `BookPage::GROWTH` entries; `payer`
+/// funds the rent either way.
+pub fn append_to_book(
+    page: &AccountView,
+    payer: &AccountView,
+    mint_a: &Address,
+    mint_b: &Address,
+    index: u32,
+    escrow: &Address,
+    maker: &Address,
+) -> Result<(), ProgramError> {
+    let index_bytes = index.to_le_bytes();
+
+    if !page.owned_by(&ID) {
+        let (pda, bump) = Address::find_program_address(
+            &[BOOK_SEED, mint_a.as_ref(), mint_b.as_ref(), &index_bytes],
+            &ID,
+        );
+        if page.address() != &pda {
+            return Err(ProgramError::InvalidSeeds);
+        }
+
+        let bump_array = [bump];
+        let seeds = [
+            Seed::from(BOOK_SEED),
+            Seed::from(mint_a.as_ref()),
+            Seed::from(mint_b.as_ref()),
+            Seed::from(&index_bytes[..]),
+            Seed::from(&bump_array[..]),
+        ];
+        create_account_with_minimum_balance_signed(
+            page,
+            BookPage::space(BookPage::INITIAL_CAPACITY),
+            &ID,
+            payer,
+            None,
+            &[Signer::from(&seeds)],
+        )?;
+
+        let mut data = page.try_borrow_mut()?;
+        BookPage::init(&mut data)?.set_inner(index, *mint_a, *mint_b, [bump]);
+    } else {
+        let data = page.try_borrow()?;
+        let (book, _) = BookPage::load(data.deref())?;
+        if book.page != index || book.mint_a != *mint_a || book.mint_b != *mint_b {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        check_program_pda(
+            page,
+            &[BOOK_SEED, mint_a.as_ref(), mint_b.as_ref(), &index_bytes, &book.bump],
+        )?;
+    }
+
+    let capacity = {
+        let data = page.try_borrow()?;
+        let (book, _) = BookPage::load(data.deref())?;
+        if book.is_full() {
+            book.capacity
+                .checked_add(BookPage::GROWTH)
+                .filter(|capacity| *capacity <= BookPage::MAX_ENTRIES)
+        } else {
+            None
+        }
+    };
+
+    // Full page: realloc and top up rent for the larger account
+    if let Some(capacity) = capacity {
+        let space = BookPage::space(capacity);
+        page.resize(space)?;
+        let required = Rent::get()?
+            .try_minimum_balance(space)?
+            .saturating_sub(page.lamports());
+        if required > 0 {
+            Transfer {
+                from: payer,
+                to: page,
+                lamports: required,
+            }
+            .invoke()?;
+        }
+        let mut data = page.try_borrow_mut()?;
+        BookPage::load_mut(&mut data)?.0.capacity = capacity;
+    }
+
+    let mut data = page.try_borrow_mut()?;
+    let (book, entries) = BookPage::load_mut(&mut data)?;
+    book.push(entries, *escrow, *maker)
+}
+
+/// Drop `escrow` from the page it is listed in (already checked with `check_book_page`)
+pub fn remove_from_book(page: &AccountView, escrow: &Address) -> Result<(), ProgramError> {
+    let mut data = page.try_borrow_mut()?;
+    let (book, entries) = BookPage::load_mut(&mut data)?;
+    book.remove(entries, escrow)
+}
@@ -6,10 +6,14 @@ use pinocchio::{
     AccountView, ProgramResult,
 };
 use pinocchio_associated_token_account::instructions::CreateIdempotent;
-use pinocchio_token::instructions::{CloseAccount, TransferChecked};
 
 use crate::errors::EscrowError;
-use crate::instructions::helpers::{get_mint_decimals, get_token_account_amount};
+use crate::instructions::helpers::{
+    check_program_id, check_program_pda, check_vault, close_program_account, get_mint_decimals,
+    get_token_account_amount,
+};
+use crate::instructions::order_book::{check_book_page, remove_from_book};
+use crate::instructions::token_interface::{check_token_program, close_vault, TransferChecked};
 use crate::state::Escrow;
 
 const ESCROW_SEED: &[u8] = b"escrow";
@@ -23,12 +27,33 @@ pub struct RefundAccounts<'a> {
     pub associated_token_program: &'a AccountView,
     pub token_program: &'a AccountView,
     pub system_program: &'a AccountView,
+    /// Receives the escrow and vault rent: `Escrow::rent_payer`, which is
+    /// `maker` unless the rent was sponsored (then a trailing account)
+    pub rent_payer: &'a AccountView,
+    /// Order-book page of indexed escrows; trailing account after `rent_payer`
+    pub book_page: Option<&'a AccountView>,
 }
 
 impl<'a> TryFrom<&'a [AccountView]> for RefundAccounts<'a> {
     type Error = ProgramError;
 
     fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let [maker, ..] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+        if !maker.is_signer() {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        Self::load(accounts)
+    }
+}
+
+impl<'a> RefundAccounts<'a> {
+    /// Validate the escrow, its vault and the rent / order-book trailing accounts.
+    /// Does not check who authorised the refund: `Refund` requires the maker's
+    /// signature, `RefundExpired` an expired escrow.
+    pub fn load(accounts: &'a [AccountView]) -> Result<Self, ProgramError> {
         let [
             maker,
             escrow,
@@ -38,14 +63,12 @@ impl<'a> TryFrom<&'a [AccountView]> for RefundAccounts<'a> {
             associated_token_program,
             token_program,
             system_program,
+            rest @ ..,
         ] = accounts
         else {
             return Err(ProgramError::NotEnoughAccountKeys);
         };
-
-        if !maker.is_signer() {
-            return Err(ProgramError::MissingRequiredSignature);
-        }
+        let mut rest = rest.iter();
 
         let escrow_data = escrow.try_borrow()?;
         let escrow_state = Escrow::load(escrow_data.deref())?;
@@ -57,6 +80,41 @@ impl<'a> TryFrom<&'a [AccountView]> for RefundAccounts<'a> {
             return Err(EscrowError::InvalidMintA.into());
         }
 
+        // The escrow must be this program's PDA for (maker, seed), and the vault its ATA
+        check_program_pda(
+            escrow,
+            &[
+                ESCROW_SEED,
+                maker.address().as_ref(),
+                &escrow_state.seed.to_le_bytes(),
+                &escrow_state.bump,
+            ],
+        )?;
+        check_vault(vault, escrow, mint_a, token_program)?;
+        check_program_id(associated_token_program, &pinocchio_associated_token_account::ID)?;
+        check_program_id(system_program, &pinocchio_system::ID)?;
+        check_token_program(mint_a, token_program)?;
+
+        // Sponsored escrows return their rent to the rent payer, passed as the first trailing account
+        let rent_payer = if escrow_state.is_sponsored() {
+            let rent_payer = rest.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+            if *rent_payer.address() != escrow_state.rent_payer {
+                return Err(EscrowError::InvalidRentPayer.into());
+            }
+            rent_payer
+        } else {
+            maker
+        };
+
+        // Indexed escrows must come with their order-book page
+        let book_page = if escrow_state.book_page == Escrow::NO_BOOK {
+            None
+        } else {
+            let page = rest.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+            check_book_page(page, escrow_state)?;
+            Some(page)
+        };
+
         Ok(Self {
             maker,
             escrow,
@@ -66,6 +124,8 @@ impl<'a> TryFrom<&'a [AccountView]> for RefundAccounts<'a> {
             associated_token_program,
             token_program,
             system_program,
+            rent_payer,
+            book_page,
         })
     }
 }
@@ -87,60 +147,73 @@ impl<'a> Refund<'a> {
     pub const DISCRIMINATOR: u8 = 2;
 
     pub fn process(&mut self) -> ProgramResult {
-        let (seed, bump) = {
-            let escrow_data = self.accounts.escrow.try_borrow()?;
-            let escrow = Escrow::load(escrow_data.deref())?;
-            (escrow.seed, escrow.bump[0])
-        };
-
-        let seed_bytes = seed.to_le_bytes();
-        let bump_array = [bump];
-        let seeds = [
-            Seed::from(ESCROW_SEED),
-            Seed::from(self.accounts.maker.address().as_ref()),
-            Seed::from(&seed_bytes[..]),
-            Seed::from(&bump_array[..]),
-        ];
-        let signers = [Signer::from(&seeds)];
-
-        let mint_a_decimals = get_mint_decimals(self.accounts.mint_a)?;
-        let vault_amount = get_token_account_amount(self.accounts.vault)?;
-
-        // 1. Create maker_ata_a if needed (init_if_needed)
-        CreateIdempotent {
-            funding_account: self.accounts.maker,
-            account: self.accounts.maker_ata_a,
-            wallet: self.accounts.maker,
-            mint: self.accounts.mint_a,
-            system_program: self.accounts.system_program,
-            token_program: self.accounts.token_program,
-        }
-        .invoke()?;
-
-        // 2. Transfer token A from vault to maker
-        TransferChecked {
-            from: self.accounts.vault,
-            mint: self.accounts.mint_a,
-            to: self.accounts.maker_ata_a,
-            authority: self.accounts.escrow,
-            amount: vault_amount,
-            decimals: mint_a_decimals,
-        }
-        .invoke_signed(&signers)?;
-
-        // 3. Close vault, send lamports to maker
-        CloseAccount {
-            account: self.accounts.vault,
-            destination: self.accounts.maker,
-            authority: self.accounts.escrow,
-        }
-        .invoke_signed(&signers)?;
-
-        // 4. Transfer escrow rent to maker (direct lamport manipulation - avoids Assign CPI)
-        let lamports = self.accounts.escrow.lamports();
-        self.accounts.maker.set_lamports(self.accounts.maker.lamports() + lamports);
-        self.accounts.escrow.set_lamports(0);
+        refund_escrow(&self.accounts, self.accounts.maker)
+    }
+}
 
-        Ok(())
+/// Return the vault's token A to the maker and close the vault and escrow, sending
+/// their rent to the rent payer and dropping the escrow from its order-book page.
+/// `funding_account` pays for `maker_ata_a` if it doesn't exist yet.
+pub fn refund_escrow(accounts: &RefundAccounts, funding_account: &AccountView) -> ProgramResult {
+    let (seed, bump) = {
+        let escrow_data = accounts.escrow.try_borrow()?;
+        let escrow = Escrow::load(escrow_data.deref())?;
+        (escrow.seed, escrow.bump[0])
+    };
+
+    let seed_bytes = seed.to_le_bytes();
+    let bump_array = [bump];
+    let seeds = [
+        Seed::from(ESCROW_SEED),
+        Seed::from(accounts.maker.address().as_ref()),
+        Seed::from(&seed_bytes[..]),
+        Seed::from(&bump_array[..]),
+    ];
+    let signers = [Signer::from(&seeds)];
+
+    let mint_a_decimals = get_mint_decimals(accounts.mint_a)?;
+    let vault_amount = get_token_account_amount(accounts.vault)?;
+
+    // 1. Create maker_ata_a if needed (init_if_needed)
+    CreateIdempotent {
+        funding_account,
+        account: accounts.maker_ata_a,
+        wallet: accounts.maker,
+        mint: accounts.mint_a,
+        system_program: accounts.system_program,
+        token_program: accounts.token_program,
     }
+    .invoke()?;
+
+    // 2. Transfer token A from vault to maker
+    TransferChecked {
+        token_program: accounts.token_program,
+        from: accounts.vault,
+        mint: accounts.mint_a,
+        to: accounts.maker_ata_a,
+        authority: accounts.escrow,
+        amount: vault_amount,
+        decimals: mint_a_decimals,
+    }
+    .invoke_signed(&signers)?;
+
+    // 3. Close vault, send lamports to the rent payer
+    close_vault(
+        accounts.token_program,
+        accounts.mint_a,
+        accounts.vault,
+        accounts.rent_payer,
+        accounts.escrow,
+        &signers,
+    )?;
+
+    // 4. Return escrow rent to the rent payer and close it (direct lamport manipulation - avoids Assign CPI)
+    close_program_account(accounts.escrow, accounts.rent_payer)?;
+
+    // 5. Drop the closed escrow from the order book
+    if let Some(page) = accounts.book_page {
+        remove_from_book(page, accounts.escrow.address())?;
+    }
+
+    Ok(())
 }
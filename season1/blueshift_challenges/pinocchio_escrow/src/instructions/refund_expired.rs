@@ -0,0 +1,65 @@
+use core::ops::Deref;
+
+use pinocchio::{
+    error::ProgramError,
+    sysvars::{clock::Clock, Sysvar},
+    AccountView, ProgramResult,
+};
+
+use crate::errors::EscrowError;
+use crate::instructions::refund::{refund_escrow, RefundAccounts};
+use crate::state::Escrow;
+
+/// Permissionless crank: anyone can close an expired escrow.
+/// Tokens go back to the maker and all rent to the rent payer; the caller
+/// only pays for `maker_ata_a` if it doesn't exist yet.
+pub struct RefundExpiredAccounts<'a> {
+    pub caller: &'a AccountView,
+    /// The `Refund` accounts after `caller`; `maker` does not sign
+    pub refund: RefundAccounts<'a>,
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for RefundExpiredAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let [caller, rest @ ..] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        if !caller.is_signer() {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let refund = RefundAccounts::load(rest)?;
+
+        let escrow_data = refund.escrow.try_borrow()?;
+        let escrow_state = Escrow::load(escrow_data.deref())?;
+        if !escrow_state.is_expired(Clock::get()?.unix_timestamp) {
+            return Err(EscrowError::EscrowNotExpired.into());
+        }
+
+        Ok(Self { caller, refund })
+    }
+}
+
+pub struct RefundExpired<'a> {
+    pub accounts: RefundExpiredAccounts<'a>,
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for RefundExpired<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let accounts = RefundExpiredAccounts::try_from(accounts)?;
+        Ok(Self { accounts })
+    }
+}
+
+impl<'a> RefundExpired<'a> {
+    pub const DISCRIMINATOR: u8 = 3;
+
+    pub fn process(&mut self) -> ProgramResult {
+        refund_escrow(&self.accounts.refund, self.accounts.caller)
+    }
+}
@@ -2,13 +2,20 @@ use core::ops::Deref;
 use pinocchio::{
     cpi::{Seed, Signer},
     error::ProgramError,
+    sysvars::{clock::Clock, Sysvar},
     AccountView, ProgramResult,
 };
 use pinocchio_associated_token_account::instructions::CreateIdempotent;
-use pinocchio_token::instructions::{CloseAccount, TransferChecked};
 
 use crate::errors::EscrowError;
-use crate::instructions::helpers::{get_mint_decimals, get_token_account_amount};
+use crate::instructions::helpers::{
+    check_program_id, check_program_pda, check_vault, close_program_account, get_mint_decimals,
+    get_token_account_amount, get_token_account_owner,
+};
+use crate::instructions::order_book::{check_book_page, remove_from_book};
+use crate::instructions::token_interface::{
+    check_token_program, close_vault, gross_amount, transfer_from_user, TransferChecked,
+};
 use crate::state::Escrow;
 
 const ESCROW_SEED: &[u8] = b"escrow";
@@ -26,6 +33,13 @@ pub struct TakeAccounts<'a> {
     pub associated_token_program: &'a AccountView,
     pub token_program: &'a AccountView,
     pub system_program: &'a AccountView,
+    /// Token program of mint B; optional trailing account, defaults to `token_program`
+    pub token_program_b: &'a AccountView,
+    /// Receives the escrow and vault rent: `Escrow::rent_payer`, which is
+    /// `maker` unless the rent was sponsored (then a trailing account)
+    pub rent_payer: &'a AccountView,
+    /// Order-book page of indexed escrows; trailing account after `rent_payer`
+    pub book_page: Option<&'a AccountView>,
 }
 
 impl<'a> TryFrom<&'a [AccountView]> for TakeAccounts<'a> {
@@ -45,15 +59,24 @@ impl<'a> TryFrom<&'a [AccountView]> for TakeAccounts<'a> {
             associated_token_program,
             token_program,
             system_program,
+            rest @ ..,
         ] = accounts
         else {
             return Err(ProgramError::NotEnoughAccountKeys);
         };
+        let mut rest = rest.iter();
+        let token_program_b = rest.next().unwrap_or(token_program);
 
         if !taker.is_signer() {
             return Err(ProgramError::MissingRequiredSignature);
         }
 
+        check_token_program(mint_a, token_program)?;
+        check_token_program(mint_b, token_program_b)?;
+        if get_token_account_owner(taker_ata_b)? != *taker.address() {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+
         let escrow_data = escrow.try_borrow()?;
         let escrow_state = Escrow::load(escrow_data.deref())?;
 
@@ -63,9 +86,52 @@ impl<'a> TryFrom<&'a [AccountView]> for TakeAccounts<'a> {
         if escrow_state.mint_a != *mint_a.address() {
             return Err(EscrowError::InvalidMintA.into());
         }
+
+        // The escrow must be this program's PDA for (maker, seed), and the vault its ATA
+        check_program_pda(
+            escrow,
+            &[
+                ESCROW_SEED,
+                maker.address().as_ref(),
+                &escrow_state.seed.to_le_bytes(),
+                &escrow_state.bump,
+            ],
+        )?;
+        check_vault(vault, escrow, mint_a, token_program)?;
+        check_program_id(associated_token_program, &pinocchio_associated_token_account::ID)?;
+        check_program_id(system_program, &pinocchio_system::ID)?;
         if escrow_state.mint_b != *mint_b.address() {
             return Err(EscrowError::InvalidMintB.into());
         }
+        if escrow_state.is_expired(Clock::get()?.unix_timestamp) {
+            return Err(EscrowError::EscrowExpired.into());
+        }
+        // Designated-taker escrows; merkle proofs come with the instruction data
+        if escrow_state.taker_mode == Escrow::TAKER_ADDRESS
+            && !escrow_state.is_allowed_taker(taker.address(), &[])
+        {
+            return Err(EscrowError::UnauthorizedTaker.into());
+        }
+
+        // Sponsored escrows return their rent to the rent payer, passed after `token_program_b`
+        let rent_payer = if escrow_state.is_sponsored() {
+            let rent_payer = rest.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+            if *rent_payer.address() != escrow_state.rent_payer {
+                return Err(EscrowError::InvalidRentPayer.into());
+            }
+            rent_payer
+        } else {
+            maker
+        };
+
+        // Indexed escrows must come with their order-book page
+        let book_page = if escrow_state.book_page == Escrow::NO_BOOK {
+            None
+        } else {
+            let page = rest.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+            check_book_page(page, escrow_state)?;
+            Some(page)
+        };
 
         Ok(Self {
             taker,
@@ -80,20 +146,132 @@ impl<'a> TryFrom<&'a [AccountView]> for TakeAccounts<'a> {
             associated_token_program,
             token_program,
             system_program,
+            token_program_b,
+            rent_payer,
+            book_page,
+        })
+    }
+}
+
+pub struct TakeInstructionData<'a> {
+    /// Token A to take; `None` takes everything that remains
+    pub amount: Option<u64>,
+    /// `Escrow::sequence` the taker agreed to; `None` accepts any amendment
+    pub sequence: Option<u64>,
+    /// Most token B the taker will pay for `amount`; required for dutch auctions
+    pub max_pay: Option<u64>,
+    /// Merkle proof for `Escrow::TAKER_MERKLE` escrows: 32-byte sibling hashes
+    pub proof: &'a [u8],
+}
+
+impl TakeInstructionData<'_> {
+    /// Flag bit: an expected `Escrow::sequence` (8) follows
+    pub const HAS_SEQUENCE: u8 = 1 << 0;
+    /// Flag bit: a maximum token B payment (8) follows
+    pub const HAS_MAX_PAY: u8 = 1 << 1;
+}
+
+/// Split a little-endian u64 off the front of `data`
+fn read_u64(data: &mut &[u8]) -> Result<u64, ProgramError> {
+    let (value, rest) = data
+        .split_first_chunk::<8>()
+        .ok_or(ProgramError::InvalidInstructionData)?;
+    *data = rest;
+    Ok(u64::from_le_bytes(*value))
+}
+
+impl<'a> TryFrom<&'a [u8]> for TakeInstructionData<'a> {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        // Optional amount (8): omitted = fill the whole escrow
+        if data.is_empty() {
+            return Ok(Self {
+                amount: None,
+                sequence: None,
+                max_pay: None,
+                proof: &[],
+            });
+        }
+
+        let mut rest = data;
+        let amount = read_u64(&mut rest)?;
+        if amount == 0 {
+            return Err(EscrowError::InvalidAmount.into());
+        }
+
+        // Then an optional flags byte announcing which of sequence (8) and
+        // max price (8) follow, in that order; the rest is the merkle proof (32 * n)
+        let flags = match rest.split_first() {
+            Some((flags, tail)) => {
+                rest = tail;
+                *flags
+            }
+            None => 0,
+        };
+        if flags & !(Self::HAS_SEQUENCE | Self::HAS_MAX_PAY) != 0 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        let sequence = if flags & Self::HAS_SEQUENCE != 0 {
+            Some(read_u64(&mut rest)?)
+        } else {
+            None
+        };
+        let max_pay = if flags & Self::HAS_MAX_PAY != 0 {
+            Some(read_u64(&mut rest)?)
+        } else {
+            None
+        };
+        if !rest.len().is_multiple_of(32) {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Ok(Self {
+            amount: Some(amount),
+            sequence,
+            max_pay,
+            proof: rest,
         })
     }
 }
 
 pub struct Take<'a> {
     pub accounts: TakeAccounts<'a>,
+    pub instruction_data: TakeInstructionData<'a>,
 }
 
-impl<'a> TryFrom<&'a [AccountView]> for Take<'a> {
+impl<'a> TryFrom<(&'a [u8], &'a [AccountView])> for Take<'a> {
     type Error = ProgramError;
 
-    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountView])) -> Result<Self, Self::Error> {
         let accounts = TakeAccounts::try_from(accounts)?;
-        Ok(Self { accounts })
+        let instruction_data = TakeInstructionData::try_from(data)?;
+
+        {
+            let escrow_data = accounts.escrow.try_borrow()?;
+            let escrow = Escrow::load(escrow_data.deref())?;
+            if escrow.taker_mode == Escrow::TAKER_MERKLE
+                && !escrow.is_allowed_taker(accounts.taker.address(), instruction_data.proof)
+            {
+                return Err(EscrowError::UnauthorizedTaker.into());
+            }
+            // The maker amended the escrow after the taker priced it
+            if instruction_data
+                .sequence
+                .is_some_and(|sequence| sequence != escrow.sequence)
+            {
+                return Err(EscrowError::SequenceMismatch.into());
+            }
+            // Auction prices move with the clock; the taker must cap what they pay
+            if escrow.is_auction() && instruction_data.max_pay.is_none() {
+                return Err(ProgramError::InvalidInstructionData);
+            }
+        }
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
     }
 }
 
@@ -101,11 +279,21 @@ impl<'a> Take<'a> {
     pub const DISCRIMINATOR: u8 = 1;
 
     pub fn process(&mut self) -> ProgramResult {
-        let (seed, bump, receive) = {
+        let (seed, bump, remaining, take_amount, pay_amount) = {
             let escrow_data = self.accounts.escrow.try_borrow()?;
             let escrow = Escrow::load(escrow_data.deref())?;
-            (escrow.seed, escrow.bump[0], escrow.receive)
+            let take_amount = self.instruction_data.amount.unwrap_or(escrow.remaining);
+            let pay_amount = escrow.receive_for(take_amount, Clock::get()?.unix_timestamp)?;
+            if self
+                .instruction_data
+                .max_pay
+                .is_some_and(|max_pay| pay_amount > max_pay)
+            {
+                return Err(EscrowError::PriceExceeded.into());
+            }
+            (escrow.seed, escrow.bump[0], escrow.remaining, take_amount, pay_amount)
         };
+        let full_fill = take_amount == remaining;
 
         let seed_bytes = seed.to_le_bytes();
         let bump_array = [bump];
@@ -128,7 +316,7 @@ impl<'a> Take<'a> {
             wallet: self.accounts.maker,
             mint: self.accounts.mint_b,
             system_program: self.accounts.system_program,
-            token_program: self.accounts.token_program,
+            token_program: self.accounts.token_program_b,
         }
         .invoke()?;
 
@@ -143,40 +331,60 @@ impl<'a> Take<'a> {
         }
         .invoke()?;
 
-        // 3. Transfer token B from taker to maker
-        TransferChecked {
-            from: self.accounts.taker_ata_b,
-            mint: self.accounts.mint_b,
-            to: self.accounts.maker_ata_b,
-            authority: self.accounts.taker,
-            amount: receive,
-            decimals: mint_b_decimals,
-        }
-        .invoke()?;
+        // 3. Transfer token B from taker to maker, grossed up so the maker nets
+        // `pay_amount` after any transfer fee (escrow PDA as delegate under CPI guard)
+        transfer_from_user(
+            TransferChecked {
+                token_program: self.accounts.token_program_b,
+                from: self.accounts.taker_ata_b,
+                mint: self.accounts.mint_b,
+                to: self.accounts.maker_ata_b,
+                authority: self.accounts.taker,
+                amount: gross_amount(self.accounts.mint_b, pay_amount)?,
+                decimals: mint_b_decimals,
+            },
+            self.accounts.escrow,
+            &signers,
+        )?;
 
-        // 4. Transfer token A from vault to taker
+        // 4. Transfer token A from vault to taker (the whole vault on the final fill);
+        // the taker receives it net of any transfer fee on mint A
         TransferChecked {
+            token_program: self.accounts.token_program,
             from: self.accounts.vault,
             mint: self.accounts.mint_a,
             to: self.accounts.taker_ata_a,
             authority: self.accounts.escrow,
-            amount: vault_amount,
+            amount: if full_fill { vault_amount } else { take_amount },
             decimals: mint_a_decimals,
         }
         .invoke_signed(&signers)?;
 
-        // 5. Close vault, send lamports to maker
-        CloseAccount {
-            account: self.accounts.vault,
-            destination: self.accounts.maker,
-            authority: self.accounts.escrow,
+        // Partial fill: keep the escrow open with the rest of the offer
+        if !full_fill {
+            let mut escrow_data = self.accounts.escrow.try_borrow_mut()?;
+            let escrow = Escrow::load_mut(&mut escrow_data)?;
+            escrow.record_fill(take_amount, pay_amount)?;
+            return Ok(());
         }
-        .invoke_signed(&signers)?;
 
-        // 6. Transfer escrow rent to maker (direct lamport manipulation - avoids Assign CPI)
-        let lamports = self.accounts.escrow.lamports();
-        self.accounts.maker.set_lamports(self.accounts.maker.lamports() + lamports);
-        self.accounts.escrow.set_lamports(0);
+        // 5. Close vault, send lamports to the rent payer
+        close_vault(
+            self.accounts.token_program,
+            self.accounts.mint_a,
+            self.accounts.vault,
+            self.accounts.rent_payer,
+            self.accounts.escrow,
+            &signers,
+        )?;
+
+        // 6. Return escrow rent to the rent payer and close it (direct lamport manipulation - avoids Assign CPI)
+        close_program_account(self.accounts.escrow, self.accounts.rent_payer)?;
+
+        // 7. Drop the closed escrow from the order book
+        if let Some(page) = self.accounts.book_page {
+            remove_from_book(page, self.accounts.escrow.address())?;
+        }
 
         Ok(())
     }
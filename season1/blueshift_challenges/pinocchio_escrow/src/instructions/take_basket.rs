@@ -0,0 +1,224 @@
+use core::ops::Deref;
+use pinocchio::{
+    cpi::{Seed, Signer},
+    error::ProgramError,
+    AccountView, ProgramResult,
+};
+use pinocchio_associated_token_account::instructions::CreateIdempotent;
+
+use crate::errors::EscrowError;
+use crate::instructions::helpers::{
+    check_program_id, check_program_pda, check_vault, close_program_account, get_mint_decimals,
+    get_token_account_amount, get_token_account_owner,
+};
+use crate::instructions::make_basket::BASKET_SEED;
+use crate::instructions::token_interface::{
+    check_token_program, close_vault, gross_amount, transfer_from_user, TransferChecked,
+};
+use crate::state::Basket;
+
+/// Accounts per deposit leg: mint (writable for transfer-fee mints), vault, taker_ata, token_program
+pub const TAKE_DEPOSIT_LEG_ACCOUNTS: usize = 4;
+/// Accounts per ask leg: mint, taker_ata, maker_ata, token_program
+pub const TAKE_ASK_LEG_ACCOUNTS: usize = 4;
+
+pub struct TakeBasketAccounts<'a> {
+    pub taker: &'a AccountView,
+    pub maker: &'a AccountView,
+    pub basket: &'a AccountView,
+    pub associated_token_program: &'a AccountView,
+    pub system_program: &'a AccountView,
+    /// One group of [mint, vault, taker_ata, token_program] per deposit, in basket order
+    pub deposit_legs: &'a [AccountView],
+    /// One group of [mint, taker_ata, maker_ata, token_program] per ask, in basket order
+    pub ask_legs: &'a [AccountView],
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for TakeBasketAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let [taker, maker, basket, associated_token_program, system_program, legs @ ..] =
+            accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        if !taker.is_signer() {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        let basket_data = basket.try_borrow()?;
+        let basket_state = Basket::load(basket_data.deref())?;
+
+        if basket_state.maker != *maker.address() {
+            return Err(EscrowError::InvalidMaker.into());
+        }
+
+        // The basket must be this program's PDA for (maker, seed)
+        check_program_pda(
+            basket,
+            &[
+                BASKET_SEED,
+                maker.address().as_ref(),
+                &basket_state.seed.to_le_bytes(),
+                &basket_state.bump,
+            ],
+        )?;
+        check_program_id(associated_token_program, &pinocchio_associated_token_account::ID)?;
+        check_program_id(system_program, &pinocchio_system::ID)?;
+
+        let deposit_len = basket_state.deposits().len() * TAKE_DEPOSIT_LEG_ACCOUNTS;
+        if legs.len() != deposit_len + basket_state.asks().len() * TAKE_ASK_LEG_ACCOUNTS {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        }
+        let (deposit_legs, ask_legs) = legs.split_at(deposit_len);
+
+        for (leg, deposit) in deposit_legs
+            .chunks_exact(TAKE_DEPOSIT_LEG_ACCOUNTS)
+            .zip(basket_state.deposits())
+        {
+            let [mint, vault, _taker_ata, token_program] = leg else {
+                unreachable!()
+            };
+            if deposit.mint != *mint.address() {
+                return Err(EscrowError::InvalidMintA.into());
+            }
+            check_token_program(mint, token_program)?;
+            check_vault(vault, basket, mint, token_program)?;
+        }
+        for (leg, ask) in ask_legs.chunks_exact(TAKE_ASK_LEG_ACCOUNTS).zip(basket_state.asks()) {
+            let [mint, taker_ata, _maker_ata, token_program] = leg else {
+                unreachable!()
+            };
+            if ask.mint != *mint.address() {
+                return Err(EscrowError::InvalidMintB.into());
+            }
+            check_token_program(mint, token_program)?;
+            if get_token_account_owner(taker_ata)? != *taker.address() {
+                return Err(ProgramError::InvalidAccountOwner);
+            }
+        }
+
+        Ok(Self {
+            taker,
+            maker,
+            basket,
+            associated_token_program,
+            system_program,
+            deposit_legs,
+            ask_legs,
+        })
+    }
+}
+
+pub struct TakeBasket<'a> {
+    pub accounts: TakeBasketAccounts<'a>,
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for TakeBasket<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let accounts = TakeBasketAccounts::try_from(accounts)?;
+        Ok(Self { accounts })
+    }
+}
+
+impl<'a> TakeBasket<'a> {
+    pub const DISCRIMINATOR: u8 = 5;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let (seed, bump) = {
+            let basket_data = self.accounts.basket.try_borrow()?;
+            let basket = Basket::load(basket_data.deref())?;
+            (basket.seed, basket.bump[0])
+        };
+
+        let seed_bytes = seed.to_le_bytes();
+        let bump_array = [bump];
+        let seeds = [
+            Seed::from(BASKET_SEED),
+            Seed::from(self.accounts.maker.address().as_ref()),
+            Seed::from(&seed_bytes[..]),
+            Seed::from(&bump_array[..]),
+        ];
+        let signers = [Signer::from(&seeds)];
+
+        // 1. Pay every asked mint to the maker, grossed up for transfer fees
+        for (i, leg) in self.accounts.ask_legs.chunks_exact(TAKE_ASK_LEG_ACCOUNTS).enumerate() {
+            let [mint, taker_ata, maker_ata, token_program] = leg else {
+                unreachable!()
+            };
+            let amount = {
+                let basket_data = self.accounts.basket.try_borrow()?;
+                Basket::load(basket_data.deref())?.asks()[i].amount
+            };
+
+            CreateIdempotent {
+                funding_account: self.accounts.taker,
+                account: maker_ata,
+                wallet: self.accounts.maker,
+                mint,
+                system_program: self.accounts.system_program,
+                token_program,
+            }
+            .invoke()?;
+
+            transfer_from_user(
+                TransferChecked {
+                    token_program,
+                    from: taker_ata,
+                    mint,
+                    to: maker_ata,
+                    authority: self.accounts.taker,
+                    amount: gross_amount(mint, amount)?,
+                    decimals: get_mint_decimals(mint)?,
+                },
+                self.accounts.basket,
+                &signers,
+            )?;
+        }
+
+        // 2. Empty and close every vault; vault rent goes to the maker
+        for leg in self.accounts.deposit_legs.chunks_exact(TAKE_DEPOSIT_LEG_ACCOUNTS) {
+            let [mint, vault, taker_ata, token_program] = leg else {
+                unreachable!()
+            };
+
+            CreateIdempotent {
+                funding_account: self.accounts.taker,
+                account: taker_ata,
+                wallet: self.accounts.taker,
+                mint,
+                system_program: self.accounts.system_program,
+                token_program,
+            }
+            .invoke()?;
+
+            TransferChecked {
+                token_program,
+                from: vault,
+                mint,
+                to: taker_ata,
+                authority: self.accounts.basket,
+                amount: get_token_account_amount(vault)?,
+                decimals: get_mint_decimals(mint)?,
+            }
+            .invoke_signed(&signers)?;
+
+            close_vault(
+                token_program,
+                mint,
+                vault,
+                self.accounts.maker,
+                self.accounts.basket,
+                &signers,
+            )?;
+        }
+
+        // 3. Return basket rent to maker and close it (direct lamport manipulation - avoids Assign CPI)
+        close_program_account(self.accounts.basket, self.accounts.maker)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,327 @@
+//! Token program interface: the same instructions work for SPL Token and
+//! Token-2022 mints, with the program picked from the mint's owner.
+
+use pinocchio::{
+    cpi::{invoke_signed, Signer},
+    error::ProgramError,
+    instruction::{InstructionAccount, InstructionView},
+    sysvars::{clock::Clock, Sysvar},
+    AccountView, Address, ProgramResult,
+};
+use pinocchio_token::ID as TOKEN_ID;
+
+use crate::errors::EscrowError;
+
+/// Token-2022 program: TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb
+pub const TOKEN_2022_ID: Address = Address::new_from_array([
+    0x06, 0xdd, 0xf6, 0xe1, 0xee, 0x75, 0x8f, 0xde,
+    0x18, 0x42, 0x5d, 0xbc, 0xe4, 0x6c, 0xcd, 0xda,
+    0xb6, 0x1a, 0xfc, 0x4d, 0x83, 0xb9, 0x0d, 0x27,
+    0xfe, 0xbd, 0xf9, 0x28, 0xd8, 0xa1, 0x8b, 0xfc,
+]);
+
+/// Token-2022 layout: base account (mints padded) to 165 bytes, then the
+/// account type byte, then TLV extensions (type u16 + length u16 + value)
+const ACCOUNT_TYPE_OFFSET: usize = 165;
+const EXTENSIONS_OFFSET: usize = 166;
+const ACCOUNT_TYPE_MINT: u8 = 1;
+const ACCOUNT_TYPE_ACCOUNT: u8 = 2;
+
+const EXTENSION_TRANSFER_FEE_CONFIG: u16 = 1;
+const EXTENSION_NON_TRANSFERABLE: u16 = 9;
+const EXTENSION_CPI_GUARD: u16 = 11;
+const EXTENSION_PERMANENT_DELEGATE: u16 = 12;
+const EXTENSION_TRANSFER_HOOK: u16 = 14;
+
+/// TransferFeeConfig: config authority(32) + withdraw authority(32) + withheld(8)
+/// + older fee(18) + newer fee(18); each fee is epoch(8) + maximum_fee(8) + bps(2)
+const TRANSFER_FEE_OLDER_OFFSET: usize = 72;
+const TRANSFER_FEE_NEWER_OFFSET: usize = 90;
+const TRANSFER_FEE_LEN: usize = 18;
+const MAX_FEE_BASIS_POINTS: u64 = 10_000;
+
+/// Check that `token_program` owns `mint` and is SPL Token or Token-2022
+pub fn check_token_program(mint: &AccountView, token_program: &AccountView) -> ProgramResult {
+    let program_id = token_program.address();
+    if program_id != &TOKEN_ID && program_id != &TOKEN_2022_ID {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    if !mint.owned_by(program_id) {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    Ok(())
+}
+
+/// Check that `mint` is owned by SPL Token or Token-2022 (its program isn't passed)
+pub fn check_token_mint(mint: &AccountView) -> ProgramResult {
+    if !mint.owned_by(&TOKEN_ID) && !mint.owned_by(&TOKEN_2022_ID) {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    Ok(())
+}
+
+/// Find the value of a Token-2022 extension; `None` for legacy accounts
+fn find_extension(data: &[u8], account_type: u8, extension: u16) -> Result<Option<&[u8]>, ProgramError> {
+    if data.len() <= ACCOUNT_TYPE_OFFSET {
+        return Ok(None);
+    }
+    if data[ACCOUNT_TYPE_OFFSET] != account_type {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let mut offset = EXTENSIONS_OFFSET;
+    while offset + 4 <= data.len() {
+        let ext_type = u16::from_le_bytes([data[offset], data[offset + 1]]);
+        let ext_len = u16::from_le_bytes([data[offset + 2], data[offset + 3]]) as usize;
+        let start = offset + 4;
+        let end = start
+            .checked_add(ext_len)
+            .filter(|end| *end <= data.len())
+            .ok_or(ProgramError::InvalidAccountData)?;
+        // Type 0 marks the unused tail of the account
+        if ext_type == 0 {
+            break;
+        }
+        if ext_type == extension {
+            return Ok(Some(&data[start..end]));
+        }
+        offset = end;
+    }
+
+    Ok(None)
+}
+
+/// Reject mint extensions the escrow cannot honour: non-transferable tokens,
+/// transfer hooks (need extra accounts) and permanent delegates (can drain the vault)
+pub fn check_mint_extensions(mint: &AccountView) -> ProgramResult {
+    let data = mint.try_borrow()?;
+    if find_extension(&data, ACCOUNT_TYPE_MINT, EXTENSION_NON_TRANSFERABLE)?.is_some()
+        || find_extension(&data, ACCOUNT_TYPE_MINT, EXTENSION_PERMANENT_DELEGATE)?.is_some()
+    {
+        return Err(EscrowError::UnsupportedMint.into());
+    }
+    // TransferHook: authority(32) + program_id(32); an unset program id is harmless
+    if let Some(hook) = find_extension(&data, ACCOUNT_TYPE_MINT, EXTENSION_TRANSFER_HOOK)? {
+        if hook.len() < 64 || hook[32..64].iter().any(|b| *b != 0) {
+            return Err(EscrowError::UnsupportedMint.into());
+        }
+    }
+    Ok(())
+}
+
+/// Whether the mint has the transfer fee extension
+pub fn has_transfer_fee(mint: &AccountView) -> Result<bool, ProgramError> {
+    let data = mint.try_borrow()?;
+    Ok(find_extension(&data, ACCOUNT_TYPE_MINT, EXTENSION_TRANSFER_FEE_CONFIG)?.is_some())
+}
+
+/// (maximum_fee, basis_points) in effect for the current epoch; (0, 0) without transfer fees
+fn current_transfer_fee(mint: &AccountView) -> Result<(u64, u64), ProgramError> {
+    let data = mint.try_borrow()?;
+    let Some(config) = find_extension(&data, ACCOUNT_TYPE_MINT, EXTENSION_TRANSFER_FEE_CONFIG)?
+    else {
+        return Ok((0, 0));
+    };
+    if config.len() < TRANSFER_FEE_NEWER_OFFSET + TRANSFER_FEE_LEN {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let newer = &config[TRANSFER_FEE_NEWER_OFFSET..TRANSFER_FEE_NEWER_OFFSET + TRANSFER_FEE_LEN];
+    let newer_epoch = u64::from_le_bytes(newer[0..8].try_into().unwrap());
+    let fee = if Clock::get()?.epoch >= newer_epoch {
+        newer
+    } else {
+        &config[TRANSFER_FEE_OLDER_OFFSET..TRANSFER_FEE_OLDER_OFFSET + TRANSFER_FEE_LEN]
+    };
+
+    let maximum_fee = u64::from_le_bytes(fee[8..16].try_into().unwrap());
+    let basis_points = u16::from_le_bytes(fee[16..18].try_into().unwrap()) as u64;
+    Ok((maximum_fee, basis_points))
+}
+
+/// Fee withheld by Token-2022 on a transfer of `amount`: ceil(amount * bps / 10000), capped
+fn fee_for(amount: u64, maximum_fee: u64, basis_points: u64) -> u64 {
+    let fee = (amount as u128 * basis_points as u128).div_ceil(MAX_FEE_BASIS_POINTS as u128);
+    (fee as u64).min(maximum_fee)
+}
+
+/// Smallest amount to send so that at least `net` arrives at the destination
+pub fn gross_amount(mint: &AccountView, net: u64) -> Result<u64, ProgramError> {
+    let (maximum_fee, basis_points) = current_transfer_fee(mint)?;
+    if basis_points == 0 || maximum_fee == 0 {
+        return Ok(net);
+    }
+
+    let mut gross = if basis_points >= MAX_FEE_BASIS_POINTS {
+        net.checked_add(maximum_fee)
+            .ok_or(ProgramError::ArithmeticOverflow)?
+    } else {
+        let raw = (net as u128 * MAX_FEE_BASIS_POINTS as u128)
+            .div_ceil((MAX_FEE_BASIS_POINTS - basis_points) as u128);
+        let capped = net as u128 + maximum_fee as u128;
+        u64::try_from(raw.min(capped)).map_err(|_| ProgramError::ArithmeticOverflow)?
+    };
+    // Absorb rounding of the per-transfer ceil
+    while gross - fee_for(gross, maximum_fee, basis_points) < net {
+        gross = gross
+            .checked_add(1)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+    }
+    Ok(gross)
+}
+
+/// Whether the token account has CPI guard enabled (owner can't sign transfers via CPI)
+pub fn cpi_guard_enabled(token_account: &AccountView) -> Result<bool, ProgramError> {
+    let data = token_account.try_borrow()?;
+    Ok(find_extension(&data, ACCOUNT_TYPE_ACCOUNT, EXTENSION_CPI_GUARD)?
+        .map(|guard| guard.first().is_some_and(|lock| *lock != 0))
+        .unwrap_or(false))
+}
+
+/// TransferChecked on the given token program
+pub struct TransferChecked<'a> {
+    pub token_program: &'a AccountView,
+    pub from: &'a AccountView,
+    pub mint: &'a AccountView,
+    pub to: &'a AccountView,
+    pub authority: &'a AccountView,
+    pub amount: u64,
+    pub decimals: u8,
+}
+
+impl TransferChecked<'_> {
+    #[inline(always)]
+    pub fn invoke(&self) -> ProgramResult {
+        self.invoke_signed(&[])
+    }
+
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        let instruction_accounts = [
+            InstructionAccount::writable(self.from.address()),
+            InstructionAccount::readonly(self.mint.address()),
+            InstructionAccount::writable(self.to.address()),
+            InstructionAccount::readonly_signer(self.authority.address()),
+        ];
+
+        // discriminator(1) = 12 + amount(8) + decimals(1)
+        let mut instruction_data = [0u8; 10];
+        instruction_data[0] = 12;
+        instruction_data[1..9].copy_from_slice(&self.amount.to_le_bytes());
+        instruction_data[9] = self.decimals;
+
+        let instruction = InstructionView {
+            program_id: self.token_program.address(),
+            accounts: &instruction_accounts,
+            data: &instruction_data,
+        };
+
+        invoke_signed(
+            &instruction,
+            &[self.from, self.mint, self.to, self.authority],
+            signers,
+        )
+    }
+}
+
+/// Transfer out of a user's token account. The owner signs normally; with CPI
+/// guard on the source the owner can't sign a CPI transfer, so `delegate` (a
+/// PDA the owner approved earlier in the transaction) moves the tokens instead.
+pub fn transfer_from_user(
+    transfer: TransferChecked,
+    delegate: &AccountView,
+    signers: &[Signer],
+) -> ProgramResult {
+    if cpi_guard_enabled(transfer.from)? {
+        TransferChecked {
+            authority: delegate,
+            ..transfer
+        }
+        .invoke_signed(signers)
+    } else {
+        transfer.invoke()
+    }
+}
+
+/// CloseAccount on the given token program
+pub struct CloseAccount<'a> {
+    pub token_program: &'a AccountView,
+    pub account: &'a AccountView,
+    pub destination: &'a AccountView,
+    pub authority: &'a AccountView,
+}
+
+impl CloseAccount<'_> {
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        let instruction_accounts = [
+            InstructionAccount::writable(self.account.address()),
+            InstructionAccount::writable(self.destination.address()),
+            InstructionAccount::readonly_signer(self.authority.address()),
+        ];
+
+        let instruction = InstructionView {
+            program_id: self.token_program.address(),
+            accounts: &instruction_accounts,
+            data: &[9],
+        };
+
+        invoke_signed(
+            &instruction,
+            &[self.account, self.destination, self.authority],
+            signers,
+        )
+    }
+}
+
+/// Token-2022 HarvestWithheldTokensToMint (permissionless): moves transfer fees
+/// withheld in `source` to the mint so the account can be closed
+pub struct HarvestWithheldTokensToMint<'a> {
+    pub token_program: &'a AccountView,
+    pub mint: &'a AccountView,
+    pub source: &'a AccountView,
+}
+
+impl HarvestWithheldTokensToMint<'_> {
+    pub fn invoke(&self) -> ProgramResult {
+        let instruction_accounts = [
+            InstructionAccount::writable(self.mint.address()),
+            InstructionAccount::writable(self.source.address()),
+        ];
+
+        // TransferFeeExtension(26) + HarvestWithheldTokensToMint(4)
+        let instruction = InstructionView {
+            program_id: self.token_program.address(),
+            accounts: &instruction_accounts,
+            data: &[26, 4],
+        };
+
+        invoke_signed(&instruction, &[self.mint, self.source], &[])
+    }
+}
+
+/// Close a vault that was emptied by a transfer, harvesting withheld
+/// transfer fees first when the mint charges them
+pub fn close_vault(
+    token_program: &AccountView,
+    mint: &AccountView,
+    vault: &AccountView,
+    destination: &AccountView,
+    authority: &AccountView,
+    signers: &[Signer],
+) -> ProgramResult {
+    if has_transfer_fee(mint)? {
+        HarvestWithheldTokensToMint {
+            token_program,
+            mint,
+            source: vault,
+        }
+        .invoke()?;
+    }
+
+    CloseAccount {
+        token_program,
+        account: vault,
+        destination,
+        authority,
+    }
+    .invoke_signed(signers)
+}
@@ -1,10 +1,9 @@
 #![no_std]
 
-use pinocchio::{
-    entrypoint, error::ProgramError, nostd_panic_handler, AccountView, Address, ProgramResult,
-};
+use pinocchio::{error::ProgramError, nostd_panic_handler, AccountView, Address, ProgramResult};
 
-entrypoint!(process_instruction);
+#[cfg(not(feature = "no-entrypoint"))]
+pinocchio::entrypoint!(process_instruction);
 nostd_panic_handler!();
 
 pub mod errors;
@@ -21,6 +20,7 @@ pub const ID: Address = Address::new_from_array([
     0x8e, 0xf8, 0xaf, 0x70, 0x47, 0xdc, 0x11, 0xf7,
 ]);
 
+#[cfg_attr(feature = "no-entrypoint", allow(dead_code))]
 fn process_instruction(
     _program_id: &Address,
     accounts: &[AccountView],
@@ -28,8 +28,15 @@ fn process_instruction(
 ) -> ProgramResult {
     match instruction_data.split_first() {
         Some((&Make::DISCRIMINATOR, data)) => Make::try_from((data, accounts))?.process(),
-        Some((&Take::DISCRIMINATOR, _)) => Take::try_from(accounts)?.process(),
+        Some((&Take::DISCRIMINATOR, data)) => Take::try_from((data, accounts))?.process(),
         Some((&Refund::DISCRIMINATOR, _)) => Refund::try_from(accounts)?.process(),
+        Some((&RefundExpired::DISCRIMINATOR, _)) => RefundExpired::try_from(accounts)?.process(),
+        Some((&MakeBasket::DISCRIMINATOR, data)) => {
+            MakeBasket::try_from((data, accounts))?.process()
+        }
+        Some((&TakeBasket::DISCRIMINATOR, _)) => TakeBasket::try_from(accounts)?.process(),
+        Some((&RefundBasket::DISCRIMINATOR, _)) => RefundBasket::try_from(accounts)?.process(),
+        Some((&Amend::DISCRIMINATOR, data)) => Amend::try_from((data, accounts))?.process(),
         _ => Err(ProgramError::InvalidInstructionData),
     }
 }
\ No newline at end of file
@@ -3,30 +3,104 @@ use pinocchio::{
     error::ProgramError,
     Address,
 };
+use solana_sha256_hasher::{hash, hashv};
+
+use crate::errors::EscrowError;
 
 #[repr(C)]
 pub struct Escrow {
+    /// `Escrow::DISCRIMINATOR` once initialized
+    pub discriminator: u8,
+    pub bump: [u8; 1],
+    /// Who may take: `TAKER_ANY`, `TAKER_ADDRESS` or `TAKER_MERKLE`
+    pub taker_mode: u8,
+    /// Price curve: `AUCTION_NONE`, `AUCTION_LINEAR` or `AUCTION_EXPONENTIAL`
+    pub auction_mode: u8,
+    /// Order-book page listing this escrow; `NO_BOOK` if it isn't indexed
+    pub book_page: u32,
     pub seed: u64,
     pub maker: Address,
     pub mint_a: Address,
     pub mint_b: Address,
+    /// Token B still owed for the `remaining` token A (decreases with partial fills)
     pub receive: u64,
-    pub bump: [u8; 1],
+    /// Unix timestamp after which the offer can no longer be taken; 0 = never expires
+    pub expiry: i64,
+    /// Token A still offered in the vault
+    pub remaining: u64,
+    /// Bumped by every `Amend`; takers can pin it to avoid front-run price changes
+    pub sequence: u64,
+    /// Designated taker (`TAKER_ADDRESS`) or merkle root of allowed takers (`TAKER_MERKLE`)
+    pub taker: Address,
+    /// Dutch auctions: price of the remaining offer at `auction_end`;
+    /// `receive` is the price at `auction_start`
+    pub end_receive: u64,
+    pub auction_start: i64,
+    pub auction_end: i64,
+    /// Who funded the escrow and vault rent and gets it back on close
+    pub rent_payer: Address,
 }
 
 impl Escrow {
-    pub const LEN: usize = size_of::<u64>()
+    pub const LEN: usize = size_of::<u8>()
+        + size_of::<[u8; 1]>()
+        + size_of::<u8>()
+        + size_of::<u8>()
+        + size_of::<u32>()
+        + size_of::<u64>()
+        + size_of::<Address>()
         + size_of::<Address>()
         + size_of::<Address>()
+        + size_of::<u64>()
+        + size_of::<i64>()
+        + size_of::<u64>()
+        + size_of::<u64>()
         + size_of::<Address>()
         + size_of::<u64>()
-        + size_of::<[u8; 1]>();
+        + size_of::<i64>()
+        + size_of::<i64>()
+        + size_of::<Address>();
+
+    /// First data byte of an initialized escrow (same value as anchor_escrow's `Escrow`)
+    pub const DISCRIMINATOR: u8 = 1;
+
+    /// Anyone may take the escrow
+    pub const TAKER_ANY: u8 = 0;
+    /// Only `taker` may take the escrow
+    pub const TAKER_ADDRESS: u8 = 1;
+    /// Takers proving membership in the merkle tree with root `taker`
+    pub const TAKER_MERKLE: u8 = 2;
+
+    /// Fixed price: `receive`
+    pub const AUCTION_NONE: u8 = 0;
+    /// Price falls linearly from `receive` to `end_receive`
+    pub const AUCTION_LINEAR: u8 = 1;
+    /// Price halves its distance to `end_receive` every `1 / AUCTION_HALVINGS`
+    /// of the auction (linear between halvings), ending exactly at `end_receive`
+    pub const AUCTION_EXPONENTIAL: u8 = 2;
+    pub const AUCTION_HALVINGS: u32 = 8;
+
+    /// `book_page` of escrows that aren't listed in an order book
+    pub const NO_BOOK: u32 = u32::MAX;
 
+    /// Stamp the discriminator on freshly created (zeroed) account data
     #[inline(always)]
-    pub fn load_mut(bytes: &mut [u8]) -> Result<&mut Self, ProgramError> {
+    pub fn init(bytes: &mut [u8]) -> Result<&mut Self, ProgramError> {
         if bytes.len() != Escrow::LEN {
             return Err(ProgramError::InvalidAccountData);
         }
+        if bytes[0] != 0 {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+        bytes[0] = Escrow::DISCRIMINATOR;
+        Self::load_mut(bytes)
+    }
+
+    #[inline(always)]
+    pub fn load_mut(bytes: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        if bytes.len() != Escrow::LEN || bytes[0] != Escrow::DISCRIMINATOR {
+            return Err(ProgramError::InvalidAccountData);
+        }
         Ok(unsafe {
             &mut *core::mem::transmute::<*mut u8, *mut Self>(bytes.as_mut_ptr())
         })
@@ -34,7 +108,7 @@ impl Escrow {
 
     #[inline(always)]
     pub fn load(bytes: &[u8]) -> Result<&Self, ProgramError> {
-        if bytes.len() != Escrow::LEN {
+        if bytes.len() != Escrow::LEN || bytes[0] != Escrow::DISCRIMINATOR {
             return Err(ProgramError::InvalidAccountData);
         }
         Ok(unsafe { &*core::mem::transmute::<*const u8, *const Self>(bytes.as_ptr()) })
@@ -65,6 +139,45 @@ impl Escrow {
         self.receive = receive;
     }
 
+    #[inline(always)]
+    pub fn set_expiry(&mut self, expiry: i64) {
+        self.expiry = expiry;
+    }
+
+    #[inline(always)]
+    pub fn set_remaining(&mut self, remaining: u64) {
+        self.remaining = remaining;
+    }
+
+    #[inline(always)]
+    pub fn set_sequence(&mut self, sequence: u64) {
+        self.sequence = sequence;
+    }
+
+    #[inline(always)]
+    pub fn set_taker(&mut self, taker_mode: u8, taker: Address) {
+        self.taker_mode = taker_mode;
+        self.taker = taker;
+    }
+
+    #[inline(always)]
+    pub fn set_auction(&mut self, auction_mode: u8, end_receive: u64, start: i64, end: i64) {
+        self.auction_mode = auction_mode;
+        self.end_receive = end_receive;
+        self.auction_start = start;
+        self.auction_end = end;
+    }
+
+    #[inline(always)]
+    pub fn set_rent_payer(&mut self, rent_payer: Address) {
+        self.rent_payer = rent_payer;
+    }
+
+    #[inline(always)]
+    pub fn set_book_page(&mut self, book_page: u32) {
+        self.book_page = book_page;
+    }
+
     #[inline(always)]
     pub fn set_bump(&mut self, bump: [u8; 1]) {
         self.bump = bump;
@@ -87,4 +200,402 @@ impl Escrow {
         self.receive = receive;
         self.bump = bump;
     }
+
+    #[inline(always)]
+    pub fn has_expiry(&self) -> bool {
+        self.expiry != 0
+    }
+
+    #[inline(always)]
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.has_expiry() && now > self.expiry
+    }
+
+    /// Whether `taker` may take the escrow. `proof` is only used for merkle
+    /// escrows: concatenated 32-byte sibling hashes from leaf to root, where the
+    /// leaf is sha256(taker) and each parent is sha256 of the sorted pair.
+    pub fn is_allowed_taker(&self, taker: &Address, proof: &[u8]) -> bool {
+        match self.taker_mode {
+            Self::TAKER_ANY => true,
+            Self::TAKER_ADDRESS => self.taker == *taker,
+            Self::TAKER_MERKLE => {
+                if !proof.len().is_multiple_of(32) {
+                    return false;
+                }
+                let mut node = hash(taker.as_ref()).to_bytes();
+                for sibling in proof.chunks_exact(32) {
+                    node = if node.as_slice() <= sibling {
+                        hashv(&[&node, sibling]).to_bytes()
+                    } else {
+                        hashv(&[sibling, &node]).to_bytes()
+                    };
+                }
+                node == self.taker.to_bytes()
+            }
+            _ => false,
+        }
+    }
+
+    /// Rent was sponsored by someone other than the maker
+    #[inline(always)]
+    pub fn is_sponsored(&self) -> bool {
+        self.rent_payer != self.maker
+    }
+
+    #[inline(always)]
+    pub fn is_auction(&self) -> bool {
+        self.auction_mode != Self::AUCTION_NONE
+    }
+
+    /// Price of the whole remaining offer at `now`: `receive` for fixed-price
+    /// escrows, otherwise the auction curve clamped to `[end_receive, receive]`
+    pub fn receive_at(&self, now: i64) -> u64 {
+        if !self.is_auction() || now <= self.auction_start {
+            return self.receive;
+        }
+        if now >= self.auction_end {
+            return self.end_receive;
+        }
+
+        let elapsed = (now - self.auction_start) as u128;
+        let duration = (self.auction_end - self.auction_start) as u128;
+        let span = (self.receive - self.end_receive) as u128;
+
+        let above_end = match self.auction_mode {
+            Self::AUCTION_LINEAR => span - span * elapsed / duration,
+            _ => {
+                // span * 2^(-HALVINGS * elapsed / duration), interpolated linearly
+                // inside each halving, minus a linear tail so the curve meets
+                // `end_receive` at `auction_end` instead of jumping to it
+                let halvings = elapsed * Self::AUCTION_HALVINGS as u128;
+                let steps = (halvings / duration) as u32;
+                let within = halvings % duration;
+                let decayed = (span * (2 * duration - within) / (2 * duration)) >> steps;
+                let tail = (span >> Self::AUCTION_HALVINGS) * elapsed / duration;
+                decayed.saturating_sub(tail)
+            }
+        };
+
+        self.end_receive + above_end as u64
+    }
+
+    /// Token B the taker pays at `now` for `amount` token A: pro-rata of the
+    /// current price, rounded up so the maker never receives less than the offer.
+    /// A partial fill may not leave the rest of the offer priced at zero.
+    #[inline(always)]
+    pub fn receive_for(&self, amount: u64, now: i64) -> Result<u64, ProgramError> {
+        if amount == 0 || amount > self.remaining {
+            return Err(EscrowError::InvalidAmount.into());
+        }
+        let receive = self.receive_at(now);
+        if amount == self.remaining {
+            return Ok(receive);
+        }
+
+        let pay = Self::pro_rata(receive, amount, self.remaining)?;
+        if pay >= receive {
+            return Err(EscrowError::InvalidAmount.into());
+        }
+
+        Ok(pay)
+    }
+
+    /// Book a partial fill of `amount` token A paid with `pay` token B. Auctions
+    /// scale both ends of the curve to the rest of the offer, keeping its timing.
+    pub fn record_fill(&mut self, amount: u64, pay: u64) -> Result<(), ProgramError> {
+        let remaining = self
+            .remaining
+            .checked_sub(amount)
+            .ok_or(EscrowError::InvalidAmount)?;
+        if self.is_auction() {
+            self.receive = Self::pro_rata(self.receive, remaining, self.remaining)?;
+            self.end_receive = Self::pro_rata(self.end_receive, remaining, self.remaining)?;
+        } else {
+            self.receive -= pay;
+        }
+        self.remaining = remaining;
+        Ok(())
+    }
+
+    /// `price * part / whole`, rounded up
+    #[inline(always)]
+    fn pro_rata(price: u64, part: u64, whole: u64) -> Result<u64, ProgramError> {
+        let numerator = (part as u128)
+            .checked_mul(price as u128)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        Ok(numerator.div_ceil(whole as u128) as u64)
+    }
 }
+
+const _: () = assert!(Escrow::LEN == size_of::<Escrow>());
+
+/// One side of a basket trade: a mint and how much of it
+#[repr(C)]
+pub struct BasketLeg {
+    pub mint: Address,
+    pub amount: u64,
+}
+
+/// Bundle escrow: the maker deposits up to `MAX_DEPOSITS` mints (one vault
+/// ATA each) and asks for up to `MAX_ASKS` mints, settled in a single Take
+#[repr(C)]
+pub struct Basket {
+    /// `Basket::DISCRIMINATOR` once initialized
+    pub discriminator: u8,
+    pub deposit_count: u8,
+    pub ask_count: u8,
+    pub bump: [u8; 1],
+    /// Keeps the u64 fields 8-byte aligned
+    pub padding: [u8; 4],
+    pub seed: u64,
+    pub maker: Address,
+    /// Deposited mints and the amount held in each vault
+    pub deposits: [BasketLeg; Basket::MAX_DEPOSITS],
+    /// Asked mints and the amount the maker receives of each
+    pub asks: [BasketLeg; Basket::MAX_ASKS],
+}
+
+impl Basket {
+    pub const MAX_DEPOSITS: usize = 4;
+    pub const MAX_ASKS: usize = 4;
+
+    pub const LEN: usize = size_of::<u8>()
+        + size_of::<u8>()
+        + size_of::<u8>()
+        + size_of::<[u8; 1]>()
+        + size_of::<[u8; 4]>()
+        + size_of::<u64>()
+        + size_of::<Address>()
+        + size_of::<BasketLeg>() * Basket::MAX_DEPOSITS
+        + size_of::<BasketLeg>() * Basket::MAX_ASKS;
+
+    /// First data byte of an initialized basket (same value as anchor_escrow's `Basket`)
+    pub const DISCRIMINATOR: u8 = 2;
+
+    /// Stamp the discriminator on freshly created (zeroed) account data
+    #[inline(always)]
+    pub fn init(bytes: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        if bytes.len() != Basket::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        if bytes[0] != 0 {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+        bytes[0] = Basket::DISCRIMINATOR;
+        Self::load_mut(bytes)
+    }
+
+    #[inline(always)]
+    pub fn load_mut(bytes: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        if bytes.len() != Basket::LEN || bytes[0] != Basket::DISCRIMINATOR {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Ok(unsafe {
+            &mut *core::mem::transmute::<*mut u8, *mut Self>(bytes.as_mut_ptr())
+        })
+    }
+
+    #[inline(always)]
+    pub fn load(bytes: &[u8]) -> Result<&Self, ProgramError> {
+        if bytes.len() != Basket::LEN || bytes[0] != Basket::DISCRIMINATOR {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Ok(unsafe { &*core::mem::transmute::<*const u8, *const Self>(bytes.as_ptr()) })
+    }
+
+    #[inline(always)]
+    pub fn set_inner(&mut self, seed: u64, maker: Address, bump: [u8; 1]) {
+        self.seed = seed;
+        self.maker = maker;
+        self.deposit_count = 0;
+        self.ask_count = 0;
+        self.bump = bump;
+    }
+
+    #[inline(always)]
+    pub fn push_deposit(&mut self, mint: Address, amount: u64) -> Result<(), ProgramError> {
+        let leg = self
+            .deposits
+            .get_mut(self.deposit_count as usize)
+            .ok_or(ProgramError::InvalidArgument)?;
+        leg.mint = mint;
+        leg.amount = amount;
+        self.deposit_count += 1;
+        Ok(())
+    }
+
+    #[inline(always)]
+    pub fn push_ask(&mut self, mint: Address, amount: u64) -> Result<(), ProgramError> {
+        let leg = self
+            .asks
+            .get_mut(self.ask_count as usize)
+            .ok_or(ProgramError::InvalidArgument)?;
+        leg.mint = mint;
+        leg.amount = amount;
+        self.ask_count += 1;
+        Ok(())
+    }
+
+    #[inline(always)]
+    pub fn deposits(&self) -> &[BasketLeg] {
+        &self.deposits[..(self.deposit_count as usize).min(Self::MAX_DEPOSITS)]
+    }
+
+    #[inline(always)]
+    pub fn asks(&self) -> &[BasketLeg] {
+        &self.asks[..(self.ask_count as usize).min(Self::MAX_ASKS)]
+    }
+}
+
+const _: () = assert!(Basket::LEN == size_of::<Basket>());
+
+/// One open escrow listed in an order-book page
+#[repr(C)]
+pub struct BookEntry {
+    pub escrow: Address,
+    pub maker: Address,
+}
+
+/// Open escrows for one (mint_a, mint_b) pair. The header is followed by
+/// `capacity` entries, the first `count` of them in use; the account grows by
+/// realloc, `GROWTH` entries at a time, up to `MAX_ENTRIES`. Full pages are
+/// continued in the next `page` index.
+#[repr(C)]
+pub struct BookPage {
+    /// `BookPage::DISCRIMINATOR` once initialized
+    pub discriminator: u8,
+    pub bump: [u8; 1],
+    pub padding: [u8; 2],
+    pub page: u32,
+    pub count: u32,
+    pub capacity: u32,
+    pub mint_a: Address,
+    pub mint_b: Address,
+}
+
+impl BookPage {
+    pub const HEADER_LEN: usize = size_of::<u8>()
+        + size_of::<[u8; 1]>()
+        + size_of::<[u8; 2]>()
+        + size_of::<u32>()
+        + size_of::<u32>()
+        + size_of::<u32>()
+        + size_of::<Address>()
+        + size_of::<Address>();
+
+    /// First data byte of an initialized page
+    pub const DISCRIMINATOR: u8 = 3;
+
+    pub const INITIAL_CAPACITY: u32 = 8;
+    pub const GROWTH: u32 = 8;
+    /// Keeps a page under one account read and below the per-instruction realloc limit
+    pub const MAX_ENTRIES: u32 = 128;
+
+    /// Account size for `capacity` entries
+    #[inline(always)]
+    pub const fn space(capacity: u32) -> usize {
+        Self::HEADER_LEN + size_of::<BookEntry>() * capacity as usize
+    }
+
+    /// Stamp the discriminator on freshly created (zeroed) account data
+    #[inline(always)]
+    pub fn init(bytes: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        if bytes.len() < Self::HEADER_LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        if bytes[0] != 0 {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+        bytes[0] = Self::DISCRIMINATOR;
+        let capacity = (bytes.len() - Self::HEADER_LEN) / size_of::<BookEntry>();
+        let (page, _) = Self::load_mut(bytes)?;
+        page.capacity = capacity as u32;
+        Ok(page)
+    }
+
+    #[inline(always)]
+    pub fn load(bytes: &[u8]) -> Result<(&Self, &[BookEntry]), ProgramError> {
+        if bytes.len() < Self::HEADER_LEN || bytes[0] != Self::DISCRIMINATOR {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let (header, entries) = bytes.split_at(Self::HEADER_LEN);
+        let page = unsafe { &*core::mem::transmute::<*const u8, *const Self>(header.as_ptr()) };
+        if page.count > page.capacity
+            || entries.len() < size_of::<BookEntry>() * page.capacity as usize
+        {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let entries = unsafe {
+            core::slice::from_raw_parts(entries.as_ptr() as *const BookEntry, page.count as usize)
+        };
+        Ok((page, entries))
+    }
+
+    /// Header and all `capacity` entry slots
+    #[inline(always)]
+    pub fn load_mut(bytes: &mut [u8]) -> Result<(&mut Self, &mut [BookEntry]), ProgramError> {
+        if bytes.len() < Self::HEADER_LEN || bytes[0] != Self::DISCRIMINATOR {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let slots = (bytes.len() - Self::HEADER_LEN) / size_of::<BookEntry>();
+        let (header, entries) = bytes.split_at_mut(Self::HEADER_LEN);
+        let page = unsafe { &mut *core::mem::transmute::<*mut u8, *mut Self>(header.as_mut_ptr()) };
+        let entries = unsafe {
+            core::slice::from_raw_parts_mut(entries.as_mut_ptr() as *mut BookEntry, slots)
+        };
+        Ok((page, entries))
+    }
+
+    #[inline(always)]
+    pub fn set_inner(&mut self, page: u32, mint_a: Address, mint_b: Address, bump: [u8; 1]) {
+        self.page = page;
+        self.mint_a = mint_a;
+        self.mint_b = mint_b;
+        self.bump = bump;
+        self.count = 0;
+    }
+
+    #[inline(always)]
+    pub fn is_full(&self) -> bool {
+        self.count >= self.capacity
+    }
+
+    /// Append an entry; the caller grows the account first when the page is full
+    pub fn push(
+        &mut self,
+        entries: &mut [BookEntry],
+        escrow: Address,
+        maker: Address,
+    ) -> Result<(), ProgramError> {
+        if self.is_full() {
+            return Err(EscrowError::BookPageFull.into());
+        }
+        let entry = entries
+            .get_mut(self.count as usize)
+            .ok_or(ProgramError::InvalidAccountData)?;
+        entry.escrow = escrow;
+        entry.maker = maker;
+        self.count += 1;
+        Ok(())
+    }
+
+    /// Swap-remove the entry of `escrow`
+    pub fn remove(&mut self, entries: &mut [BookEntry], escrow: &Address) -> Result<(), ProgramError> {
+        let count = self.count as usize;
+        let index = entries[..count]
+            .iter()
+            .position(|entry| entry.escrow == *escrow)
+            .ok_or(EscrowError::NotInBook)?;
+        let last = count - 1;
+        if index != last {
+            entries[index].escrow = entries[last].escrow;
+            entries[index].maker = entries[last].maker;
+        }
+        entries[last].escrow = Address::default();
+        entries[last].maker = Address::default();
+        self.count -= 1;
+        Ok(())
+    }
+}
+
+const _: () = assert!(BookPage::HEADER_LEN == size_of::<BookPage>());
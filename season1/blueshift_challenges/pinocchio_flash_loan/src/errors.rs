@@ -0,0 +1,24 @@
+use pinocchio::error::ProgramError;
+
+/// Same variants and codes as anchor_flash_loan's `ProtocolError`
+#[repr(u32)]
+pub enum FlashLoanError {
+    InvalidIx = 6000,
+    InvalidInstructionIndex = 6001,
+    InvalidAmount = 6002,
+    NotEnoughFunds = 6003,
+    ProgramMismatch = 6004,
+    /// Borrow / Repay must be top-level instructions, not CPIs
+    InvalidProgram = 6005,
+    InvalidBorrowerAta = 6006,
+    InvalidProtocolAta = 6007,
+    MissingRepayIx = 6008,
+    MissingBorrowIx = 6009,
+    Overflow = 6010,
+}
+
+impl From<FlashLoanError> for ProgramError {
+    fn from(e: FlashLoanError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
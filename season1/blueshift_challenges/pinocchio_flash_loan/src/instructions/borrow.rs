@@ -0,0 +1,111 @@
+use core::mem::size_of;
+
+use pinocchio::{
+    cpi::{Seed, Signer},
+    error::ProgramError,
+    AccountView, ProgramResult,
+};
+use pinocchio_associated_token_account::instructions::CreateIdempotent;
+use pinocchio_token::{
+    instructions::TransferChecked,
+    state::{Mint, TokenAccount},
+};
+
+use crate::errors::FlashLoanError;
+use crate::instructions::helpers::{load_instructions, LoanAccounts, PROTOCOL_SEED};
+use crate::instructions::Repay;
+use crate::ID;
+
+pub struct BorrowInstructionData {
+    pub amount: u64,
+}
+
+impl<'a> TryFrom<&'a [u8]> for BorrowInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        if data.len() != size_of::<u64>() {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let amount = u64::from_le_bytes(data.try_into().unwrap());
+
+        if amount == 0 {
+            return Err(FlashLoanError::InvalidAmount.into());
+        }
+
+        Ok(Self { amount })
+    }
+}
+
+/// Lend `amount` from the protocol ATA. The next instruction of this program
+/// in the transaction must be a Repay on the same token accounts, so loans
+/// can't be nested or left open.
+pub struct Borrow<'a> {
+    pub accounts: LoanAccounts<'a>,
+    pub instruction_data: BorrowInstructionData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountView])> for Borrow<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountView])) -> Result<Self, Self::Error> {
+        let accounts = LoanAccounts::try_from(accounts)?;
+        let instruction_data = BorrowInstructionData::try_from(data)?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> Borrow<'a> {
+    pub const DISCRIMINATOR: u8 = 0;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let amount = self.instruction_data.amount;
+        if TokenAccount::from_account_view(self.accounts.protocol_ata)?.amount() < amount {
+            return Err(FlashLoanError::NotEnoughFunds.into());
+        }
+
+        // 1. Find the Repay: the first later instruction of ours must be it
+        {
+            let (instructions, current) = load_instructions(self.accounts.instructions)?;
+            let repay = (current + 1..instructions.num_instructions())
+                .filter_map(|index| instructions.load_instruction_at(index).ok())
+                .find(|ix| ix.get_program_id() == &ID);
+            if !repay.is_some_and(|ix| self.accounts.matches(&ix, Repay::DISCRIMINATOR)) {
+                return Err(FlashLoanError::MissingRepayIx.into());
+            }
+        }
+
+        // 2. Create the borrower ATA if needed
+        CreateIdempotent {
+            funding_account: self.accounts.borrower,
+            account: self.accounts.borrower_ata,
+            wallet: self.accounts.borrower,
+            mint: self.accounts.mint,
+            system_program: self.accounts.system_program,
+            token_program: self.accounts.token_program,
+        }
+        .invoke()?;
+
+        // 3. Lend, signed by the protocol PDA
+        let decimals = Mint::from_account_view(self.accounts.mint)?.decimals();
+        let seeds = [
+            Seed::from(PROTOCOL_SEED),
+            Seed::from(&self.accounts.bumps[..]),
+        ];
+
+        TransferChecked {
+            from: self.accounts.protocol_ata,
+            mint: self.accounts.mint,
+            to: self.accounts.borrower_ata,
+            authority: self.accounts.protocol,
+            amount,
+            decimals,
+        }
+        .invoke_signed(&[Signer::from(&seeds)])
+    }
+}
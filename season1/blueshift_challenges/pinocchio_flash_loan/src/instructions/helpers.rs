@@ -0,0 +1,137 @@
+use pinocchio::{
+    account::Ref,
+    error::ProgramError,
+    sysvars::instructions::{Instructions, IntrospectedInstruction},
+    AccountView, Address, ProgramResult,
+};
+use pinocchio_token::state::TokenAccount;
+
+use crate::errors::FlashLoanError;
+use crate::ID;
+
+pub const PROTOCOL_SEED: &[u8] = b"protocol";
+
+/// Loan fee in basis points (5%, as in anchor_flash_loan)
+pub const FEE_BPS: u64 = 500;
+const BPS_DENOMINATOR: u64 = 10_000;
+
+/// Borrowed view of the Instructions sysvar
+pub type InstructionsSysvar<'a> = Instructions<Ref<'a, [u8]>>;
+
+/// Position of the token accounts in Borrow / Repay, used to pair them up
+const BORROWER_ATA_INDEX: usize = 3;
+const PROTOCOL_ATA_INDEX: usize = 4;
+
+/// Accounts shared by Borrow and Repay, in anchor_flash_loan's `Loan` order
+pub struct LoanAccounts<'a> {
+    pub borrower: &'a AccountView,
+    pub protocol: &'a AccountView,
+    pub mint: &'a AccountView,
+    pub borrower_ata: &'a AccountView,
+    pub protocol_ata: &'a AccountView,
+    pub instructions: &'a AccountView,
+    pub token_program: &'a AccountView,
+    pub system_program: &'a AccountView,
+    pub bumps: [u8; 1],
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for LoanAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let [borrower, protocol, mint, borrower_ata, protocol_ata, instructions, token_program, associated_token_program, system_program] =
+            accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        if !borrower.is_signer() {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let (protocol_key, bump) = Address::find_program_address(&[PROTOCOL_SEED], &ID);
+        if protocol.address() != &protocol_key {
+            return Err(ProgramError::InvalidSeeds);
+        }
+
+        check_program_id(token_program, &pinocchio_token::ID)?;
+        check_program_id(associated_token_program, &pinocchio_associated_token_account::ID)?;
+        check_program_id(system_program, &pinocchio_system::ID)?;
+
+        {
+            let vault = TokenAccount::from_account_view(protocol_ata)
+                .map_err(|_| FlashLoanError::InvalidProtocolAta)?;
+            if vault.owner() != protocol.address() || vault.mint() != mint.address() {
+                return Err(FlashLoanError::InvalidProtocolAta.into());
+            }
+        }
+
+        let (ata, _) = Address::find_program_address(
+            &[
+                borrower.address().as_ref(),
+                pinocchio_token::ID.as_ref(),
+                mint.address().as_ref(),
+            ],
+            &pinocchio_associated_token_account::ID,
+        );
+        if borrower_ata.address() != &ata {
+            return Err(FlashLoanError::InvalidBorrowerAta.into());
+        }
+
+        Ok(Self {
+            borrower,
+            protocol,
+            mint,
+            borrower_ata,
+            protocol_ata,
+            instructions,
+            token_program,
+            system_program,
+            bumps: [bump],
+        })
+    }
+}
+
+impl LoanAccounts<'_> {
+    /// Whether `ix` is this program's `discriminator` instruction on the same
+    /// borrower and protocol token accounts
+    pub fn matches(&self, ix: &IntrospectedInstruction, discriminator: u8) -> bool {
+        if ix.get_program_id() != &ID || ix.get_instruction_data().first() != Some(&discriminator) {
+            return false;
+        }
+        let key_at = |index| ix.get_instruction_account_at(index).map(|account| account.key);
+        key_at(BORROWER_ATA_INDEX).is_ok_and(|key| &key == self.borrower_ata.address())
+            && key_at(PROTOCOL_ATA_INDEX).is_ok_and(|key| &key == self.protocol_ata.address())
+    }
+}
+
+/// Load the Instructions sysvar and return it with the current index, making
+/// sure the current instruction is ours: a CPI would see its caller's
+/// top-level instruction here and could not be paired reliably
+pub fn load_instructions(
+    instructions: &AccountView,
+) -> Result<(InstructionsSysvar<'_>, usize), ProgramError> {
+    let instructions = Instructions::try_from(instructions)?;
+    let current = instructions.load_current_index() as usize;
+    if instructions.load_instruction_at(current)?.get_program_id() != &ID {
+        return Err(FlashLoanError::InvalidProgram.into());
+    }
+    Ok((instructions, current))
+}
+
+/// Amount owed for a loan of `amount`: principal plus `FEE_BPS`
+pub fn repay_amount(amount: u64) -> Result<u64, ProgramError> {
+    let fee = (amount as u128)
+        .checked_mul(FEE_BPS as u128)
+        .map(|fee| fee / BPS_DENOMINATOR as u128)
+        .ok_or(FlashLoanError::Overflow)?;
+    u64::try_from(amount as u128 + fee).map_err(|_| FlashLoanError::Overflow.into())
+}
+
+/// Check that a program account is the expected program
+pub fn check_program_id(program: &AccountView, id: &Address) -> ProgramResult {
+    if program.address() != id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    Ok(())
+}
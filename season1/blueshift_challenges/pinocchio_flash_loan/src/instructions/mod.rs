@@ -0,0 +1,7 @@
+mod borrow;
+mod helpers;
+mod repay;
+
+pub use borrow::*;
+pub use helpers::{LoanAccounts, FEE_BPS, PROTOCOL_SEED};
+pub use repay::*;
@@ -0,0 +1,67 @@
+use pinocchio::{error::ProgramError, AccountView, ProgramResult};
+use pinocchio_token::{
+    instructions::TransferChecked,
+    state::{Mint, TokenAccount},
+};
+
+use crate::errors::FlashLoanError;
+use crate::instructions::helpers::{load_instructions, repay_amount, LoanAccounts};
+use crate::instructions::Borrow;
+use crate::ID;
+
+/// Pay back the loan opened by the closest earlier instruction of this
+/// program, which must be a Borrow on the same token accounts, plus the fee
+pub struct Repay<'a> {
+    pub accounts: LoanAccounts<'a>,
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for Repay<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let accounts = LoanAccounts::try_from(accounts)?;
+
+        Ok(Self { accounts })
+    }
+}
+
+impl<'a> Repay<'a> {
+    pub const DISCRIMINATOR: u8 = 1;
+
+    pub fn process(&mut self) -> ProgramResult {
+        // 1. Read the borrowed amount from the matching Borrow
+        let amount = {
+            let (instructions, current) = load_instructions(self.accounts.instructions)?;
+            let borrow = (0..current)
+                .rev()
+                .filter_map(|index| instructions.load_instruction_at(index).ok())
+                .find(|ix| ix.get_program_id() == &ID)
+                .filter(|ix| self.accounts.matches(ix, Borrow::DISCRIMINATOR))
+                .ok_or(FlashLoanError::MissingBorrowIx)?;
+
+            borrow
+                .get_instruction_data()
+                .get(1..9)
+                .and_then(|bytes| bytes.try_into().ok())
+                .map(u64::from_le_bytes)
+                .ok_or(FlashLoanError::InvalidAmount)?
+        };
+
+        // 2. Principal plus fee, all of it from the borrower
+        let owed = repay_amount(amount)?;
+        if TokenAccount::from_account_view(self.accounts.borrower_ata)?.amount() < owed {
+            return Err(FlashLoanError::NotEnoughFunds.into());
+        }
+
+        let decimals = Mint::from_account_view(self.accounts.mint)?.decimals();
+        TransferChecked {
+            from: self.accounts.borrower_ata,
+            mint: self.accounts.mint,
+            to: self.accounts.protocol_ata,
+            authority: self.accounts.borrower,
+            amount: owed,
+            decimals,
+        }
+        .invoke()
+    }
+}
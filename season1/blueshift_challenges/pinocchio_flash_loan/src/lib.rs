@@ -1,14 +1,31 @@
-pub fn add(left: u64, right: u64) -> u64 {
-    left + right
-}
+#![no_std]
+
+use pinocchio::{nostd_panic_handler, error::ProgramError, AccountView, Address, ProgramResult};
+
+#[cfg(not(feature = "no-entrypoint"))]
+pinocchio::entrypoint!(process_instruction);
+nostd_panic_handler!();
+
+pub mod errors;
+pub mod instructions;
+
+pub use instructions::*;
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+pub const ID: Address = Address::new_from_array([
+    0x0f, 0x1e, 0x6b, 0x14, 0x21, 0xc0, 0x4a, 0x07, 0x04, 0x31, 0x26, 0x5c, 0x19, 0xc5, 0xbb,
+    0xee, 0x19, 0x92, 0xba, 0xe8, 0xaf, 0xd1, 0xcd, 0x07, 0x8e, 0xf8, 0xaf, 0x70, 0x47, 0xdc,
+    0x11, 0xf7,
+]);
 
-    #[test]
-    fn it_works() {
-        let result = add(2, 2);
-        assert_eq!(result, 4);
+#[cfg_attr(feature = "no-entrypoint", allow(dead_code))]
+fn process_instruction(
+    _program_id: &Address,
+    accounts: &[AccountView],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    match instruction_data.split_first() {
+        Some((&Borrow::DISCRIMINATOR, data)) => Borrow::try_from((data, accounts))?.process(),
+        Some((&Repay::DISCRIMINATOR, _)) => Repay::try_from(accounts)?.process(),
+        _ => Err(ProgramError::InvalidInstructionData),
     }
 }
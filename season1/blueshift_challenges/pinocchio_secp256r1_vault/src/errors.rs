@@ -0,0 +1,30 @@
+use pinocchio::error::ProgramError;
+
+#[repr(u32)]
+pub enum Secp256r1VaultError {
+    /// 签名消息中的 program ID 或 vault 与本次调用不符
+    DomainMismatch = 6000,
+    /// 签名消息中的 nonce 不是 vault 当前的 nonce（已使用或尚未轮到）
+    InvalidNonce = 6001,
+    /// 签名消息已过期
+    MessageExpired = 6002,
+    /// recipient 账户不是签名消息指定的收款人
+    RecipientMismatch = 6003,
+    InvalidAmount = 6004,
+    /// 部分取款后 vault 余额低于免租下限
+    BelowRentExemption = 6005,
+    /// passkey 断言的 rpIdHash 不是本程序的依赖方
+    RpIdMismatch = 6006,
+    /// passkey 断言未设置 UP（用户在场）标志
+    UserNotPresent = 6007,
+    /// clientDataJSON 与签名不符、格式错误或 type 不是 webauthn.get
+    InvalidClientData = 6008,
+    /// clientDataJSON 的 challenge 不是 Withdraw 消息的哈希
+    ChallengeMismatch = 6009,
+}
+
+impl From<Secp256r1VaultError> for ProgramError {
+    fn from(e: Secp256r1VaultError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
@@ -7,28 +7,144 @@ use pinocchio::{
     sysvars::{
         clock::Clock,
         instructions::{Instructions, IntrospectedInstruction},
+        rent::Rent,
         Sysvar,
     },
     AccountView, Address, ProgramResult,
 };
-use pinocchio_secp256r1_instruction::Secp256r1Instruction;
-use pinocchio_system::instructions::Transfer;
+use pinocchio_secp256r1_instruction::{Secp256r1Instruction, SECP256R1_PROGRAM_ID};
+use pinocchio_system::{create_account_with_minimum_balance_signed, instructions::Transfer};
+use solana_sha256_hasher::hash;
+
+use crate::errors::Secp256r1VaultError;
+use crate::state::VaultState;
+use crate::webauthn;
 
 /// Secp256r1 压缩公钥：1 字节奇偶性 + 32 字节 x 坐标
 pub type Secp256r1Pubkey = [u8; 33];
 
+pub const VAULT_STATE_SEED: &[u8] = b"vault_state";
+
+/// 返回 vault 的 (vault_state_pda, bump)
+pub fn find_vault_state(vault: &Address) -> (Address, u8) {
+    Address::find_program_address(&[VAULT_STATE_SEED, vault.as_ref()], &crate::ID)
+}
+
+/// 校验 `vault` 是 `pubkey` 的 vault PDA
+fn check_vault(vault: &AccountView, pubkey: &Secp256r1Pubkey) -> ProgramResult {
+    let (vault_key, _) = Address::find_program_address(
+        &[b"vault".as_slice(), &pubkey[..1], &pubkey[1..33]],
+        &crate::ID,
+    );
+    if vault.address() != &vault_key {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+    Ok(())
+}
+
+/// 校验 `vault_state` 是 `vault` 的状态 PDA，尚不归本程序所有时创建它。
+/// 按所有者而不是余额判断：有人预先往 PDA 地址转入 lamports 也不影响创建。
+/// 已存在的状态沿用原 nonce，旧签名不会因 nonce 归零而重新生效。
+fn init_vault_state(
+    payer: &AccountView,
+    vault: &AccountView,
+    vault_state: &AccountView,
+) -> ProgramResult {
+    let (state_key, state_bump) = find_vault_state(vault.address());
+    if vault_state.address() != &state_key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    if vault_state.owned_by(&crate::ID) {
+        let data = vault_state.try_borrow()?;
+        VaultState::load(&data)?;
+        return Ok(());
+    }
+
+    let bump = [state_bump];
+    let seeds = [
+        Seed::from(VAULT_STATE_SEED),
+        Seed::from(vault.address().as_ref()),
+        Seed::from(bump.as_slice()),
+    ];
+    create_account_with_minimum_balance_signed(
+        vault_state,
+        VaultState::LEN,
+        &crate::ID,
+        payer,
+        None,
+        &[Signer::from(&seeds)],
+    )?;
+
+    let mut data = vault_state.try_borrow_mut()?;
+    VaultState::init(&mut data)?.bump = bump;
+    Ok(())
+}
+
+// ========== Withdraw 签名消息 ==========
+
+/// Withdraw 签名消息的长度：
+/// `program_id(32) || vault(32) || nonce(8) || recipient(32) || amount(8) || expiry(8)`，
+/// 整数均为小端。program ID 和 vault 地址做域分隔，签名不能拿到别的程序或别的 vault 上使用；
+/// 收款人和金额由签名者决定，任何人都可以代为提交并支付交易费。
+pub const WITHDRAW_MESSAGE_LEN: usize = 32 + 32 + 8 + 32 + 8 + 8;
+
+pub struct WithdrawMessage {
+    pub program_id: Address,
+    pub vault: Address,
+    pub nonce: u64,
+    pub recipient: Address,
+    pub amount: u64,
+    pub expiry: i64,
+}
+
+impl WithdrawMessage {
+    /// 按签名布局编码，供客户端构造待签名消息
+    pub fn to_bytes(&self) -> [u8; WITHDRAW_MESSAGE_LEN] {
+        let mut bytes = [0u8; WITHDRAW_MESSAGE_LEN];
+        bytes[..32].copy_from_slice(self.program_id.as_ref());
+        bytes[32..64].copy_from_slice(self.vault.as_ref());
+        bytes[64..72].copy_from_slice(&self.nonce.to_le_bytes());
+        bytes[72..104].copy_from_slice(self.recipient.as_ref());
+        bytes[104..112].copy_from_slice(&self.amount.to_le_bytes());
+        bytes[112..].copy_from_slice(&self.expiry.to_le_bytes());
+        bytes
+    }
+}
+
+impl<'a> TryFrom<&'a [u8]> for WithdrawMessage {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        if data.len() != WITHDRAW_MESSAGE_LEN {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let address =
+            |offset: usize| Address::new_from_array(data[offset..offset + 32].try_into().unwrap());
+        Ok(Self {
+            program_id: address(0),
+            vault: address(32),
+            nonce: u64::from_le_bytes(data[64..72].try_into().unwrap()),
+            recipient: address(72),
+            amount: u64::from_le_bytes(data[104..112].try_into().unwrap()),
+            expiry: i64::from_le_bytes(data[112..].try_into().unwrap()),
+        })
+    }
+}
+
 // ========== Deposit ==========
 
 pub struct DepositAccounts<'a> {
     pub payer: &'a AccountView,
     pub vault: &'a AccountView,
+    pub vault_state: &'a AccountView,
 }
 
 impl<'a> TryFrom<&'a [AccountView]> for DepositAccounts<'a> {
     type Error = ProgramError;
 
     fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
-        let [payer, vault, _] = accounts else {
+        let [payer, vault, vault_state, _] = accounts else {
             return Err(ProgramError::NotEnoughAccountKeys);
         };
 
@@ -44,7 +160,11 @@ impl<'a> TryFrom<&'a [AccountView]> for DepositAccounts<'a> {
             return Err(ProgramError::InvalidAccountData);
         }
 
-        Ok(Self { payer, vault })
+        Ok(Self {
+            payer,
+            vault,
+            vault_state,
+        })
     }
 }
 
@@ -94,17 +214,14 @@ impl<'a> Deposit<'a> {
     pub const DISCRIMINATOR: u8 = 0;
 
     pub fn process(&mut self) -> ProgramResult {
-        let (vault_key, _) = Address::find_program_address(
-            &[
-                b"vault".as_slice(),
-                &self.instruction_data.pubkey[..1],
-                &self.instruction_data.pubkey[1..33],
-            ],
-            &crate::ID,
-        );
-        if self.accounts.vault.address() != &vault_key {
-            return Err(ProgramError::InvalidAccountOwner);
-        }
+        check_vault(self.accounts.vault, &self.instruction_data.pubkey)?;
+
+        // 首次存款时创建 nonce 状态
+        init_vault_state(
+            self.accounts.payer,
+            self.accounts.vault,
+            self.accounts.vault_state,
+        )?;
 
         Transfer {
             from: self.accounts.payer,
@@ -117,11 +234,94 @@ impl<'a> Deposit<'a> {
     }
 }
 
+// ========== InitVaultState ==========
+
+/// 为已有余额的 vault 创建 nonce 状态。直接转账入金的 vault 没有经过 Deposit，
+/// 没有状态就无法 Withdraw；任何人都可以代为创建并支付租金，状态已存在时什么也不做。
+pub struct InitVaultStateAccounts<'a> {
+    pub payer: &'a AccountView,
+    pub vault: &'a AccountView,
+    pub vault_state: &'a AccountView,
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for InitVaultStateAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let [payer, vault, vault_state, _] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        if !payer.is_signer() {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+
+        if !vault.owned_by(&pinocchio_system::ID) {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+
+        Ok(Self {
+            payer,
+            vault,
+            vault_state,
+        })
+    }
+}
+
+pub struct InitVaultStateInstructionData {
+    pub pubkey: Secp256r1Pubkey,
+}
+
+impl<'a> TryFrom<&'a [u8]> for InitVaultStateInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        let pubkey = data
+            .try_into()
+            .map_err(|_| ProgramError::InvalidInstructionData)?;
+        Ok(Self { pubkey })
+    }
+}
+
+pub struct InitVaultState<'a> {
+    pub accounts: InitVaultStateAccounts<'a>,
+    pub instruction_data: InitVaultStateInstructionData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountView])> for InitVaultState<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountView])) -> Result<Self, Self::Error> {
+        let accounts = InitVaultStateAccounts::try_from(accounts)?;
+        let instruction_data = InitVaultStateInstructionData::try_from(data)?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> InitVaultState<'a> {
+    pub const DISCRIMINATOR: u8 = 2;
+
+    pub fn process(&mut self) -> ProgramResult {
+        check_vault(self.accounts.vault, &self.instruction_data.pubkey)?;
+        init_vault_state(
+            self.accounts.payer,
+            self.accounts.vault,
+            self.accounts.vault_state,
+        )
+    }
+}
+
 // ========== Withdraw ==========
 
 pub struct WithdrawAccounts<'a> {
     pub payer: &'a AccountView,
     pub vault: &'a AccountView,
+    pub vault_state: &'a AccountView,
+    pub recipient: &'a AccountView,
     pub instructions: &'a AccountView,
 }
 
@@ -129,7 +329,7 @@ impl<'a> TryFrom<&'a [AccountView]> for WithdrawAccounts<'a> {
     type Error = ProgramError;
 
     fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
-        let [payer, vault, instructions, _system_program] = accounts else {
+        let [payer, vault, vault_state, recipient, instructions, _system_program] = accounts else {
             return Err(ProgramError::NotEnoughAccountKeys);
         };
 
@@ -145,33 +345,55 @@ impl<'a> TryFrom<&'a [AccountView]> for WithdrawAccounts<'a> {
             return Err(ProgramError::InvalidAccountData);
         }
 
+        if !vault_state.owned_by(&crate::ID) {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+
         Ok(Self {
             payer,
             vault,
+            vault_state,
+            recipient,
             instructions,
         })
     }
 }
 
-pub struct WithdrawInstructionData {
+/// `bump(1)`：secp256r1 签名的就是 [`WithdrawMessage`]；
+/// `bump(1) || WithdrawMessage || clientDataJSON`：passkey 断言，签名的是
+/// `authenticatorData || sha256(clientDataJSON)`，挑战为 `sha256(WithdrawMessage)`
+pub struct WithdrawInstructionData<'a> {
     pub bump: [u8; 1],
+    pub webauthn: Option<(WithdrawMessage, &'a [u8])>,
 }
 
-impl<'a> TryFrom<&'a [u8]> for WithdrawInstructionData {
+impl<'a> TryFrom<&'a [u8]> for WithdrawInstructionData<'a> {
     type Error = ProgramError;
 
     fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        let (bump, rest) = data
+            .split_first()
+            .ok_or(ProgramError::InvalidInstructionData)?;
+
+        let webauthn = if rest.is_empty() {
+            None
+        } else if rest.len() > WITHDRAW_MESSAGE_LEN {
+            let (message, client_data_json) = rest.split_at(WITHDRAW_MESSAGE_LEN);
+            Some((WithdrawMessage::try_from(message)?, client_data_json))
+        } else {
+            return Err(ProgramError::InvalidInstructionData);
+        };
+
         Ok(Self {
-            bump: [*data
-                .first()
-                .ok_or(ProgramError::InvalidInstructionData)?],
+            bump: [*bump],
+            webauthn,
         })
     }
 }
 
 pub struct Withdraw<'a> {
     pub accounts: WithdrawAccounts<'a>,
-    pub instruction_data: WithdrawInstructionData,
+    pub instruction_data: WithdrawInstructionData<'a>,
 }
 
 impl<'a> TryFrom<(&'a [u8], &'a [AccountView])> for Withdraw<'a> {
@@ -195,6 +417,10 @@ impl<'a> Withdraw<'a> {
         let instructions: Instructions<Ref<[u8]>> =
             Instructions::try_from(self.accounts.instructions)?;
         let ix: IntrospectedInstruction = instructions.get_instruction_relative(1)?;
+        // 签名只有经过预编译程序验证才可信
+        if ix.get_program_id() != &Address::new_from_array(SECP256R1_PROGRAM_ID) {
+            return Err(ProgramError::IncorrectProgramId);
+        }
         let ix_data = ix.get_instruction_data();
         let secp256r1_ix = Secp256r1Instruction::try_from(ix_data)
             .map_err(|_| ProgramError::InvalidInstructionData)?;
@@ -207,30 +433,53 @@ impl<'a> Withdraw<'a> {
             .get_signer(0)
             .map_err(|_| ProgramError::InvalidInstructionData)?;
 
-        let msg = secp256r1_ix
+        let signed = secp256r1_ix
             .get_message_data(0)
             .map_err(|_| ProgramError::InvalidInstructionData)?;
-        if msg.len() < 32 + 8 {
-            return Err(ProgramError::InvalidInstructionData);
+        let msg = match self.instruction_data.webauthn.take() {
+            None => WithdrawMessage::try_from(signed)?,
+            Some((msg, client_data_json)) => {
+                let challenge = hash(&msg.to_bytes()).to_bytes();
+                webauthn::verify_assertion(signed, client_data_json, &challenge)?;
+                msg
+            }
+        };
+        if msg.program_id != crate::ID || self.accounts.vault.address() != &msg.vault {
+            return Err(Secp256r1VaultError::DomainMismatch.into());
         }
-        let (payer_bytes, expiry_bytes) = msg.split_at(32);
-        let payer_address = Address::new_from_array(
-            payer_bytes
-                .try_into()
-                .map_err(|_| ProgramError::InvalidInstructionData)?,
-        );
-        if self.accounts.payer.address() != &payer_address {
-            return Err(ProgramError::InvalidAccountOwner);
+        if self.accounts.recipient.address() != &msg.recipient {
+            return Err(Secp256r1VaultError::RecipientMismatch.into());
         }
 
-        let expiry = i64::from_le_bytes(
-            expiry_bytes[..8]
-                .try_into()
-                .map_err(|_| ProgramError::InvalidInstructionData)?,
-        );
         let now = Clock::get()?.unix_timestamp;
-        if now > expiry {
-            return Err(ProgramError::InvalidInstructionData);
+        if now > msg.expiry {
+            return Err(Secp256r1VaultError::MessageExpired.into());
+        }
+
+        if self.accounts.vault_state.address() != &find_vault_state(&msg.vault).0 {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        {
+            let mut data = self.accounts.vault_state.try_borrow_mut()?;
+            let state = VaultState::load_mut(&mut data)?;
+            if state.nonce != msg.nonce {
+                return Err(Secp256r1VaultError::InvalidNonce.into());
+            }
+            state.advance()?;
+        }
+
+        // 可以部分取款，但 vault 剩余余额要么为 0，要么仍然免租
+        if msg.amount == 0 {
+            return Err(Secp256r1VaultError::InvalidAmount.into());
+        }
+        let remaining = self
+            .accounts
+            .vault
+            .lamports()
+            .checked_sub(msg.amount)
+            .ok_or(ProgramError::InsufficientFunds)?;
+        if remaining != 0 && remaining < Rent::get()?.try_minimum_balance(0)? {
+            return Err(Secp256r1VaultError::BelowRentExemption.into());
         }
 
         let seeds = [
@@ -243,8 +492,8 @@ impl<'a> Withdraw<'a> {
 
         Transfer {
             from: self.accounts.vault,
-            to: self.accounts.payer,
-            lamports: self.accounts.vault.lamports(),
+            to: self.accounts.recipient,
+            lamports: msg.amount,
         }
         .invoke_signed(&signers)?;
 
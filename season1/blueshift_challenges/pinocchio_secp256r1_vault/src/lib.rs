@@ -1,13 +1,15 @@
 #![no_std]
 
-use pinocchio::{
-    entrypoint, nostd_panic_handler, error::ProgramError, AccountView, Address, ProgramResult,
-};
+use pinocchio::{nostd_panic_handler, error::ProgramError, AccountView, Address, ProgramResult};
 
-entrypoint!(process_instruction);
+#[cfg(not(feature = "no-entrypoint"))]
+pinocchio::entrypoint!(process_instruction);
 nostd_panic_handler!();
 
+pub mod errors;
 pub mod instructions;
+pub mod state;
+pub mod webauthn;
 pub use instructions::*;
 
 pub const ID: Address = Address::new_from_array([
@@ -16,6 +18,7 @@ pub const ID: Address = Address::new_from_array([
     0x11, 0xf7,
 ]);
 
+#[cfg_attr(feature = "no-entrypoint", allow(dead_code))]
 fn process_instruction(
     _program_id: &Address,
     accounts: &[AccountView],
@@ -24,6 +27,9 @@ fn process_instruction(
     match instruction_data.split_first() {
         Some((&Deposit::DISCRIMINATOR, data)) => Deposit::try_from((data, accounts))?.process(),
         Some((&Withdraw::DISCRIMINATOR, data)) => Withdraw::try_from((data, accounts))?.process(),
+        Some((&InitVaultState::DISCRIMINATOR, data)) => {
+            InitVaultState::try_from((data, accounts))?.process()
+        }
         _ => Err(ProgramError::InvalidInstructionData),
     }
 }
@@ -0,0 +1,63 @@
+use core::mem::size_of;
+use pinocchio::error::ProgramError;
+
+/// vault 的防重放状态，PDA：`["vault_state", vault]`，由本程序拥有。
+/// 每次 Withdraw 消耗当前 nonce 并加一，已签名的消息因此只能使用一次。
+#[repr(C)]
+pub struct VaultState {
+    /// 初始化后为 `VaultState::DISCRIMINATOR`
+    pub discriminator: u8,
+    pub bump: [u8; 1],
+    /// 使 nonce 按 8 字节对齐
+    pub padding: [u8; 6],
+    /// 下一次 Withdraw 的签名消息必须携带的 nonce
+    pub nonce: u64,
+}
+
+impl VaultState {
+    pub const LEN: usize =
+        size_of::<u8>() + size_of::<[u8; 1]>() + size_of::<[u8; 6]>() + size_of::<u64>();
+
+    pub const DISCRIMINATOR: u8 = 1;
+
+    /// 在新建（全零）的账户数据上写入 discriminator
+    #[inline(always)]
+    pub fn init(bytes: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        if bytes.len() != VaultState::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        if bytes[0] != 0 {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+        bytes[0] = VaultState::DISCRIMINATOR;
+        Self::load_mut(bytes)
+    }
+
+    #[inline(always)]
+    pub fn load_mut(bytes: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        if bytes.len() != VaultState::LEN || bytes[0] != VaultState::DISCRIMINATOR {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Ok(unsafe { &mut *core::mem::transmute::<*mut u8, *mut Self>(bytes.as_mut_ptr()) })
+    }
+
+    #[inline(always)]
+    pub fn load(bytes: &[u8]) -> Result<&Self, ProgramError> {
+        if bytes.len() != VaultState::LEN || bytes[0] != VaultState::DISCRIMINATOR {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Ok(unsafe { &*core::mem::transmute::<*const u8, *const Self>(bytes.as_ptr()) })
+    }
+
+    /// 消耗当前 nonce
+    #[inline(always)]
+    pub fn advance(&mut self) -> Result<(), ProgramError> {
+        self.nonce = self
+            .nonce
+            .checked_add(1)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        Ok(())
+    }
+}
+
+const _: () = assert!(VaultState::LEN == size_of::<VaultState>());
@@ -0,0 +1,235 @@
+//! WebAuthn / passkey 断言校验
+//!
+//! passkey 不直接签名 Withdraw 消息，而是签名
+//! `authenticatorData || sha256(clientDataJSON)`，其中 clientDataJSON 的 `challenge`
+//! 是浏览器传入的挑战值（base64url，无填充）。这里用 `sha256(WithdrawMessage)` 作为挑战，
+//! 签名本身仍由 secp256r1 预编译程序验证，本模块只检查签名内容：
+//! - authenticatorData 的 rpIdHash 等于 `sha256(RP_ID)`，且设置了 UP（用户在场）标志
+//! - sha256(clientDataJSON) 等于签名消息末尾的 32 字节
+//! - clientDataJSON 的 `type` 为 `webauthn.get`，`challenge` 等于预期挑战
+//!
+//! passkey 绑定在注册时的依赖方（通常是前端的域名）上，[`RP_ID`] 在构建时确定：
+//! `SECP256R1_VAULT_RP_ID=wallet.example.com cargo build-programs`，未设置时为 `localhost`。
+//! 同一次部署的所有 vault 共用一个依赖方，更换域名需要重新部署。
+
+use pinocchio::{error::ProgramError, ProgramResult};
+use solana_sha256_hasher::hash;
+
+use crate::errors::Secp256r1VaultError;
+
+/// 依赖方 ID，构建时由环境变量 `SECP256R1_VAULT_RP_ID` 指定，默认 `localhost`
+pub const RP_ID: &str = match option_env!("SECP256R1_VAULT_RP_ID") {
+    Some(rp_id) => rp_id,
+    None => "localhost",
+};
+
+/// authenticatorData 开头应有的 rpIdHash：`sha256(RP_ID)`
+pub fn rp_id_hash() -> [u8; 32] {
+    hash(RP_ID.as_bytes()).to_bytes()
+}
+
+/// authenticatorData 的 flags：UP（用户在场）
+pub const FLAG_USER_PRESENT: u8 = 0x01;
+
+/// authenticatorData 至少包含 rpIdHash(32) + flags(1) + signCount(4)
+pub const AUTHENTICATOR_DATA_MIN_LEN: usize = 32 + 1 + 4;
+
+/// 32 字节挑战的 base64url（无填充）长度
+pub const CHALLENGE_B64_LEN: usize = 43;
+
+const CLIENT_DATA_TYPE_GET: &[u8] = b"webauthn.get";
+
+/// 校验 passkey 签名的内容。`signed` 为预编译程序验证过的消息
+/// `authenticatorData || sha256(clientDataJSON)`，`challenge` 为预期挑战
+pub fn verify_assertion(
+    signed: &[u8],
+    client_data_json: &[u8],
+    challenge: &[u8; 32],
+) -> ProgramResult {
+    if signed.len() < AUTHENTICATOR_DATA_MIN_LEN + 32 {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    let (authenticator_data, client_data_hash) = signed.split_at(signed.len() - 32);
+
+    if authenticator_data[..32] != rp_id_hash() {
+        return Err(Secp256r1VaultError::RpIdMismatch.into());
+    }
+    if authenticator_data[32] & FLAG_USER_PRESENT == 0 {
+        return Err(Secp256r1VaultError::UserNotPresent.into());
+    }
+
+    if hash(client_data_json).to_bytes() != client_data_hash {
+        return Err(Secp256r1VaultError::InvalidClientData.into());
+    }
+    let client_data = ClientData::parse(client_data_json)?;
+    if client_data.type_ != CLIENT_DATA_TYPE_GET {
+        return Err(Secp256r1VaultError::InvalidClientData.into());
+    }
+    if client_data.challenge != base64url_encode_32(challenge) {
+        return Err(Secp256r1VaultError::ChallengeMismatch.into());
+    }
+
+    Ok(())
+}
+
+/// clientDataJSON 中需要检查的字段，值为引号内的原始字节（不做转义还原）
+pub struct ClientData<'a> {
+    pub type_: &'a [u8],
+    pub challenge: &'a [u8],
+}
+
+impl<'a> ClientData<'a> {
+    /// 解析顶层 JSON 对象，取出 `type` 与 `challenge`；其余字段（origin、crossOrigin、
+    /// tokenBinding 等）跳过。两个字段必须各出现一次。
+    pub fn parse(json: &'a [u8]) -> Result<Self, ProgramError> {
+        let mut parser = Parser { json, pos: 0 };
+        let mut type_ = None;
+        let mut challenge = None;
+
+        parser.expect(b'{')?;
+        if parser.peek() == Some(b'}') {
+            return Err(Secp256r1VaultError::InvalidClientData.into());
+        }
+        loop {
+            let key = parser.string()?;
+            parser.expect(b':')?;
+            let field = match key {
+                b"type" => Some(&mut type_),
+                b"challenge" => Some(&mut challenge),
+                _ => None,
+            };
+            match field {
+                Some(field) => {
+                    if field.replace(parser.string()?).is_some() {
+                        return Err(Secp256r1VaultError::InvalidClientData.into());
+                    }
+                }
+                None => parser.skip_value()?,
+            }
+            if !parser.next_member()? {
+                break;
+            }
+        }
+        if parser.peek().is_some() {
+            return Err(Secp256r1VaultError::InvalidClientData.into());
+        }
+
+        match (type_, challenge) {
+            (Some(type_), Some(challenge)) => Ok(Self { type_, challenge }),
+            _ => Err(Secp256r1VaultError::InvalidClientData.into()),
+        }
+    }
+}
+
+/// 只识别 clientDataJSON 所需的 JSON 子集：字符串只定位边界，不还原转义
+struct Parser<'a> {
+    json: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    /// 跳过空白后的下一个字节
+    fn peek(&mut self) -> Option<u8> {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.json.get(self.pos) {
+            self.pos += 1;
+        }
+        self.json.get(self.pos).copied()
+    }
+
+    fn expect(&mut self, byte: u8) -> ProgramResult {
+        if self.peek() != Some(byte) {
+            return Err(Secp256r1VaultError::InvalidClientData.into());
+        }
+        self.pos += 1;
+        Ok(())
+    }
+
+    /// 对象成员之后：`,` 返回 true，`}` 返回 false
+    fn next_member(&mut self) -> Result<bool, ProgramError> {
+        match self.peek() {
+            Some(b',') => {
+                self.pos += 1;
+                Ok(true)
+            }
+            Some(b'}') => {
+                self.pos += 1;
+                Ok(false)
+            }
+            _ => Err(Secp256r1VaultError::InvalidClientData.into()),
+        }
+    }
+
+    /// 读取一个字符串，返回引号之间的字节
+    fn string(&mut self) -> Result<&'a [u8], ProgramError> {
+        self.expect(b'"')?;
+        let start = self.pos;
+        loop {
+            match self.json.get(self.pos) {
+                Some(b'"') => break,
+                Some(b'\\') => self.pos += 2,
+                Some(0x00..=0x1f) | None => {
+                    return Err(Secp256r1VaultError::InvalidClientData.into())
+                }
+                Some(_) => self.pos += 1,
+            }
+        }
+        let value = self
+            .json
+            .get(start..self.pos)
+            .ok_or(Secp256r1VaultError::InvalidClientData)?;
+        self.pos += 1;
+        Ok(value)
+    }
+
+    /// 跳过任意值：字符串、对象、数组、数字或字面量
+    fn skip_value(&mut self) -> ProgramResult {
+        let mut depth = 0usize;
+        loop {
+            match self.peek() {
+                Some(b'"') => {
+                    self.string()?;
+                }
+                Some(b'{' | b'[') => {
+                    depth += 1;
+                    self.pos += 1;
+                }
+                Some(b'}' | b']') if depth > 0 => {
+                    depth -= 1;
+                    self.pos += 1;
+                }
+                Some(b',' | b':') if depth > 0 => self.pos += 1,
+                Some(b'-' | b'+' | b'.' | b'0'..=b'9' | b'a'..=b'z' | b'A'..=b'Z') => {
+                    while let Some(b'-' | b'+' | b'.' | b'0'..=b'9' | b'a'..=b'z' | b'A'..=b'Z') =
+                        self.json.get(self.pos)
+                    {
+                        self.pos += 1;
+                    }
+                }
+                _ => return Err(Secp256r1VaultError::InvalidClientData.into()),
+            }
+            if depth == 0 {
+                return Ok(());
+            }
+        }
+    }
+}
+
+const BASE64URL: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// 32 字节的 base64url 编码（无填充）
+pub fn base64url_encode_32(bytes: &[u8; 32]) -> [u8; CHALLENGE_B64_LEN] {
+    let mut out = [0u8; CHALLENGE_B64_LEN];
+    let mut o = 0;
+    for chunk in bytes.chunks(3) {
+        let b0 = chunk[0] as u32;
+        let b1 = chunk.get(1).copied().unwrap_or(0) as u32;
+        let b2 = chunk.get(2).copied().unwrap_or(0) as u32;
+        let n = (b0 << 16) | (b1 << 8) | b2;
+        // 最后一组只有 2 字节，输出 3 个字符
+        for i in 0..chunk.len() + 1 {
+            out[o] = BASE64URL[((n >> (18 - 6 * i)) & 0x3f) as usize];
+            o += 1;
+        }
+    }
+    out
+}
@@ -0,0 +1,30 @@
+use pinocchio::error::ProgramError;
+
+#[repr(u32)]
+pub enum VaultError {
+    InvalidAmount = 6000,
+    /// The vault would be left holding less than the rent-exempt minimum
+    BelowRentExemption = 6001,
+    VaultLocked = 6002,
+    /// The vault has a cooldown: use RequestWithdraw / ExecuteWithdraw
+    WithdrawalRequestRequired = 6003,
+    WithdrawalNotReady = 6004,
+    NoPendingWithdrawal = 6005,
+    PendingWithdrawalExists = 6006,
+    /// Time locks can only be made stricter
+    InvalidTimelock = 6007,
+    InvalidAllowance = 6008,
+    AllowanceExpired = 6009,
+    /// More than the allowance has left in total
+    AllowanceExceeded = 6010,
+    /// More than the allowance's per-period limit
+    PeriodLimitExceeded = 6011,
+    /// Token vaults only hold mints of the legacy SPL Token program
+    UnsupportedMint = 6012,
+}
+
+impl From<VaultError> for ProgramError {
+    fn from(e: VaultError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
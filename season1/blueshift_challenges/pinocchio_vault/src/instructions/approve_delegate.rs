@@ -0,0 +1,151 @@
+use core::mem::size_of;
+
+use pinocchio::{
+    cpi::{Seed, Signer},
+    error::ProgramError,
+    sysvars::{clock::Clock, Sysvar},
+    AccountView, Address, ProgramResult,
+};
+use pinocchio_system::create_account_with_minimum_balance_signed;
+
+use crate::errors::VaultError;
+use crate::instructions::helpers::ALLOWANCE_SEED;
+use crate::state::Allowance;
+use crate::ID;
+
+/// Grant `delegate` an allowance over the owner's SOL vault, or replace the
+/// terms of an existing one
+pub struct ApproveDelegateAccounts<'a> {
+    pub owner: &'a AccountView,
+    pub delegate: &'a AccountView,
+    pub allowance: &'a AccountView,
+    pub bumps: [u8; 1],
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for ApproveDelegateAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let [owner, delegate, allowance, _] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        if !owner.is_signer() {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let (allowance_key, bump) = Address::find_program_address(
+            &[
+                ALLOWANCE_SEED,
+                owner.address().as_ref(),
+                delegate.address().as_ref(),
+            ],
+            &ID,
+        );
+        if allowance.address() != &allowance_key {
+            return Err(ProgramError::InvalidSeeds);
+        }
+
+        Ok(Self {
+            owner,
+            delegate,
+            allowance,
+            bumps: [bump],
+        })
+    }
+}
+
+pub struct ApproveDelegateInstructionData {
+    pub amount: u64,
+    pub expires_at: i64,
+    pub period: i64,
+    pub period_limit: u64,
+}
+
+impl<'a> TryFrom<&'a [u8]> for ApproveDelegateInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        // amount (8) + expires_at (8) + period (8) + period_limit (8)
+        if data.len() != size_of::<u64>() * 4 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let amount = u64::from_le_bytes(data[0..8].try_into().unwrap());
+        let expires_at = i64::from_le_bytes(data[8..16].try_into().unwrap());
+        let period = i64::from_le_bytes(data[16..24].try_into().unwrap());
+        let period_limit = u64::from_le_bytes(data[24..32].try_into().unwrap());
+
+        if amount == 0 || expires_at < 0 || period < 0 || (period != 0 && period_limit == 0) {
+            return Err(VaultError::InvalidAllowance.into());
+        }
+
+        Ok(Self {
+            amount,
+            expires_at,
+            period,
+            period_limit,
+        })
+    }
+}
+
+pub struct ApproveDelegate<'a> {
+    pub accounts: ApproveDelegateAccounts<'a>,
+    pub instruction_data: ApproveDelegateInstructionData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountView])> for ApproveDelegate<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountView])) -> Result<Self, Self::Error> {
+        let accounts = ApproveDelegateAccounts::try_from(accounts)?;
+        let instruction_data = ApproveDelegateInstructionData::try_from(data)?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> ApproveDelegate<'a> {
+    pub const DISCRIMINATOR: u8 = 8;
+
+    pub fn process(&mut self) -> ProgramResult {
+        if !self.accounts.allowance.owned_by(&ID) {
+            let seeds = [
+                Seed::from(ALLOWANCE_SEED),
+                Seed::from(self.accounts.owner.address().as_ref()),
+                Seed::from(self.accounts.delegate.address().as_ref()),
+                Seed::from(&self.accounts.bumps[..]),
+            ];
+
+            create_account_with_minimum_balance_signed(
+                self.accounts.allowance,
+                Allowance::LEN,
+                &ID,
+                self.accounts.owner,
+                None,
+                &[Signer::from(&seeds)],
+            )?;
+
+            let mut data = self.accounts.allowance.try_borrow_mut()?;
+            Allowance::init(&mut data)?.set_inner(
+                *self.accounts.owner.address(),
+                *self.accounts.delegate.address(),
+                self.accounts.bumps,
+            );
+        }
+
+        let mut data = self.accounts.allowance.try_borrow_mut()?;
+        Allowance::load_mut(&mut data)?.set_terms(
+            self.instruction_data.amount,
+            self.instruction_data.expires_at,
+            self.instruction_data.period,
+            self.instruction_data.period_limit,
+            Clock::get()?.unix_timestamp,
+        );
+
+        Ok(())
+    }
+}
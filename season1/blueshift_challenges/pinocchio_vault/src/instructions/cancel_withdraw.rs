@@ -0,0 +1,61 @@
+use pinocchio::{error::ProgramError, AccountView, ProgramResult};
+
+use crate::errors::VaultError;
+use crate::instructions::helpers::check_vault_state;
+use crate::state::VaultState;
+
+/// Drop the pending withdrawal request
+pub struct CancelWithdrawAccounts<'a> {
+    pub owner: &'a AccountView,
+    pub vault_state: &'a AccountView,
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for CancelWithdrawAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let [owner, vault_state] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        if !owner.is_signer() {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        if !check_vault_state(owner, vault_state)? {
+            return Err(ProgramError::UninitializedAccount);
+        }
+
+        Ok(Self { owner, vault_state })
+    }
+}
+
+pub struct CancelWithdraw<'a> {
+    pub accounts: CancelWithdrawAccounts<'a>,
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for CancelWithdraw<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let accounts = CancelWithdrawAccounts::try_from(accounts)?;
+
+        Ok(Self { accounts })
+    }
+}
+
+impl<'a> CancelWithdraw<'a> {
+    pub const DISCRIMINATOR: u8 = 5;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let mut data = self.accounts.vault_state.try_borrow_mut()?;
+        let state = VaultState::load_mut(&mut data)?;
+
+        if !state.has_pending() {
+            return Err(VaultError::NoPendingWithdrawal.into());
+        }
+        state.clear_pending();
+
+        Ok(())
+    }
+}
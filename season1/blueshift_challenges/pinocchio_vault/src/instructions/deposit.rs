@@ -0,0 +1,94 @@
+use core::mem::size_of;
+
+use pinocchio::{error::ProgramError, AccountView, ProgramResult};
+use pinocchio_system::instructions::Transfer;
+
+use crate::instructions::helpers::{check_vault, check_vault_balance};
+
+pub struct DepositAccounts<'a> {
+    pub owner: &'a AccountView,
+    pub vault: &'a AccountView,
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for DepositAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let [owner, vault, _] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        if !owner.is_signer() {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+
+        check_vault(owner, vault)?;
+
+        Ok(Self { owner, vault })
+    }
+}
+
+pub struct DepositInstructionData {
+    pub amount: u64,
+}
+
+impl<'a> TryFrom<&'a [u8]> for DepositInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        if data.len() != size_of::<u64>() {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let amount = u64::from_le_bytes(data.try_into().unwrap());
+
+        if amount == 0 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Ok(Self { amount })
+    }
+}
+
+pub struct Deposit<'a> {
+    pub accounts: DepositAccounts<'a>,
+    pub instruction_data: DepositInstructionData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountView])> for Deposit<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountView])) -> Result<Self, Self::Error> {
+        let accounts = DepositAccounts::try_from(accounts)?;
+        let instruction_data = DepositInstructionData::try_from(data)?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> Deposit<'a> {
+    pub const DISCRIMINATOR: u8 = 0;
+
+    pub fn process(&mut self) -> ProgramResult {
+        // Deposits may top up an existing vault, but the first one must make it rent-exempt
+        check_vault_balance(
+            self.accounts
+                .vault
+                .lamports()
+                .checked_add(self.instruction_data.amount)
+                .ok_or(ProgramError::ArithmeticOverflow)?,
+        )?;
+
+        Transfer {
+            from: self.accounts.owner,
+            to: self.accounts.vault,
+            lamports: self.instruction_data.amount,
+        }
+        .invoke()?;
+
+        Ok(())
+    }
+}
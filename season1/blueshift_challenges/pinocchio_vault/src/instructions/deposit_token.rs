@@ -0,0 +1,123 @@
+use core::mem::size_of;
+
+use pinocchio::{error::ProgramError, AccountView, ProgramResult};
+use pinocchio_associated_token_account::instructions::CreateIdempotent;
+use pinocchio_token::{instructions::TransferChecked, state::Mint};
+
+use crate::errors::VaultError;
+use crate::instructions::helpers::{check_program_id, check_token_vault};
+
+/// Deposit SPL tokens into the owner's vault ATA for `mint`, creating it on
+/// first use. Withdrawals honour the owner's time lock like SOL withdrawals.
+pub struct DepositTokenAccounts<'a> {
+    pub owner: &'a AccountView,
+    pub mint: &'a AccountView,
+    pub owner_ata: &'a AccountView,
+    pub vault_authority: &'a AccountView,
+    pub vault_ata: &'a AccountView,
+    pub system_program: &'a AccountView,
+    pub token_program: &'a AccountView,
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for DepositTokenAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let [owner, mint, owner_ata, vault_authority, vault_ata, system_program, token_program, associated_token_program] =
+            accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        if !owner.is_signer() {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        check_program_id(system_program, &pinocchio_system::ID)?;
+        check_program_id(token_program, &pinocchio_token::ID)?;
+        check_program_id(
+            associated_token_program,
+            &pinocchio_associated_token_account::ID,
+        )?;
+        check_token_vault(owner, mint, vault_authority, vault_ata)?;
+
+        Ok(Self {
+            owner,
+            mint,
+            owner_ata,
+            vault_authority,
+            vault_ata,
+            system_program,
+            token_program,
+        })
+    }
+}
+
+pub struct DepositTokenInstructionData {
+    pub amount: u64,
+}
+
+impl<'a> TryFrom<&'a [u8]> for DepositTokenInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        if data.len() != size_of::<u64>() {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let amount = u64::from_le_bytes(data.try_into().unwrap());
+
+        if amount == 0 {
+            return Err(VaultError::InvalidAmount.into());
+        }
+
+        Ok(Self { amount })
+    }
+}
+
+pub struct DepositToken<'a> {
+    pub accounts: DepositTokenAccounts<'a>,
+    pub instruction_data: DepositTokenInstructionData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountView])> for DepositToken<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountView])) -> Result<Self, Self::Error> {
+        let accounts = DepositTokenAccounts::try_from(accounts)?;
+        let instruction_data = DepositTokenInstructionData::try_from(data)?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> DepositToken<'a> {
+    pub const DISCRIMINATOR: u8 = 6;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let decimals = Mint::from_account_view(self.accounts.mint)?.decimals();
+
+        CreateIdempotent {
+            funding_account: self.accounts.owner,
+            account: self.accounts.vault_ata,
+            wallet: self.accounts.vault_authority,
+            mint: self.accounts.mint,
+            system_program: self.accounts.system_program,
+            token_program: self.accounts.token_program,
+        }
+        .invoke()?;
+
+        TransferChecked {
+            from: self.accounts.owner_ata,
+            mint: self.accounts.mint,
+            to: self.accounts.vault_ata,
+            authority: self.accounts.owner,
+            amount: self.instruction_data.amount,
+            decimals,
+        }
+        .invoke()
+    }
+}
@@ -0,0 +1,121 @@
+use pinocchio::{
+    error::ProgramError,
+    sysvars::{clock::Clock, Sysvar},
+    AccountView, ProgramResult,
+};
+
+use crate::errors::VaultError;
+use crate::instructions::helpers::{
+    check_program_id, check_token_vault, check_vault, check_vault_state, withdraw_from_token_vault,
+    withdraw_from_vault,
+};
+use crate::state::VaultState;
+
+/// Second phase: pay out the pending request once its cooldown has passed.
+/// A token request takes the token program instead of the system program,
+/// followed by the mint, the owner's ATA and the vault ATA; `vault` is then
+/// the mint's vault authority.
+pub struct ExecuteWithdrawAccounts<'a> {
+    pub owner: &'a AccountView,
+    pub vault: &'a AccountView,
+    pub vault_state: &'a AccountView,
+    /// `(mint, owner_ata, vault_ata)` of a token request
+    pub token: Option<(&'a AccountView, &'a AccountView, &'a AccountView)>,
+    pub bumps: [u8; 1],
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for ExecuteWithdrawAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let [owner, vault, vault_state, program, token @ ..] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        if !owner.is_signer() {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let (bump, token) = match token {
+            [] => (check_vault(owner, vault)?, None),
+            [mint, owner_ata, vault_ata] => {
+                check_program_id(program, &pinocchio_token::ID)?;
+                let bump = check_token_vault(owner, mint, vault, vault_ata)?;
+                (bump, Some((mint, owner_ata, vault_ata)))
+            }
+            _ => return Err(ProgramError::NotEnoughAccountKeys),
+        };
+        if !check_vault_state(owner, vault_state)? {
+            return Err(ProgramError::UninitializedAccount);
+        }
+
+        Ok(Self {
+            owner,
+            vault,
+            vault_state,
+            token,
+            bumps: [bump],
+        })
+    }
+}
+
+pub struct ExecuteWithdraw<'a> {
+    pub accounts: ExecuteWithdrawAccounts<'a>,
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for ExecuteWithdraw<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let accounts = ExecuteWithdrawAccounts::try_from(accounts)?;
+
+        Ok(Self { accounts })
+    }
+}
+
+impl<'a> ExecuteWithdraw<'a> {
+    pub const DISCRIMINATOR: u8 = 4;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let amount = {
+            let mut data = self.accounts.vault_state.try_borrow_mut()?;
+            let state = VaultState::load_mut(&mut data)?;
+
+            // The request must be for this vault: lamports or the passed mint
+            let mint = self.accounts.token.map(|(mint, _, _)| mint.address());
+            if !state.has_pending() || state.pending_mint() != mint {
+                return Err(VaultError::NoPendingWithdrawal.into());
+            }
+            let now = Clock::get()?.unix_timestamp;
+            if state.is_locked(now) {
+                return Err(VaultError::VaultLocked.into());
+            }
+            if now < state.pending_ready_at {
+                return Err(VaultError::WithdrawalNotReady.into());
+            }
+
+            let amount = state.pending_amount;
+            state.clear_pending();
+            amount
+        };
+
+        match self.accounts.token {
+            Some((mint, owner_ata, vault_ata)) => withdraw_from_token_vault(
+                self.accounts.owner,
+                mint,
+                self.accounts.vault,
+                self.accounts.bumps[0],
+                vault_ata,
+                owner_ata,
+                Some(amount),
+            ),
+            None => withdraw_from_vault(
+                self.accounts.owner,
+                self.accounts.vault,
+                self.accounts.bumps[0],
+                self.accounts.owner,
+                amount,
+            ),
+        }
+    }
+}
@@ -0,0 +1,222 @@
+use core::ops::Deref;
+
+use pinocchio::{
+    cpi::{Seed, Signer},
+    error::ProgramError,
+    sysvars::{clock::Clock, rent::Rent, Sysvar},
+    AccountView, Address, ProgramResult,
+};
+use pinocchio_system::instructions::Transfer;
+use pinocchio_token::{
+    instructions::{CloseAccount, TransferChecked},
+    state::{Mint, TokenAccount},
+};
+
+use crate::errors::VaultError;
+use crate::state::VaultState;
+use crate::ID;
+
+pub const VAULT_SEED: &[u8] = b"vault";
+pub const VAULT_STATE_SEED: &[u8] = b"vault_state";
+pub const ALLOWANCE_SEED: &[u8] = b"allowance";
+
+/// The system-owned vault holds no data: it must either stay rent-exempt for
+/// a zero-length account or be emptied completely
+pub fn check_vault_balance(lamports: u64) -> ProgramResult {
+    if lamports != 0 && lamports < Rent::get()?.try_minimum_balance(0)? {
+        return Err(VaultError::BelowRentExemption.into());
+    }
+    Ok(())
+}
+
+/// Check that `vault` is the owner's system-owned vault PDA; returns its bump
+pub fn check_vault(owner: &AccountView, vault: &AccountView) -> Result<u8, ProgramError> {
+    if !vault.owned_by(&pinocchio_system::ID) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    let (vault_key, bump) =
+        Address::find_program_address(&[VAULT_SEED, owner.address().as_ref()], &ID);
+    if vault.address() != &vault_key {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    Ok(bump)
+}
+
+/// Check that `state` is the owner's vault state PDA; returns whether it has
+/// been initialized (owners without a time lock have none)
+pub fn check_vault_state(owner: &AccountView, state: &AccountView) -> Result<bool, ProgramError> {
+    let (state_key, _) =
+        Address::find_program_address(&[VAULT_STATE_SEED, owner.address().as_ref()], &ID);
+    if state.address() != &state_key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    if !state.owned_by(&ID) {
+        return Ok(false);
+    }
+
+    let data = state.try_borrow()?;
+    VaultState::load(data.deref())?;
+    Ok(true)
+}
+
+/// Reject direct SOL or token withdrawals while the owner's time lock is
+/// active or a cooldown requires the two-phase flow
+pub fn check_direct_withdraw(owner: &AccountView, vault_state: &AccountView) -> ProgramResult {
+    if !check_vault_state(owner, vault_state)? {
+        return Ok(());
+    }
+
+    let data = vault_state.try_borrow()?;
+    let state = VaultState::load(data.deref())?;
+    if state.is_locked(Clock::get()?.unix_timestamp) {
+        return Err(VaultError::VaultLocked.into());
+    }
+    if state.cooldown != 0 {
+        return Err(VaultError::WithdrawalRequestRequired.into());
+    }
+    Ok(())
+}
+
+/// Send `amount` lamports from the owner's vault to `destination`, leaving the
+/// vault rent-exempt or empty
+pub fn withdraw_from_vault(
+    owner: &AccountView,
+    vault: &AccountView,
+    bump: u8,
+    destination: &AccountView,
+    amount: u64,
+) -> ProgramResult {
+    let remaining = vault
+        .lamports()
+        .checked_sub(amount)
+        .ok_or(VaultError::InvalidAmount)?;
+    check_vault_balance(remaining)?;
+
+    let bump_array = [bump];
+    let seeds = [
+        Seed::from(VAULT_SEED),
+        Seed::from(owner.address().as_ref()),
+        Seed::from(&bump_array[..]),
+    ];
+    let signers = [Signer::from(&seeds)];
+
+    Transfer {
+        from: vault,
+        to: destination,
+        lamports: amount,
+    }
+    .invoke_signed(&signers)
+}
+
+/// Token vaults extend the SOL vault seeds with the mint: the data-less
+/// `[VAULT_SEED, owner, mint]` PDA owns an associated token account per mint.
+/// Checks both addresses and returns the authority bump. Token-2022 mints are
+/// rejected: their vault ATA would live at a different address.
+pub fn check_token_vault(
+    owner: &AccountView,
+    mint: &AccountView,
+    authority: &AccountView,
+    vault: &AccountView,
+) -> Result<u8, ProgramError> {
+    if !mint.owned_by(&pinocchio_token::ID) {
+        return Err(VaultError::UnsupportedMint.into());
+    }
+
+    let (authority_key, bump) = Address::find_program_address(
+        &[VAULT_SEED, owner.address().as_ref(), mint.address().as_ref()],
+        &ID,
+    );
+    if authority.address() != &authority_key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let (ata, _) = Address::find_program_address(
+        &[
+            authority_key.as_ref(),
+            pinocchio_token::ID.as_ref(),
+            mint.address().as_ref(),
+        ],
+        &pinocchio_associated_token_account::ID,
+    );
+    if vault.address() != &ata {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    Ok(bump)
+}
+
+/// Send `amount` tokens (`None` = all) from the owner's vault ATA for `mint`
+/// to `destination`, closing the ATA and refunding its rent to the owner once
+/// it is emptied
+pub fn withdraw_from_token_vault(
+    owner: &AccountView,
+    mint: &AccountView,
+    authority: &AccountView,
+    bump: u8,
+    vault: &AccountView,
+    destination: &AccountView,
+    amount: Option<u64>,
+) -> ProgramResult {
+    let decimals = Mint::from_account_view(mint)?.decimals();
+    let balance = TokenAccount::from_account_view(vault)?.amount();
+
+    let amount = amount.unwrap_or(balance);
+    if amount == 0 || amount > balance {
+        return Err(VaultError::InvalidAmount.into());
+    }
+
+    let bump_array = [bump];
+    let seeds = [
+        Seed::from(VAULT_SEED),
+        Seed::from(owner.address().as_ref()),
+        Seed::from(mint.address().as_ref()),
+        Seed::from(&bump_array[..]),
+    ];
+    let signers = [Signer::from(&seeds)];
+
+    TransferChecked {
+        from: vault,
+        mint,
+        to: destination,
+        authority,
+        amount,
+        decimals,
+    }
+    .invoke_signed(&signers)?;
+
+    if amount == balance {
+        CloseAccount {
+            account: vault,
+            destination: owner,
+            authority,
+        }
+        .invoke_signed(&signers)?;
+    }
+
+    Ok(())
+}
+
+/// Check that a program account is the expected program
+pub fn check_program_id(program: &AccountView, id: &Address) -> ProgramResult {
+    if program.address() != id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    Ok(())
+}
+
+/// Move all lamports of a program-owned account to `destination` and close it
+pub fn close_program_account(
+    account: &AccountView,
+    destination: &AccountView,
+) -> ProgramResult {
+    destination.set_lamports(
+        destination
+            .lamports()
+            .checked_add(account.lamports())
+            .ok_or(ProgramError::ArithmeticOverflow)?,
+    );
+    account.set_lamports(0);
+    account.close()
+}
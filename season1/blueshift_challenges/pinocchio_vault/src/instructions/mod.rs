@@ -0,0 +1,24 @@
+mod approve_delegate;
+mod cancel_withdraw;
+mod deposit;
+mod deposit_token;
+mod execute_withdraw;
+mod helpers;
+mod request_withdraw;
+mod revoke_delegate;
+mod set_timelock;
+mod withdraw;
+mod withdraw_as_delegate;
+mod withdraw_token;
+
+pub use approve_delegate::*;
+pub use cancel_withdraw::*;
+pub use deposit::*;
+pub use deposit_token::*;
+pub use execute_withdraw::*;
+pub use request_withdraw::*;
+pub use revoke_delegate::*;
+pub use set_timelock::*;
+pub use withdraw::*;
+pub use withdraw_as_delegate::*;
+pub use withdraw_token::*;
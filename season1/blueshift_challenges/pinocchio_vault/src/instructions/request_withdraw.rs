@@ -0,0 +1,132 @@
+use core::mem::size_of;
+
+use pinocchio::{
+    error::ProgramError,
+    sysvars::{clock::Clock, Sysvar},
+    AccountView, ProgramResult,
+};
+
+use pinocchio_token::state::TokenAccount;
+
+use crate::errors::VaultError;
+use crate::instructions::helpers::{check_token_vault, check_vault, check_vault_state};
+use crate::state::VaultState;
+
+/// First phase of a time-locked withdrawal: record the amount and start the cooldown.
+/// Passing a mint and its vault ATA after the state requests tokens instead of
+/// lamports; `vault` is then the mint's vault authority.
+pub struct RequestWithdrawAccounts<'a> {
+    pub owner: &'a AccountView,
+    pub vault: &'a AccountView,
+    pub vault_state: &'a AccountView,
+    /// `(mint, vault_ata)` of a token request
+    pub token: Option<(&'a AccountView, &'a AccountView)>,
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for RequestWithdrawAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let [owner, vault, vault_state, token @ ..] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        if !owner.is_signer() {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let token = match token {
+            [] => {
+                check_vault(owner, vault)?;
+                None
+            }
+            [mint, vault_ata] => {
+                check_token_vault(owner, mint, vault, vault_ata)?;
+                Some((mint, vault_ata))
+            }
+            _ => return Err(ProgramError::NotEnoughAccountKeys),
+        };
+        if !check_vault_state(owner, vault_state)? {
+            return Err(ProgramError::UninitializedAccount);
+        }
+
+        Ok(Self {
+            owner,
+            vault,
+            vault_state,
+            token,
+        })
+    }
+}
+
+pub struct RequestWithdrawInstructionData {
+    pub amount: u64,
+}
+
+impl<'a> TryFrom<&'a [u8]> for RequestWithdrawInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        if data.len() != size_of::<u64>() {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let amount = u64::from_le_bytes(data.try_into().unwrap());
+
+        if amount == 0 {
+            return Err(VaultError::InvalidAmount.into());
+        }
+
+        Ok(Self { amount })
+    }
+}
+
+pub struct RequestWithdraw<'a> {
+    pub accounts: RequestWithdrawAccounts<'a>,
+    pub instruction_data: RequestWithdrawInstructionData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountView])> for RequestWithdraw<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountView])) -> Result<Self, Self::Error> {
+        let accounts = RequestWithdrawAccounts::try_from(accounts)?;
+        let instruction_data = RequestWithdrawInstructionData::try_from(data)?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> RequestWithdraw<'a> {
+    pub const DISCRIMINATOR: u8 = 3;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let balance = match self.accounts.token {
+            Some((_, vault_ata)) => TokenAccount::from_account_view(vault_ata)?.amount(),
+            None => self.accounts.vault.lamports(),
+        };
+        if self.instruction_data.amount > balance {
+            return Err(VaultError::InvalidAmount.into());
+        }
+
+        let mut data = self.accounts.vault_state.try_borrow_mut()?;
+        let state = VaultState::load_mut(&mut data)?;
+
+        // One request at a time; cancel it to change the amount
+        if state.has_pending() {
+            return Err(VaultError::PendingWithdrawalExists.into());
+        }
+
+        let ready_at = Clock::get()?
+            .unix_timestamp
+            .checked_add(state.cooldown)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        let mint = self.accounts.token.map(|(mint, _)| mint.address());
+        state.set_pending(self.instruction_data.amount, ready_at, mint);
+
+        Ok(())
+    }
+}
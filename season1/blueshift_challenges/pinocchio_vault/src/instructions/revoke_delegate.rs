@@ -0,0 +1,57 @@
+use pinocchio::{error::ProgramError, AccountView, ProgramResult};
+
+use crate::instructions::helpers::close_program_account;
+use crate::state::Allowance;
+use crate::ID;
+
+/// Revoke a delegate's allowance and return its rent to the owner
+pub struct RevokeDelegateAccounts<'a> {
+    pub owner: &'a AccountView,
+    pub allowance: &'a AccountView,
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for RevokeDelegateAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let [owner, allowance] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        if !owner.is_signer() {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        if !allowance.owned_by(&ID) {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        let data = allowance.try_borrow()?;
+        if &Allowance::load(&data)?.owner != owner.address() {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Ok(Self { owner, allowance })
+    }
+}
+
+pub struct RevokeDelegate<'a> {
+    pub accounts: RevokeDelegateAccounts<'a>,
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for RevokeDelegate<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let accounts = RevokeDelegateAccounts::try_from(accounts)?;
+
+        Ok(Self { accounts })
+    }
+}
+
+impl<'a> RevokeDelegate<'a> {
+    pub const DISCRIMINATOR: u8 = 10;
+
+    pub fn process(&mut self) -> ProgramResult {
+        close_program_account(self.accounts.allowance, self.accounts.owner)
+    }
+}
@@ -0,0 +1,137 @@
+use core::mem::size_of;
+
+use pinocchio::{
+    cpi::{Seed, Signer},
+    error::ProgramError,
+    sysvars::{clock::Clock, Sysvar},
+    AccountView, Address, ProgramResult,
+};
+use pinocchio_system::create_account_with_minimum_balance_signed;
+
+use crate::errors::VaultError;
+use crate::instructions::helpers::{check_vault_state, VAULT_STATE_SEED};
+use crate::state::VaultState;
+use crate::ID;
+
+/// Create or tighten the owner's time lock. A lock can only get stricter:
+/// an active `unlock_at` can't be moved earlier and `cooldown` never shrinks,
+/// so a leaked owner key can't shorten the cooling-off period.
+pub struct SetTimelockAccounts<'a> {
+    pub owner: &'a AccountView,
+    pub vault_state: &'a AccountView,
+    pub initialized: bool,
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for SetTimelockAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let [owner, vault_state, _] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        if !owner.is_signer() {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let initialized = check_vault_state(owner, vault_state)?;
+
+        Ok(Self {
+            owner,
+            vault_state,
+            initialized,
+        })
+    }
+}
+
+pub struct SetTimelockInstructionData {
+    pub unlock_at: i64,
+    pub cooldown: i64,
+}
+
+impl<'a> TryFrom<&'a [u8]> for SetTimelockInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        // unlock_at (8) + cooldown (8)
+        if data.len() != size_of::<i64>() * 2 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let unlock_at = i64::from_le_bytes(data[0..8].try_into().unwrap());
+        let cooldown = i64::from_le_bytes(data[8..16].try_into().unwrap());
+
+        if unlock_at < 0 || cooldown < 0 {
+            return Err(VaultError::InvalidTimelock.into());
+        }
+
+        Ok(Self {
+            unlock_at,
+            cooldown,
+        })
+    }
+}
+
+pub struct SetTimelock<'a> {
+    pub accounts: SetTimelockAccounts<'a>,
+    pub instruction_data: SetTimelockInstructionData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountView])> for SetTimelock<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountView])) -> Result<Self, Self::Error> {
+        let accounts = SetTimelockAccounts::try_from(accounts)?;
+        let instruction_data = SetTimelockInstructionData::try_from(data)?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> SetTimelock<'a> {
+    pub const DISCRIMINATOR: u8 = 2;
+
+    pub fn process(&mut self) -> ProgramResult {
+        if !self.accounts.initialized {
+            let (_, bump) = Address::find_program_address(
+                &[VAULT_STATE_SEED, self.accounts.owner.address().as_ref()],
+                &ID,
+            );
+            let bump_array = [bump];
+            let seeds = [
+                Seed::from(VAULT_STATE_SEED),
+                Seed::from(self.accounts.owner.address().as_ref()),
+                Seed::from(&bump_array[..]),
+            ];
+
+            create_account_with_minimum_balance_signed(
+                self.accounts.vault_state,
+                VaultState::LEN,
+                &ID,
+                self.accounts.owner,
+                None,
+                &[Signer::from(&seeds)],
+            )?;
+
+            let mut data = self.accounts.vault_state.try_borrow_mut()?;
+            VaultState::init(&mut data)?.set_inner(*self.accounts.owner.address(), [bump]);
+        }
+
+        let mut data = self.accounts.vault_state.try_borrow_mut()?;
+        let state = VaultState::load_mut(&mut data)?;
+
+        let now = Clock::get()?.unix_timestamp;
+        if (state.is_locked(now) && self.instruction_data.unlock_at < state.unlock_at)
+            || self.instruction_data.cooldown < state.cooldown
+        {
+            return Err(VaultError::InvalidTimelock.into());
+        }
+
+        state.set_lock(self.instruction_data.unlock_at, self.instruction_data.cooldown);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,107 @@
+use core::mem::size_of;
+
+use pinocchio::{error::ProgramError, AccountView, ProgramResult};
+
+use crate::errors::VaultError;
+use crate::instructions::helpers::{check_direct_withdraw, check_vault, withdraw_from_vault};
+
+pub struct WithdrawAccounts<'a> {
+    pub owner: &'a AccountView,
+    pub vault: &'a AccountView,
+    pub bumps: [u8; 1],
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for WithdrawAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let [owner, vault, vault_state, _] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        if !owner.is_signer() {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+
+        let bump = check_vault(owner, vault)?;
+
+        if vault.lamports() == 0 {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        // The state PDA is always passed so a time lock can't be skipped by omitting it
+        check_direct_withdraw(owner, vault_state)?;
+
+        Ok(Self {
+            owner,
+            vault,
+            bumps: [bump],
+        })
+    }
+}
+
+pub struct WithdrawInstructionData {
+    /// Lamports to withdraw; `None` sweeps the whole vault
+    pub amount: Option<u64>,
+}
+
+impl<'a> TryFrom<&'a [u8]> for WithdrawInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        if data.is_empty() {
+            return Ok(Self { amount: None });
+        }
+        if data.len() != size_of::<u64>() {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let amount = u64::from_le_bytes(data.try_into().unwrap());
+
+        if amount == 0 {
+            return Err(VaultError::InvalidAmount.into());
+        }
+
+        Ok(Self {
+            amount: Some(amount),
+        })
+    }
+}
+
+pub struct Withdraw<'a> {
+    pub accounts: WithdrawAccounts<'a>,
+    pub instruction_data: WithdrawInstructionData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountView])> for Withdraw<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountView])) -> Result<Self, Self::Error> {
+        let accounts = WithdrawAccounts::try_from(accounts)?;
+        let instruction_data = WithdrawInstructionData::try_from(data)?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> Withdraw<'a> {
+    pub const DISCRIMINATOR: u8 = 1;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let amount = self
+            .instruction_data
+            .amount
+            .unwrap_or(self.accounts.vault.lamports());
+
+        withdraw_from_vault(
+            self.accounts.owner,
+            self.accounts.vault,
+            self.accounts.bumps[0],
+            self.accounts.owner,
+            amount,
+        )
+    }
+}
@@ -0,0 +1,126 @@
+use core::mem::size_of;
+
+use pinocchio::{
+    error::ProgramError,
+    sysvars::{clock::Clock, Sysvar},
+    AccountView, ProgramResult,
+};
+
+use crate::errors::VaultError;
+use crate::instructions::helpers::{check_direct_withdraw, check_vault, withdraw_from_vault};
+use crate::state::Allowance;
+use crate::ID;
+
+/// Withdraw from the owner's SOL vault to the delegate, debiting its
+/// allowance. The owner's time lock applies to delegates as well.
+pub struct WithdrawAsDelegateAccounts<'a> {
+    pub delegate: &'a AccountView,
+    pub owner: &'a AccountView,
+    pub vault: &'a AccountView,
+    pub allowance: &'a AccountView,
+    pub bumps: [u8; 1],
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for WithdrawAsDelegateAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let [delegate, owner, vault, allowance, vault_state, _] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        if !delegate.is_signer() {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let bump = check_vault(owner, vault)?;
+        check_direct_withdraw(owner, vault_state)?;
+
+        // Only ApproveDelegate creates program-owned allowances, so matching
+        // owner and delegate fields pin the ("allowance", owner, delegate) PDA
+        if !allowance.owned_by(&ID) {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        {
+            let data = allowance.try_borrow()?;
+            let state = Allowance::load(&data)?;
+            if &state.owner != owner.address() || &state.delegate != delegate.address() {
+                return Err(ProgramError::InvalidAccountData);
+            }
+        }
+
+        Ok(Self {
+            delegate,
+            owner,
+            vault,
+            allowance,
+            bumps: [bump],
+        })
+    }
+}
+
+pub struct WithdrawAsDelegateInstructionData {
+    pub amount: u64,
+}
+
+impl<'a> TryFrom<&'a [u8]> for WithdrawAsDelegateInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        if data.len() != size_of::<u64>() {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let amount = u64::from_le_bytes(data.try_into().unwrap());
+
+        if amount == 0 {
+            return Err(VaultError::InvalidAmount.into());
+        }
+
+        Ok(Self { amount })
+    }
+}
+
+pub struct WithdrawAsDelegate<'a> {
+    pub accounts: WithdrawAsDelegateAccounts<'a>,
+    pub instruction_data: WithdrawAsDelegateInstructionData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountView])> for WithdrawAsDelegate<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountView])) -> Result<Self, Self::Error> {
+        let accounts = WithdrawAsDelegateAccounts::try_from(accounts)?;
+        let instruction_data = WithdrawAsDelegateInstructionData::try_from(data)?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> WithdrawAsDelegate<'a> {
+    pub const DISCRIMINATOR: u8 = 9;
+
+    pub fn process(&mut self) -> ProgramResult {
+        {
+            let mut data = self.accounts.allowance.try_borrow_mut()?;
+            let allowance = Allowance::load_mut(&mut data)?;
+
+            let now = Clock::get()?.unix_timestamp;
+            if allowance.is_expired(now) {
+                return Err(VaultError::AllowanceExpired.into());
+            }
+            allowance.debit(self.instruction_data.amount, now)?;
+        }
+
+        withdraw_from_vault(
+            self.accounts.owner,
+            self.accounts.vault,
+            self.accounts.bumps[0],
+            self.accounts.delegate,
+            self.instruction_data.amount,
+        )
+    }
+}
@@ -0,0 +1,114 @@
+use core::mem::size_of;
+
+use pinocchio::{error::ProgramError, AccountView, ProgramResult};
+
+use crate::errors::VaultError;
+use crate::instructions::helpers::{
+    check_direct_withdraw, check_program_id, check_token_vault, withdraw_from_token_vault,
+};
+
+/// Withdraw SPL tokens from the owner's vault ATA. Emptying it closes the
+/// account and returns its rent to the owner. The owner's time lock applies
+/// as for SOL withdrawals: with a cooldown, use RequestWithdraw / ExecuteWithdraw.
+pub struct WithdrawTokenAccounts<'a> {
+    pub owner: &'a AccountView,
+    pub mint: &'a AccountView,
+    pub owner_ata: &'a AccountView,
+    pub vault_authority: &'a AccountView,
+    pub vault_ata: &'a AccountView,
+    pub bumps: [u8; 1],
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for WithdrawTokenAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let [owner, mint, owner_ata, vault_authority, vault_ata, vault_state, token_program] =
+            accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        if !owner.is_signer() {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        check_program_id(token_program, &pinocchio_token::ID)?;
+        let bump = check_token_vault(owner, mint, vault_authority, vault_ata)?;
+
+        // The state PDA is always passed so a time lock can't be skipped by omitting it
+        check_direct_withdraw(owner, vault_state)?;
+
+        Ok(Self {
+            owner,
+            mint,
+            owner_ata,
+            vault_authority,
+            vault_ata,
+            bumps: [bump],
+        })
+    }
+}
+
+pub struct WithdrawTokenInstructionData {
+    /// Tokens to withdraw; `None` empties and closes the vault
+    pub amount: Option<u64>,
+}
+
+impl<'a> TryFrom<&'a [u8]> for WithdrawTokenInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        if data.is_empty() {
+            return Ok(Self { amount: None });
+        }
+        if data.len() != size_of::<u64>() {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let amount = u64::from_le_bytes(data.try_into().unwrap());
+
+        if amount == 0 {
+            return Err(VaultError::InvalidAmount.into());
+        }
+
+        Ok(Self {
+            amount: Some(amount),
+        })
+    }
+}
+
+pub struct WithdrawToken<'a> {
+    pub accounts: WithdrawTokenAccounts<'a>,
+    pub instruction_data: WithdrawTokenInstructionData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountView])> for WithdrawToken<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountView])) -> Result<Self, Self::Error> {
+        let accounts = WithdrawTokenAccounts::try_from(accounts)?;
+        let instruction_data = WithdrawTokenInstructionData::try_from(data)?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> WithdrawToken<'a> {
+    pub const DISCRIMINATOR: u8 = 7;
+
+    pub fn process(&mut self) -> ProgramResult {
+        withdraw_from_token_vault(
+            self.accounts.owner,
+            self.accounts.mint,
+            self.accounts.vault_authority,
+            self.accounts.bumps[0],
+            self.accounts.vault_ata,
+            self.accounts.owner_ata,
+            self.instruction_data.amount,
+        )
+    }
+}
@@ -1,14 +1,17 @@
 #![no_std]
 
-use pinocchio::{
-    entrypoint, nostd_panic_handler, error::ProgramError, AccountView, Address, ProgramResult,
-};
+use pinocchio::{nostd_panic_handler, error::ProgramError, AccountView, Address, ProgramResult};
 
-entrypoint!(process_instruction);
+#[cfg(not(feature = "no-entrypoint"))]
+pinocchio::entrypoint!(process_instruction);
 nostd_panic_handler!();
 
+pub mod errors;
 pub mod instructions;
+pub mod state;
+
 pub use instructions::*;
+pub use state::*;
 
 pub const ID: Address = Address::new_from_array([
     0x0f, 0x1e, 0x6b, 0x14, 0x21, 0xc0, 0x4a, 0x07, 0x04, 0x31, 0x26, 0x5c, 0x19, 0xc5, 0xbb,
@@ -16,6 +19,7 @@ pub const ID: Address = Address::new_from_array([
     0x11, 0xf7,
 ]);
 
+#[cfg_attr(feature = "no-entrypoint", allow(dead_code))]
 fn process_instruction(
     _program_id: &Address,
     accounts: &[AccountView],
@@ -23,7 +27,30 @@ fn process_instruction(
 ) -> ProgramResult {
     match instruction_data.split_first() {
         Some((&Deposit::DISCRIMINATOR, data)) => Deposit::try_from((data, accounts))?.process(),
-        Some((&Withdraw::DISCRIMINATOR, _)) => Withdraw::try_from(accounts)?.process(),
+        Some((&Withdraw::DISCRIMINATOR, data)) => Withdraw::try_from((data, accounts))?.process(),
+        Some((&SetTimelock::DISCRIMINATOR, data)) => {
+            SetTimelock::try_from((data, accounts))?.process()
+        }
+        Some((&RequestWithdraw::DISCRIMINATOR, data)) => {
+            RequestWithdraw::try_from((data, accounts))?.process()
+        }
+        Some((&ExecuteWithdraw::DISCRIMINATOR, _)) => {
+            ExecuteWithdraw::try_from(accounts)?.process()
+        }
+        Some((&CancelWithdraw::DISCRIMINATOR, _)) => CancelWithdraw::try_from(accounts)?.process(),
+        Some((&DepositToken::DISCRIMINATOR, data)) => {
+            DepositToken::try_from((data, accounts))?.process()
+        }
+        Some((&WithdrawToken::DISCRIMINATOR, data)) => {
+            WithdrawToken::try_from((data, accounts))?.process()
+        }
+        Some((&ApproveDelegate::DISCRIMINATOR, data)) => {
+            ApproveDelegate::try_from((data, accounts))?.process()
+        }
+        Some((&WithdrawAsDelegate::DISCRIMINATOR, data)) => {
+            WithdrawAsDelegate::try_from((data, accounts))?.process()
+        }
+        Some((&RevokeDelegate::DISCRIMINATOR, _)) => RevokeDelegate::try_from(accounts)?.process(),
         _ => Err(ProgramError::InvalidInstructionData),
     }
 }
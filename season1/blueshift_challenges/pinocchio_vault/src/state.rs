@@ -0,0 +1,231 @@
+use core::mem::size_of;
+use pinocchio::{error::ProgramError, Address};
+
+use crate::errors::VaultError;
+
+/// Time lock of an owner's vault, kept next to the system-owned `vault` PDA.
+/// Owners without one withdraw freely.
+#[repr(C)]
+pub struct VaultState {
+    /// `VaultState::DISCRIMINATOR` once initialized
+    pub discriminator: u8,
+    pub bump: [u8; 1],
+    /// Keeps the i64 fields 8-byte aligned
+    pub padding: [u8; 6],
+    pub owner: Address,
+    /// Unix timestamp before which nothing can be withdrawn; 0 = not locked
+    pub unlock_at: i64,
+    /// Seconds between `RequestWithdraw` and `ExecuteWithdraw`; 0 allows direct withdrawals
+    pub cooldown: i64,
+    /// Lamports, or tokens of `pending_mint`, of the pending request; 0 = none
+    pub pending_amount: u64,
+    /// When the pending request can be executed
+    pub pending_ready_at: i64,
+    /// Mint of the token vault the pending request drains; zeroed for lamports
+    pub pending_mint: Address,
+}
+
+impl VaultState {
+    pub const LEN: usize = size_of::<u8>()
+        + size_of::<[u8; 1]>()
+        + size_of::<[u8; 6]>()
+        + size_of::<Address>()
+        + size_of::<i64>()
+        + size_of::<i64>()
+        + size_of::<u64>()
+        + size_of::<i64>()
+        + size_of::<Address>();
+
+    pub const DISCRIMINATOR: u8 = 1;
+
+    /// Stamp the discriminator on freshly created (zeroed) account data
+    #[inline(always)]
+    pub fn init(bytes: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        if bytes.len() != VaultState::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        if bytes[0] != 0 {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+        bytes[0] = VaultState::DISCRIMINATOR;
+        Self::load_mut(bytes)
+    }
+
+    #[inline(always)]
+    pub fn load_mut(bytes: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        if bytes.len() != VaultState::LEN || bytes[0] != VaultState::DISCRIMINATOR {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Ok(unsafe {
+            &mut *core::mem::transmute::<*mut u8, *mut Self>(bytes.as_mut_ptr())
+        })
+    }
+
+    #[inline(always)]
+    pub fn load(bytes: &[u8]) -> Result<&Self, ProgramError> {
+        if bytes.len() != VaultState::LEN || bytes[0] != VaultState::DISCRIMINATOR {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Ok(unsafe { &*core::mem::transmute::<*const u8, *const Self>(bytes.as_ptr()) })
+    }
+
+    #[inline(always)]
+    pub fn set_inner(&mut self, owner: Address, bump: [u8; 1]) {
+        self.owner = owner;
+        self.bump = bump;
+    }
+
+    #[inline(always)]
+    pub fn set_lock(&mut self, unlock_at: i64, cooldown: i64) {
+        self.unlock_at = unlock_at;
+        self.cooldown = cooldown;
+    }
+
+    #[inline(always)]
+    pub fn set_pending(&mut self, amount: u64, ready_at: i64, mint: Option<&Address>) {
+        self.pending_amount = amount;
+        self.pending_ready_at = ready_at;
+        self.pending_mint = mint.copied().unwrap_or_default();
+    }
+
+    #[inline(always)]
+    pub fn clear_pending(&mut self) {
+        self.set_pending(0, 0, None);
+    }
+
+    /// Mint of the pending request; `None` when it withdraws lamports
+    #[inline(always)]
+    pub fn pending_mint(&self) -> Option<&Address> {
+        (self.pending_mint != Address::default()).then_some(&self.pending_mint)
+    }
+
+    #[inline(always)]
+    pub fn has_pending(&self) -> bool {
+        self.pending_amount != 0
+    }
+
+    #[inline(always)]
+    pub fn is_locked(&self, now: i64) -> bool {
+        now < self.unlock_at
+    }
+}
+
+const _: () = assert!(VaultState::LEN == size_of::<VaultState>());
+
+/// Spending allowance an owner grants a delegate over their SOL vault
+#[repr(C)]
+pub struct Allowance {
+    /// `Allowance::DISCRIMINATOR` once initialized
+    pub discriminator: u8,
+    pub bump: [u8; 1],
+    /// Keeps the u64 / i64 fields 8-byte aligned
+    pub padding: [u8; 6],
+    pub owner: Address,
+    pub delegate: Address,
+    /// Lamports the delegate can still withdraw in total
+    pub remaining: u64,
+    /// Unix timestamp from which the allowance is void; 0 = never expires
+    pub expires_at: i64,
+    /// Length of a spending period in seconds; 0 = no per-period limit
+    pub period: i64,
+    /// Lamports the delegate can withdraw per period
+    pub period_limit: u64,
+    pub period_start: i64,
+    pub period_spent: u64,
+}
+
+impl Allowance {
+    pub const LEN: usize = size_of::<u8>()
+        + size_of::<[u8; 1]>()
+        + size_of::<[u8; 6]>()
+        + size_of::<Address>()
+        + size_of::<Address>()
+        + size_of::<u64>()
+        + size_of::<i64>()
+        + size_of::<i64>()
+        + size_of::<u64>()
+        + size_of::<i64>()
+        + size_of::<u64>();
+
+    pub const DISCRIMINATOR: u8 = 2;
+
+    /// Stamp the discriminator on freshly created (zeroed) account data
+    #[inline(always)]
+    pub fn init(bytes: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        if bytes.len() != Allowance::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        if bytes[0] != 0 {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+        bytes[0] = Allowance::DISCRIMINATOR;
+        Self::load_mut(bytes)
+    }
+
+    #[inline(always)]
+    pub fn load_mut(bytes: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        if bytes.len() != Allowance::LEN || bytes[0] != Allowance::DISCRIMINATOR {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Ok(unsafe {
+            &mut *core::mem::transmute::<*mut u8, *mut Self>(bytes.as_mut_ptr())
+        })
+    }
+
+    #[inline(always)]
+    pub fn load(bytes: &[u8]) -> Result<&Self, ProgramError> {
+        if bytes.len() != Allowance::LEN || bytes[0] != Allowance::DISCRIMINATOR {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Ok(unsafe { &*core::mem::transmute::<*const u8, *const Self>(bytes.as_ptr()) })
+    }
+
+    #[inline(always)]
+    pub fn set_inner(&mut self, owner: Address, delegate: Address, bump: [u8; 1]) {
+        self.owner = owner;
+        self.delegate = delegate;
+        self.bump = bump;
+    }
+
+    /// Replace the terms; the current period starts over at `now`
+    #[inline(always)]
+    pub fn set_terms(&mut self, amount: u64, expires_at: i64, period: i64, period_limit: u64, now: i64) {
+        self.remaining = amount;
+        self.expires_at = expires_at;
+        self.period = period;
+        self.period_limit = period_limit;
+        self.period_start = now;
+        self.period_spent = 0;
+    }
+
+    #[inline(always)]
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.expires_at != 0 && now >= self.expires_at
+    }
+
+    /// Charge `amount` against the total cap and the current period, rolling
+    /// the period over once it has elapsed
+    pub fn debit(&mut self, amount: u64, now: i64) -> Result<(), VaultError> {
+        if amount > self.remaining {
+            return Err(VaultError::AllowanceExceeded);
+        }
+
+        if self.period != 0 {
+            if now.saturating_sub(self.period_start) >= self.period {
+                self.period_start = now;
+                self.period_spent = 0;
+            }
+            let spent = self
+                .period_spent
+                .checked_add(amount)
+                .filter(|spent| *spent <= self.period_limit)
+                .ok_or(VaultError::PeriodLimitExceeded)?;
+            self.period_spent = spent;
+        }
+
+        self.remaining -= amount;
+        Ok(())
+    }
+}
+
+const _: () = assert!(Allowance::LEN == size_of::<Allowance>());
@@ -1,3 +1,6 @@
+// 每个指令模块都导出 `handler`，Anchor 需要 glob 导出 Accounts 结构体
+#![allow(ambiguous_glob_reexports)]
+
 pub mod initialize;
 pub mod deposit;
 pub mod withdraw;
@@ -27,7 +27,7 @@ pub fn handler(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
     let min_balance = rent.minimum_balance(vault.to_account_info().data_len());
     
     let current_lamports = vault.to_account_info().lamports();
-    let available_amount = current_lamports.checked_sub(min_balance).unwrap_or(0);
+    let available_amount = current_lamports.saturating_sub(min_balance);
 
     require!(amount <= available_amount, VaultError::InsufficientBalance);
 
@@ -0,0 +1,454 @@
+//! anchor_flash_loan LiteSVM 测试的公共代码：账户准备与指令构造
+//!
+//! 指令数据按 Anchor 格式手工编码：`sha256("global:<name>")[..8]` + borsh 参数。
+//! 运行前请先在 season1/ 构建程序：`cargo build-programs`，测试默认 ignored，用 `cargo test-programs` 运行。
+
+#![allow(dead_code, clippy::result_large_err)]
+
+use std::str::FromStr;
+
+use litesvm::types::TransactionResult;
+use litesvm::LiteSVM;
+use solana_address::Address;
+use solana_instruction::error::InstructionError;
+use solana_instruction::{AccountMeta, Instruction};
+use solana_keypair::Keypair;
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+use solana_transaction_error::TransactionError;
+use test_support::{
+    associated_token_address, associated_token_program_id, mint_account, svm_with_programs,
+    system_program_id, token_account, token_program_id,
+};
+
+/// anchor_flash_loan 的 `declare_id!`
+const ANCHOR_FLASH_LOAN_ID: &str = "22222222222222222222222222222222222222222222";
+
+/// flash_loan_receiver 的 `declare_id!`
+const FLASH_LOAN_RECEIVER_ID: &str = "55iixe5x6bwYDPZC549VdTUyZEm7tf4EsTUWZdDrC4Pv";
+
+/// Instructions sysvar：Sysvar1nstructions1111111111111111111111111
+const INSTRUCTIONS_SYSVAR_ID: &str = "Sysvar1nstructions1111111111111111111111111";
+
+pub const MINT_DECIMALS: u8 = 6;
+/// lender 存入池子的初始流动性
+pub const LIQUIDITY: u64 = 1_000_000_000;
+/// borrower 初始 token 余额，用来支付手续费
+pub const BORROWER_BALANCE: u64 = 100_000_000;
+/// 默认配置：3% 手续费，单笔最多借出池子的 50%
+pub const FEE_BPS: u16 = 300;
+pub const MAX_BORROW_BPS: u16 = 5_000;
+
+/// ProtocolError 错误码
+pub const INVALID_INSTRUCTION_INDEX: u32 = 6001;
+pub const INVALID_AMOUNT: u32 = 6002;
+pub const NOT_ENOUGH_FUNDS: u32 = 6003;
+pub const PROGRAM_MISMATCH: u32 = 6004;
+pub const INVALID_PROGRAM: u32 = 6005;
+pub const MISSING_REPAY_IX: u32 = 6008;
+pub const MISSING_BORROW_IX: u32 = 6009;
+pub const PROTOCOL_PAUSED: u32 = 6011;
+pub const INVALID_FEE: u32 = 6012;
+pub const INVALID_BORROW_FRACTION: u32 = 6013;
+pub const EXCEEDS_MAX_BORROW: u32 = 6014;
+pub const UNAUTHORIZED: u32 = 6015;
+pub const NESTED_LOAN: u32 = 6016;
+pub const LOAN_MISMATCH: u32 = 6017;
+pub const POOL_BUSY: u32 = 6018;
+pub const FLASH_LOAN_NOT_REPAID: u32 = 6019;
+pub const POOL_NOT_EMPTY: u32 = 6020;
+
+pub fn program_id() -> Address {
+    Address::from_str(ANCHOR_FLASH_LOAN_ID).unwrap()
+}
+
+pub fn receiver_program_id() -> Address {
+    Address::from_str(FLASH_LOAN_RECEIVER_ID).unwrap()
+}
+
+pub fn instructions_sysvar_id() -> Address {
+    Address::from_str(INSTRUCTIONS_SYSVAR_ID).unwrap()
+}
+
+/// Anchor 指令 discriminator
+pub fn discriminator(name: &str) -> [u8; 8] {
+    let hash = solana_sha256_hasher::hash(format!("global:{name}").as_bytes()).to_bytes();
+    hash[..8].try_into().unwrap()
+}
+
+pub fn find_pda(seed: &[u8]) -> Address {
+    Address::find_program_address(&[seed], &program_id()).0
+}
+
+/// 以 mint 区分的 PDA：`pool` / `shares`
+pub fn find_mint_pda(seed: &[u8], mint: &Address) -> Address {
+    Address::find_program_address(&[seed, mint.as_ref()], &program_id()).0
+}
+
+/// 借款 `amount` 需归还的数量：本金 + `fee_bps` 手续费（向下取整）
+pub fn repay_amount(amount: u64, fee_bps: u16) -> u64 {
+    amount + amount * fee_bps as u64 / 10_000
+}
+
+/// 向持有 `assets`、已发行 `supply` 份额的池子存入 `amount` 得到的份额：
+/// 定价时加 1 份虚拟份额与 1 个虚拟 token，向下取整
+pub fn shares_for_deposit(amount: u64, assets: u64, supply: u64) -> u64 {
+    (amount as u128 * (supply as u128 + 1) / (assets as u128 + 1)) as u64
+}
+
+/// 赎回 `shares` 得到的 token 数量，同样含虚拟偏移、向下取整
+pub fn amount_for_shares(shares: u64, assets: u64, supply: u64) -> u64 {
+    (shares as u128 * (assets as u128 + 1) / (supply as u128 + 1)) as u64
+}
+
+/// 一个 mint 的池子及各方的 token 账户
+#[derive(Clone, Copy)]
+pub struct Market {
+    pub mint: Address,
+    pub pool: Address,
+    pub share_mint: Address,
+    pub protocol_ata: Address,
+    pub borrower_ata: Address,
+    pub lender_ata: Address,
+    pub lender_share_ata: Address,
+}
+
+impl Market {
+    /// 新建一个 mint：borrower 持有 BORROWER_BALANCE，lender 持有 LIQUIDITY
+    fn create(svm: &mut LiteSVM, protocol: &Address, borrower: &Address, lender: &Address) -> Self {
+        let mint = Address::new_unique();
+        svm.set_account(
+            mint,
+            mint_account(MINT_DECIMALS, LIQUIDITY + BORROWER_BALANCE),
+        )
+        .unwrap();
+
+        let share_mint = find_mint_pda(b"shares", &mint);
+        let market = Self {
+            mint,
+            pool: find_mint_pda(b"pool", &mint),
+            share_mint,
+            protocol_ata: associated_token_address(protocol, &mint),
+            borrower_ata: associated_token_address(borrower, &mint),
+            lender_ata: associated_token_address(lender, &mint),
+            lender_share_ata: associated_token_address(lender, &share_mint),
+        };
+        svm.set_account(
+            market.borrower_ata,
+            token_account(&mint, borrower, BORROWER_BALANCE),
+        )
+        .unwrap();
+        svm.set_account(market.lender_ata, token_account(&mint, lender, LIQUIDITY))
+            .unwrap();
+        market
+    }
+}
+
+/// 加载 anchor_flash_loan 与 flash_loan_receiver，准备 admin、borrower、lender 和默认 mint；未构建二进制时 panic
+pub struct Fixture {
+    pub svm: LiteSVM,
+    pub admin: Keypair,
+    pub borrower: Keypair,
+    pub lender: Keypair,
+    pub protocol: Address,
+    pub config: Address,
+    /// 默认 mint，`borrow_ix` / `repay_ix` 使用它的池子
+    pub market: Market,
+}
+
+impl Fixture {
+    pub fn new() -> Self {
+        let mut svm = svm_with_programs(&[
+            (program_id(), "anchor_flash_loan"),
+            (receiver_program_id(), "flash_loan_receiver"),
+        ]);
+        let admin = Keypair::new();
+        let borrower = Keypair::new();
+        let lender = Keypair::new();
+        svm.airdrop(&admin.pubkey(), 10_000_000_000).unwrap();
+        svm.airdrop(&borrower.pubkey(), 10_000_000_000).unwrap();
+        svm.airdrop(&lender.pubkey(), 10_000_000_000).unwrap();
+
+        let protocol = find_pda(b"protocol");
+        let market = Market::create(&mut svm, &protocol, &borrower.pubkey(), &lender.pubkey());
+
+        Self {
+            svm,
+            admin,
+            borrower,
+            lender,
+            protocol,
+            config: find_pda(b"config"),
+            market,
+        }
+    }
+
+    /// 已用默认参数初始化配置、创建默认池子并由 lender 存入 LIQUIDITY 的场景
+    pub fn configured() -> Self {
+        let mut fixture = Self::new();
+        let result =
+            fixture.send_as_admin(&[fixture.initialize_config_ix(FEE_BPS, MAX_BORROW_BPS)]);
+        assert!(result.is_ok(), "initialize_config 应成功: {:?}", result);
+        let market = fixture.market;
+        fixture.open_market(&market);
+        fixture
+    }
+
+    /// 新建一个尚未开池的 mint
+    pub fn add_market(&mut self) -> Market {
+        Market::create(
+            &mut self.svm,
+            &self.protocol,
+            &self.borrower.pubkey(),
+            &self.lender.pubkey(),
+        )
+    }
+
+    /// 为 `market` 创建池子并由 lender 存入全部 LIQUIDITY
+    pub fn open_market(&mut self, market: &Market) {
+        let result = self.send_as_admin(&[self.create_pool_ix(market)]);
+        assert!(result.is_ok(), "create_pool 应成功: {:?}", result);
+        let result = self.send_as_lender(&[self.deposit_liquidity_ix(market, LIQUIDITY)]);
+        assert!(result.is_ok(), "deposit_liquidity 应成功: {:?}", result);
+    }
+
+    pub fn initialize_config_ix(&self, fee_bps: u16, max_borrow_bps: u16) -> Instruction {
+        let mut data = discriminator("initialize_config").to_vec();
+        data.extend_from_slice(&fee_bps.to_le_bytes());
+        data.extend_from_slice(&max_borrow_bps.to_le_bytes());
+        Instruction {
+            program_id: program_id(),
+            accounts: vec![
+                AccountMeta::new(self.admin.pubkey(), true),
+                AccountMeta::new(self.config, false),
+                AccountMeta::new_readonly(system_program_id(), false),
+            ],
+            data,
+        }
+    }
+
+    fn admin_ix(&self, admin: &Address, data: Vec<u8>) -> Instruction {
+        Instruction {
+            program_id: program_id(),
+            accounts: vec![
+                AccountMeta::new_readonly(*admin, true),
+                AccountMeta::new(self.config, false),
+            ],
+            data,
+        }
+    }
+
+    pub fn update_config_ix(
+        &self,
+        admin: &Address,
+        fee_bps: u16,
+        max_borrow_bps: u16,
+    ) -> Instruction {
+        let mut data = discriminator("update_config").to_vec();
+        data.extend_from_slice(&fee_bps.to_le_bytes());
+        data.extend_from_slice(&max_borrow_bps.to_le_bytes());
+        self.admin_ix(admin, data)
+    }
+
+    pub fn set_paused_ix(&self, admin: &Address, paused: bool) -> Instruction {
+        let mut data = discriminator("set_paused").to_vec();
+        data.push(paused as u8);
+        self.admin_ix(admin, data)
+    }
+
+    pub fn transfer_admin_ix(&self, admin: &Address, new_admin: &Address) -> Instruction {
+        let mut data = discriminator("transfer_admin").to_vec();
+        data.extend_from_slice(new_admin.as_ref());
+        self.admin_ix(admin, data)
+    }
+
+    pub fn create_pool_ix(&self, market: &Market) -> Instruction {
+        Instruction {
+            program_id: program_id(),
+            accounts: vec![
+                AccountMeta::new(self.admin.pubkey(), true),
+                AccountMeta::new_readonly(self.config, false),
+                AccountMeta::new_readonly(self.protocol, false),
+                AccountMeta::new_readonly(market.mint, false),
+                AccountMeta::new(market.pool, false),
+                AccountMeta::new(market.share_mint, false),
+                AccountMeta::new(market.protocol_ata, false),
+                AccountMeta::new_readonly(token_program_id(), false),
+                AccountMeta::new_readonly(associated_token_program_id(), false),
+                AccountMeta::new_readonly(system_program_id(), false),
+            ],
+            data: discriminator("create_pool").to_vec(),
+        }
+    }
+
+    /// deposit_liquidity / withdraw_liquidity 共用的账户，顺序与 `Liquidity` 一致
+    fn liquidity_ix(&self, market: &Market, name: &str, amount: u64) -> Instruction {
+        let mut data = discriminator(name).to_vec();
+        data.extend_from_slice(&amount.to_le_bytes());
+        Instruction {
+            program_id: program_id(),
+            accounts: vec![
+                AccountMeta::new(self.lender.pubkey(), true),
+                AccountMeta::new_readonly(self.protocol, false),
+                AccountMeta::new_readonly(market.mint, false),
+                AccountMeta::new_readonly(market.pool, false),
+                AccountMeta::new(market.share_mint, false),
+                AccountMeta::new(market.lender_ata, false),
+                AccountMeta::new(market.lender_share_ata, false),
+                AccountMeta::new(market.protocol_ata, false),
+                AccountMeta::new_readonly(token_program_id(), false),
+                AccountMeta::new_readonly(associated_token_program_id(), false),
+                AccountMeta::new_readonly(system_program_id(), false),
+            ],
+            data,
+        }
+    }
+
+    pub fn deposit_liquidity_ix(&self, market: &Market, amount: u64) -> Instruction {
+        self.liquidity_ix(market, "deposit_liquidity", amount)
+    }
+
+    pub fn withdraw_liquidity_ix(&self, market: &Market, shares: u64) -> Instruction {
+        self.liquidity_ix(market, "withdraw_liquidity", shares)
+    }
+
+    /// borrow / repay 共用的账户，顺序与 `Loan` 一致
+    pub fn loan_accounts(&self, market: &Market) -> Vec<AccountMeta> {
+        vec![
+            AccountMeta::new(self.borrower.pubkey(), true),
+            AccountMeta::new_readonly(self.protocol, false),
+            AccountMeta::new_readonly(self.config, false),
+            AccountMeta::new_readonly(market.mint, false),
+            AccountMeta::new(market.borrower_ata, false),
+            AccountMeta::new(market.protocol_ata, false),
+            AccountMeta::new(market.pool, false),
+            AccountMeta::new_readonly(instructions_sysvar_id(), false),
+            AccountMeta::new_readonly(token_program_id(), false),
+            AccountMeta::new_readonly(associated_token_program_id(), false),
+            AccountMeta::new_readonly(system_program_id(), false),
+        ]
+    }
+
+    /// 默认池子上的 borrow，`repay_index` 为配对 repay 在交易中的位置
+    pub fn borrow_ix(&self, amount: u64, repay_index: u16) -> Instruction {
+        self.borrow_ix_in(&self.market, amount, repay_index)
+    }
+
+    pub fn borrow_ix_in(&self, market: &Market, amount: u64, repay_index: u16) -> Instruction {
+        let mut data = discriminator("borrow").to_vec();
+        data.extend_from_slice(&amount.to_le_bytes());
+        data.extend_from_slice(&repay_index.to_le_bytes());
+        Instruction {
+            program_id: program_id(),
+            accounts: self.loan_accounts(market),
+            data,
+        }
+    }
+
+    pub fn repay_ix(&self) -> Instruction {
+        self.repay_ix_in(&self.market)
+    }
+
+    pub fn repay_ix_in(&self, market: &Market) -> Instruction {
+        Instruction {
+            program_id: program_id(),
+            accounts: self.loan_accounts(market),
+            data: discriminator("repay").to_vec(),
+        }
+    }
+
+    /// 借给 borrower 的 ATA，由 flash_loan_receiver 回调归还；
+    /// `data` 原样转发给回调（非空时前 8 字节为 receiver 实际归还的数量）
+    pub fn flash_loan_ix(&self, market: &Market, amount: u64, data: &[u8]) -> Instruction {
+        let mut ix_data = discriminator("flash_loan").to_vec();
+        ix_data.extend_from_slice(&amount.to_le_bytes());
+        ix_data.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        ix_data.extend_from_slice(data);
+        Instruction {
+            program_id: program_id(),
+            accounts: vec![
+                AccountMeta::new(self.borrower.pubkey(), true),
+                AccountMeta::new_readonly(self.protocol, false),
+                AccountMeta::new_readonly(self.config, false),
+                AccountMeta::new_readonly(market.mint, false),
+                AccountMeta::new(market.pool, false),
+                AccountMeta::new(market.borrower_ata, false),
+                AccountMeta::new(market.protocol_ata, false),
+                AccountMeta::new_readonly(receiver_program_id(), false),
+                AccountMeta::new_readonly(token_program_id(), false),
+            ],
+            data: ix_data,
+        }
+    }
+
+    /// 以 `signer` 为 payer 和唯一签名者发送一笔多指令交易
+    pub fn send(&mut self, ixs: &[Instruction], signer: &Keypair) -> TransactionResult {
+        self.svm.expire_blockhash();
+        let tx = Transaction::new_signed_with_payer(
+            ixs,
+            Some(&signer.pubkey()),
+            &[signer],
+            self.svm.latest_blockhash(),
+        );
+        self.svm.send_transaction(tx)
+    }
+
+    /// 由 `signers[0]` 付交易费，所有 `signers` 签名
+    pub fn send_signed(&mut self, ixs: &[Instruction], signers: &[&Keypair]) -> TransactionResult {
+        self.svm.expire_blockhash();
+        let tx = Transaction::new_signed_with_payer(
+            ixs,
+            Some(&signers[0].pubkey()),
+            signers,
+            self.svm.latest_blockhash(),
+        );
+        self.svm.send_transaction(tx)
+    }
+
+    pub fn send_as_admin(&mut self, ixs: &[Instruction]) -> TransactionResult {
+        let admin = self.admin.insecure_clone();
+        self.send(ixs, &admin)
+    }
+
+    pub fn send_as_borrower(&mut self, ixs: &[Instruction]) -> TransactionResult {
+        let borrower = self.borrower.insecure_clone();
+        self.send(ixs, &borrower)
+    }
+
+    pub fn send_as_lender(&mut self, ixs: &[Instruction]) -> TransactionResult {
+        let lender = self.lender.insecure_clone();
+        self.send(ixs, &lender)
+    }
+
+    pub fn share_supply(&self, market: &Market) -> u64 {
+        self.svm
+            .get_account(&market.share_mint)
+            .map(|account| test_support::mint_supply(&account.data))
+            .unwrap_or(0)
+    }
+
+    pub fn token_balance(&self, address: &Address) -> u64 {
+        self.svm
+            .get_account(address)
+            .map(|account| test_support::token_account_amount(&account.data))
+            .unwrap_or(0)
+    }
+}
+
+/// 断言交易在第 `index` 条指令因指定的 InstructionError 失败
+pub fn assert_instruction_error(result: TransactionResult, index: u8, expected: InstructionError) {
+    match result {
+        Err(failed) => assert_eq!(
+            failed.err,
+            TransactionError::InstructionError(index, expected),
+            "logs: {:#?}",
+            failed.meta.logs
+        ),
+        Ok(_) => panic!("交易应失败，期望 {:?}", expected),
+    }
+}
+
+/// 断言交易在第 `index` 条指令因指定的 ProtocolError 自定义错误码失败
+pub fn assert_custom_error(result: TransactionResult, index: u8, code: u32) {
+    assert_instruction_error(result, index, InstructionError::Custom(code));
+}
@@ -0,0 +1,109 @@
+//! LiteSVM 集成测试：anchor_flash_loan 的 flash_loan 指令与 receiver 回调
+//!
+//! 运行前请先在 season1/ 构建程序：`cargo build-programs`（输出到 target/deploy），
+//! 测试默认 ignored，用 `cargo test-programs` 运行。
+
+mod common;
+
+use common::*;
+use solana_signer::Signer;
+
+const LOAN: u64 = 200_000_000;
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_flash_loan_repaid_by_receiver() {
+    let mut fixture = Fixture::configured();
+    let market = fixture.market;
+    let result = fixture.send_as_borrower(&[fixture.flash_loan_ix(&market, LOAN, &[])]);
+    assert!(result.is_ok(), "flash_loan 应成功: {:?}", result);
+
+    let fee = repay_amount(LOAN, FEE_BPS) - LOAN;
+    assert_eq!(fixture.token_balance(&market.protocol_ata), LIQUIDITY + fee);
+    assert_eq!(
+        fixture.token_balance(&market.borrower_ata),
+        BORROWER_BALANCE - fee
+    );
+
+    // 借款结束后池子空闲：同一交易里紧接着再借、再赎回都可以
+    let lender = fixture.lender.insecure_clone();
+    let borrower = fixture.borrower.insecure_clone();
+    let result = fixture.send_signed(
+        &[
+            fixture.flash_loan_ix(&market, LOAN, &[]),
+            fixture.flash_loan_ix(&market, LOAN, &[]),
+            fixture.withdraw_liquidity_ix(&market, LIQUIDITY),
+        ],
+        &[&borrower, &lender],
+    );
+    assert!(result.is_ok(), "连续 flash_loan 后赎回应成功: {:?}", result);
+    let assets = LIQUIDITY + 3 * fee;
+    let withdrawn = amount_for_shares(LIQUIDITY, assets, LIQUIDITY);
+    assert_eq!(fixture.token_balance(&market.lender_ata), withdrawn);
+    // 虚拟份额分走的零头留在池子里
+    assert!(assets - withdrawn <= 1);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_flash_loan_fail_under_repaid() {
+    let mut fixture = Fixture::configured();
+    let market = fixture.market;
+    // receiver 只归还本金，不付手续费
+    let result =
+        fixture.send_as_borrower(&[fixture.flash_loan_ix(&market, LOAN, &LOAN.to_le_bytes())]);
+    assert_custom_error(result, 0, FLASH_LOAN_NOT_REPAID);
+    assert_eq!(fixture.token_balance(&market.protocol_ata), LIQUIDITY);
+
+    // 多付也可以
+    let overpaid = repay_amount(LOAN, FEE_BPS) + 1;
+    let result =
+        fixture.send_as_borrower(&[fixture.flash_loan_ix(&market, LOAN, &overpaid.to_le_bytes())]);
+    assert!(result.is_ok(), "多付应成功: {:?}", result);
+    assert_eq!(
+        fixture.token_balance(&market.protocol_ata),
+        LIQUIDITY + overpaid - LOAN
+    );
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_flash_loan_fail_receiver_is_protocol() {
+    let mut fixture = Fixture::configured();
+    let market = fixture.market;
+    let mut ix = fixture.flash_loan_ix(&market, LOAN, &[]);
+    ix.accounts[7].pubkey = program_id();
+    let result = fixture.send_as_borrower(&[ix]);
+    assert_custom_error(result, 0, INVALID_PROGRAM);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_flash_loan_fail_inside_borrow() {
+    let mut fixture = Fixture::configured();
+    let market = fixture.market;
+    let result = fixture.send_as_borrower(&[
+        fixture.borrow_ix(LOAN, 2),
+        fixture.flash_loan_ix(&market, LOAN, &[]),
+        fixture.repay_ix(),
+    ]);
+    assert_custom_error(result, 1, POOL_BUSY);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_flash_loan_respects_config() {
+    let mut fixture = Fixture::configured();
+    let market = fixture.market;
+    let max = LIQUIDITY * MAX_BORROW_BPS as u64 / 10_000;
+    let result = fixture.send_as_borrower(&[fixture.flash_loan_ix(&market, max + 1, &[])]);
+    assert_custom_error(result, 0, EXCEEDS_MAX_BORROW);
+    let result = fixture.send_as_borrower(&[fixture.flash_loan_ix(&market, 0, &[])]);
+    assert_custom_error(result, 0, INVALID_AMOUNT);
+
+    let admin = fixture.admin.pubkey();
+    let result = fixture.send_as_admin(&[fixture.set_paused_ix(&admin, true)]);
+    assert!(result.is_ok(), "set_paused 应成功: {:?}", result);
+    let result = fixture.send_as_borrower(&[fixture.flash_loan_ix(&market, LOAN, &[])]);
+    assert_custom_error(result, 0, PROTOCOL_PAUSED);
+}
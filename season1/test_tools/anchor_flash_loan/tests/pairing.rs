@@ -0,0 +1,148 @@
+//! LiteSVM 集成测试：anchor_flash_loan 的 borrow / repay 严格配对
+//!
+//! 每个用例对应一种利用方式：漏掉 repay、repay 指向错误位置、
+//! 嵌套或重复借款、borrow 与 repay 使用不同账户、少还款。
+//! 运行前请先在 season1/ 构建程序：`cargo build-programs`（输出到 target/deploy），
+//! 测试默认 ignored，用 `cargo test-programs` 运行。
+
+mod common;
+
+use common::*;
+use solana_address::Address;
+use solana_instruction::{AccountMeta, Instruction};
+use solana_signer::Signer;
+use test_support::{system_program_id, token_account};
+
+const LOAN: u64 = 100_000_000;
+
+/// 一条与借贷无关的指令：borrower 给自己转 1 lamport
+fn noop_ix(fixture: &Fixture) -> Instruction {
+    let borrower = fixture.borrower.pubkey();
+    let mut data = 2u32.to_le_bytes().to_vec();
+    data.extend_from_slice(&1u64.to_le_bytes());
+    Instruction {
+        program_id: system_program_id(),
+        accounts: vec![
+            AccountMeta::new(borrower, true),
+            AccountMeta::new(borrower, false),
+        ],
+        data,
+    }
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_sequential_loans() {
+    let mut fixture = Fixture::configured();
+    let result = fixture.send_as_borrower(&[
+        fixture.borrow_ix(LOAN, 1),
+        fixture.repay_ix(),
+        fixture.borrow_ix(LOAN, 4),
+        noop_ix(&fixture),
+        fixture.repay_ix(),
+    ]);
+    assert!(result.is_ok(), "两笔独立借款应成功: {:?}", result);
+
+    let fee = repay_amount(LOAN, FEE_BPS) - LOAN;
+    assert_eq!(
+        fixture.token_balance(&fixture.market.protocol_ata),
+        LIQUIDITY + 2 * fee
+    );
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_missing_repay() {
+    let mut fixture = Fixture::configured();
+    let result = fixture.send_as_borrower(&[fixture.borrow_ix(LOAN, 1)]);
+    assert_custom_error(result, 0, MISSING_REPAY_IX);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_repay_index_must_point_at_repay() {
+    let mut fixture = Fixture::configured();
+    // repay 实际在 2，borrow 却指向 1 处的其他程序指令
+    let result = fixture.send_as_borrower(&[
+        fixture.borrow_ix(LOAN, 1),
+        noop_ix(&fixture),
+        fixture.repay_ix(),
+    ]);
+    assert_custom_error(result, 0, PROGRAM_MISMATCH);
+
+    // 不能指向自己或之前的指令
+    let result = fixture.send_as_borrower(&[fixture.borrow_ix(LOAN, 0), fixture.repay_ix()]);
+    assert_custom_error(result, 0, INVALID_INSTRUCTION_INDEX);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_nested_borrows_rejected() {
+    let mut fixture = Fixture::configured();
+    // 两笔借款共用一个 repay：只还一次就借走两份
+    let result = fixture.send_as_borrower(&[
+        fixture.borrow_ix(LOAN, 2),
+        fixture.borrow_ix(LOAN, 2),
+        fixture.repay_ix(),
+    ]);
+    assert_custom_error(result, 0, NESTED_LOAN);
+
+    // 外层借款包住一对完整的内层借款
+    let result = fixture.send_as_borrower(&[
+        fixture.borrow_ix(LOAN, 3),
+        fixture.borrow_ix(LOAN, 2),
+        fixture.repay_ix(),
+        fixture.repay_ix(),
+    ]);
+    assert_custom_error(result, 0, NESTED_LOAN);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_repay_without_borrow() {
+    let mut fixture = Fixture::configured();
+    let result = fixture.send_as_borrower(&[fixture.repay_ix()]);
+    assert_custom_error(result, 0, MISSING_BORROW_IX);
+
+    // 多出来的 repay 不能再和已经配对的 borrow 匹配
+    let result = fixture.send_as_borrower(&[
+        fixture.borrow_ix(LOAN, 1),
+        fixture.repay_ix(),
+        fixture.repay_ix(),
+    ]);
+    assert_custom_error(result, 2, MISSING_BORROW_IX);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_repay_on_other_accounts_rejected() {
+    let mut fixture = Fixture::configured();
+    // repay 换成另一个 mint 的账户：借 A 还 B
+    for index in [3, 4, 5] {
+        let mut repay = fixture.repay_ix();
+        repay.accounts[index].pubkey = Address::new_unique();
+        let result = fixture.send_as_borrower(&[fixture.borrow_ix(LOAN, 1), repay]);
+        assert_custom_error(result, 0, LOAN_MISMATCH);
+    }
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_under_repay() {
+    let mut fixture = Fixture::configured();
+    // borrower 没有余额支付手续费
+    let borrower = fixture.borrower.pubkey();
+    fixture
+        .svm
+        .set_account(
+            fixture.market.borrower_ata,
+            token_account(&fixture.market.mint, &borrower, 0),
+        )
+        .unwrap();
+    let result = fixture.send_as_borrower(&[fixture.borrow_ix(LOAN, 1), fixture.repay_ix()]);
+    assert_custom_error(result, 1, NOT_ENOUGH_FUNDS);
+    assert_eq!(
+        fixture.token_balance(&fixture.market.protocol_ata),
+        LIQUIDITY
+    );
+}
@@ -0,0 +1,255 @@
+//! LiteSVM 集成测试：anchor_flash_loan 的按 mint 划分的 LP 池子
+//!
+//! 运行前请先在 season1/ 构建程序：`cargo build-programs`（输出到 target/deploy），
+//! 测试默认 ignored，用 `cargo test-programs` 运行。
+
+mod common;
+
+use common::*;
+use solana_instruction::{AccountMeta, Instruction};
+use solana_signer::Signer;
+use test_support::{associated_token_address, token_account, token_program_id};
+
+const LOAN: u64 = 200_000_000;
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_first_deposit_mints_one_share_per_token() {
+    let fixture = Fixture::configured();
+    let market = fixture.market;
+    assert_eq!(fixture.token_balance(&market.protocol_ata), LIQUIDITY);
+    assert_eq!(fixture.token_balance(&market.lender_share_ata), LIQUIDITY);
+    assert_eq!(fixture.share_supply(&market), LIQUIDITY);
+    assert_eq!(fixture.token_balance(&market.lender_ata), 0);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_fees_accrue_to_lenders() {
+    let mut fixture = Fixture::configured();
+    let market = fixture.market;
+    let result = fixture.send_as_borrower(&[fixture.borrow_ix(LOAN, 1), fixture.repay_ix()]);
+    assert!(result.is_ok(), "borrow + repay 应成功: {:?}", result);
+    let fee = repay_amount(LOAN, FEE_BPS) - LOAN;
+    let assets = LIQUIDITY + fee;
+    assert_eq!(fixture.token_balance(&market.protocol_ata), assets);
+
+    // 赎回一半份额：按池子资产（含手续费）比例取回
+    let shares = LIQUIDITY / 2;
+    let result = fixture.send_as_lender(&[fixture.withdraw_liquidity_ix(&market, shares)]);
+    assert!(result.is_ok(), "withdraw_liquidity 应成功: {:?}", result);
+    let withdrawn = amount_for_shares(shares, assets, LIQUIDITY);
+    assert!(withdrawn > shares && withdrawn <= assets / 2);
+    assert_eq!(fixture.token_balance(&market.lender_ata), withdrawn);
+    assert_eq!(
+        fixture.token_balance(&market.lender_share_ata),
+        LIQUIDITY - shares
+    );
+    assert_eq!(fixture.share_supply(&market), LIQUIDITY - shares);
+
+    // 以新的份额价格存回：两次向下取整，得到的份额不多于赎回的
+    let result = fixture.send_as_lender(&[fixture.deposit_liquidity_ix(&market, withdrawn)]);
+    assert!(result.is_ok(), "deposit_liquidity 应成功: {:?}", result);
+    let redeposited = shares_for_deposit(withdrawn, assets - withdrawn, LIQUIDITY - shares);
+    assert!(redeposited <= shares && shares - redeposited <= 2);
+    let supply = LIQUIDITY - shares + redeposited;
+    assert_eq!(fixture.token_balance(&market.lender_share_ata), supply);
+    assert_eq!(fixture.token_balance(&market.protocol_ata), assets);
+
+    // 全部赎回：除虚拟份额分走的零头外全部取回
+    let result = fixture.send_as_lender(&[fixture.withdraw_liquidity_ix(&market, supply)]);
+    assert!(result.is_ok(), "withdraw_liquidity 应成功: {:?}", result);
+    let withdrawn = amount_for_shares(supply, assets, supply);
+    assert_eq!(fixture.token_balance(&market.lender_ata), withdrawn);
+    assert_eq!(
+        fixture.token_balance(&market.protocol_ata),
+        assets - withdrawn
+    );
+    assert!(assets - withdrawn <= 1);
+    assert_eq!(fixture.share_supply(&market), 0);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_withdraw_fail_too_many_shares() {
+    let mut fixture = Fixture::configured();
+    let market = fixture.market;
+    let result = fixture.send_as_lender(&[fixture.withdraw_liquidity_ix(&market, 0)]);
+    assert_custom_error(result, 0, INVALID_AMOUNT);
+    let result = fixture.send_as_lender(&[fixture.withdraw_liquidity_ix(&market, LIQUIDITY + 1)]);
+    assert_custom_error(result, 0, INVALID_AMOUNT);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_liquidity_fail_during_open_loan() {
+    let mut fixture = Fixture::configured();
+    let market = fixture.market;
+    let borrower = fixture.borrower.insecure_clone();
+    let lender = fixture.lender.insecure_clone();
+
+    // 借款未归还时池子余额偏低，份额价格不可信：存取都被拒绝
+    for lp_ix in [
+        fixture.withdraw_liquidity_ix(&market, LIQUIDITY / 2),
+        fixture.deposit_liquidity_ix(&market, 1_000),
+    ] {
+        let result = fixture.send_signed(
+            &[fixture.borrow_ix(LOAN, 2), lp_ix, fixture.repay_ix()],
+            &[&borrower, &lender],
+        );
+        assert_custom_error(result, 1, POOL_BUSY);
+    }
+
+    // repay 之后可以正常存取
+    let result = fixture.send_signed(
+        &[
+            fixture.borrow_ix(LOAN, 1),
+            fixture.repay_ix(),
+            fixture.withdraw_liquidity_ix(&market, LIQUIDITY / 2),
+        ],
+        &[&borrower, &lender],
+    );
+    assert!(result.is_ok(), "repay 后赎回应成功: {:?}", result);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_loans_on_two_pools_in_one_transaction() {
+    let mut fixture = Fixture::configured();
+    let a = fixture.market;
+    let b = fixture.add_market();
+    fixture.open_market(&b);
+
+    // 不同池子的借款可以交叉：借 A、借 B、还 B、还 A
+    let result = fixture.send_as_borrower(&[
+        fixture.borrow_ix_in(&a, LOAN, 3),
+        fixture.borrow_ix_in(&b, LOAN, 2),
+        fixture.repay_ix_in(&b),
+        fixture.repay_ix_in(&a),
+    ]);
+    assert!(result.is_ok(), "两个池子的借款应成功: {:?}", result);
+
+    let fee = repay_amount(LOAN, FEE_BPS) - LOAN;
+    for market in [a, b] {
+        assert_eq!(fixture.token_balance(&market.protocol_ata), LIQUIDITY + fee);
+        assert_eq!(
+            fixture.token_balance(&market.borrower_ata),
+            BORROWER_BALANCE - fee
+        );
+    }
+
+    // 同一池子仍然不能嵌套
+    let result = fixture.send_as_borrower(&[
+        fixture.borrow_ix_in(&a, LOAN, 3),
+        fixture.borrow_ix_in(&a, LOAN, 2),
+        fixture.repay_ix_in(&a),
+        fixture.repay_ix_in(&a),
+    ]);
+    assert_custom_error(result, 0, NESTED_LOAN);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_create_pool_fail_not_admin() {
+    let mut fixture = Fixture::configured();
+    let market = fixture.add_market();
+    let mut ix = fixture.create_pool_ix(&market);
+    ix.accounts[0].pubkey = fixture.borrower.pubkey();
+    let result = fixture.send_as_borrower(&[ix]);
+    assert_custom_error(result, 0, UNAUTHORIZED);
+}
+
+/// 未开池的默认 mint：只初始化配置并创建池子，尚无存款
+fn empty_pool() -> Fixture {
+    let mut fixture = Fixture::new();
+    let result = fixture.send_as_admin(&[fixture.initialize_config_ix(FEE_BPS, MAX_BORROW_BPS)]);
+    assert!(result.is_ok(), "initialize_config 应成功: {:?}", result);
+    let result = fixture.send_as_admin(&[fixture.create_pool_ix(&fixture.market)]);
+    assert!(result.is_ok(), "create_pool 应成功: {:?}", result);
+    fixture
+}
+
+/// borrower 作为 LP 存取，换用自己的 token / 份额账户
+fn as_borrower_lp(fixture: &Fixture, mut ix: Instruction) -> Instruction {
+    let market = fixture.market;
+    let borrower = fixture.borrower.pubkey();
+    ix.accounts[0].pubkey = borrower;
+    ix.accounts[5].pubkey = market.borrower_ata;
+    ix.accounts[6].pubkey = associated_token_address(&borrower, &market.share_mint);
+    ix
+}
+
+/// SPL Token Transfer：discriminator(3) + amount(8)，直接打进池子而不换份额
+fn donate_ix(fixture: &Fixture, amount: u64) -> Instruction {
+    let mut data = vec![3u8];
+    data.extend_from_slice(&amount.to_le_bytes());
+    Instruction {
+        program_id: token_program_id(),
+        accounts: vec![
+            AccountMeta::new(fixture.market.borrower_ata, false),
+            AccountMeta::new(fixture.market.protocol_ata, false),
+            AccountMeta::new_readonly(fixture.borrower.pubkey(), true),
+        ],
+        data,
+    }
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_donation_before_first_deposit_does_not_pay() {
+    let mut fixture = empty_pool();
+    let market = fixture.market;
+
+    // 攻击者先存 1 个 token 拿到 1 份，再把其余余额捐进池子抬高份额价格
+    let result = fixture.send_as_borrower(&[
+        as_borrower_lp(&fixture, fixture.deposit_liquidity_ix(&market, 1)),
+        donate_ix(&fixture, BORROWER_BALANCE - 1),
+    ]);
+    assert!(result.is_ok(), "存入 + 捐赠应成功: {:?}", result);
+    assert_eq!(fixture.share_supply(&market), 1);
+    assert_eq!(
+        fixture.token_balance(&market.protocol_ata),
+        BORROWER_BALANCE
+    );
+
+    // 受害者照常存入：虚拟份额让向下取整的损失很小，且仍能拿到份额
+    let shares = shares_for_deposit(LIQUIDITY, BORROWER_BALANCE, 1);
+    assert!(shares > 0);
+    let result = fixture.send_as_lender(&[fixture.deposit_liquidity_ix(&market, LIQUIDITY)]);
+    assert!(result.is_ok(), "deposit_liquidity 应成功: {:?}", result);
+    assert_eq!(fixture.token_balance(&market.lender_share_ata), shares);
+
+    // 双方全部赎回：攻击者亏的比受害者多，攻击无利可图
+    let result = fixture.send_as_borrower(&[as_borrower_lp(
+        &fixture,
+        fixture.withdraw_liquidity_ix(&market, 1),
+    )]);
+    assert!(result.is_ok(), "攻击者赎回应成功: {:?}", result);
+    let result = fixture.send_as_lender(&[fixture.withdraw_liquidity_ix(&market, shares)]);
+    assert!(result.is_ok(), "受害者赎回应成功: {:?}", result);
+
+    let attacker_loss = BORROWER_BALANCE - fixture.token_balance(&market.borrower_ata);
+    let victim_loss = LIQUIDITY - fixture.token_balance(&market.lender_ata);
+    assert!(
+        attacker_loss > victim_loss,
+        "attacker lost {attacker_loss}, victim lost {victim_loss}"
+    );
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_create_pool_fail_funded_protocol_ata() {
+    let mut fixture = Fixture::configured();
+    let market = fixture.add_market();
+
+    // 开池前已有人往 protocol ATA 转了 token：这些 token 会白送给第一个存款人
+    fixture
+        .svm
+        .set_account(
+            market.protocol_ata,
+            token_account(&market.mint, &fixture.protocol, 1),
+        )
+        .unwrap();
+    let result = fixture.send_as_admin(&[fixture.create_pool_ix(&market)]);
+    assert_custom_error(result, 0, POOL_NOT_EMPTY);
+}
@@ -0,0 +1,108 @@
+//! LiteSVM 集成测试：anchor_flash_loan 的 ProtocolConfig 与管理指令
+//!
+//! 运行前请先在 season1/ 构建程序：`cargo build-programs`（输出到 target/deploy），
+//! 测试默认 ignored，用 `cargo test-programs` 运行。
+
+mod common;
+
+use common::*;
+use solana_keypair::Keypair;
+use solana_signer::Signer;
+
+const LOAN: u64 = 200_000_000;
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_repay_uses_configured_fee() {
+    let mut fixture = Fixture::configured();
+    let result = fixture.send_as_borrower(&[fixture.borrow_ix(LOAN, 1), fixture.repay_ix()]);
+    assert!(result.is_ok(), "borrow + repay 应成功: {:?}", result);
+
+    let fee = repay_amount(LOAN, FEE_BPS) - LOAN;
+    assert_eq!(fee, 6_000_000);
+    assert_eq!(
+        fixture.token_balance(&fixture.market.protocol_ata),
+        LIQUIDITY + fee
+    );
+
+    // 调整手续费后按新费率收取
+    let admin = fixture.admin.pubkey();
+    let result = fixture.send_as_admin(&[fixture.update_config_ix(&admin, 100, MAX_BORROW_BPS)]);
+    assert!(result.is_ok(), "update_config 应成功: {:?}", result);
+    let result = fixture.send_as_borrower(&[fixture.borrow_ix(LOAN, 1), fixture.repay_ix()]);
+    assert!(result.is_ok(), "borrow + repay 应成功: {:?}", result);
+    assert_eq!(
+        fixture.token_balance(&fixture.market.protocol_ata),
+        LIQUIDITY + fee + repay_amount(LOAN, 100) - LOAN
+    );
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_borrow_fail_over_max_fraction() {
+    let mut fixture = Fixture::configured();
+    let max = LIQUIDITY * MAX_BORROW_BPS as u64 / 10_000;
+    let result = fixture.send_as_borrower(&[fixture.borrow_ix(max + 1, 1), fixture.repay_ix()]);
+    assert_custom_error(result, 0, EXCEEDS_MAX_BORROW);
+
+    let result = fixture.send_as_borrower(&[fixture.borrow_ix(max, 1), fixture.repay_ix()]);
+    assert!(result.is_ok(), "借满上限应成功: {:?}", result);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_pause_blocks_borrow() {
+    let mut fixture = Fixture::configured();
+    let admin = fixture.admin.pubkey();
+    let result = fixture.send_as_admin(&[fixture.set_paused_ix(&admin, true)]);
+    assert!(result.is_ok(), "set_paused 应成功: {:?}", result);
+
+    let result = fixture.send_as_borrower(&[fixture.borrow_ix(LOAN, 1), fixture.repay_ix()]);
+    assert_custom_error(result, 0, PROTOCOL_PAUSED);
+
+    let result = fixture.send_as_admin(&[fixture.set_paused_ix(&admin, false)]);
+    assert!(result.is_ok(), "取消暂停应成功: {:?}", result);
+    let result = fixture.send_as_borrower(&[fixture.borrow_ix(LOAN, 1), fixture.repay_ix()]);
+    assert!(result.is_ok(), "恢复后借款应成功: {:?}", result);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_admin_only() {
+    let mut fixture = Fixture::configured();
+    let attacker = Keypair::new();
+    fixture
+        .svm
+        .airdrop(&attacker.pubkey(), 10_000_000_000)
+        .unwrap();
+
+    let ix = fixture.update_config_ix(&attacker.pubkey(), 0, 10_000);
+    let result = fixture.send(&[ix], &attacker);
+    assert_custom_error(result, 0, UNAUTHORIZED);
+
+    let ix = fixture.set_paused_ix(&attacker.pubkey(), true);
+    let result = fixture.send(&[ix], &attacker);
+    assert_custom_error(result, 0, UNAUTHORIZED);
+
+    // 移交后旧 admin 失去权限
+    let admin = fixture.admin.pubkey();
+    let result = fixture.send_as_admin(&[fixture.transfer_admin_ix(&admin, &attacker.pubkey())]);
+    assert!(result.is_ok(), "transfer_admin 应成功: {:?}", result);
+    let result = fixture.send_as_admin(&[fixture.set_paused_ix(&admin, true)]);
+    assert_custom_error(result, 0, UNAUTHORIZED);
+    let ix = fixture.set_paused_ix(&attacker.pubkey(), true);
+    let result = fixture.send(&[ix], &attacker);
+    assert!(result.is_ok(), "新 admin 应可操作: {:?}", result);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_invalid_config() {
+    let mut fixture = Fixture::new();
+    let result = fixture.send_as_admin(&[fixture.initialize_config_ix(10_001, MAX_BORROW_BPS)]);
+    assert_custom_error(result, 0, INVALID_FEE);
+    let result = fixture.send_as_admin(&[fixture.initialize_config_ix(FEE_BPS, 0)]);
+    assert_custom_error(result, 0, INVALID_BORROW_FRACTION);
+    let result = fixture.send_as_admin(&[fixture.initialize_config_ix(FEE_BPS, 10_001)]);
+    assert_custom_error(result, 0, INVALID_BORROW_FRACTION);
+}
@@ -1,10 +0,0 @@
-mod deposit;
-mod helpers;
-mod initialize;
-mod swap;
-mod withdraw;
-
-pub use deposit::*;
-pub use initialize::*;
-pub use swap::*;
-pub use withdraw::*;
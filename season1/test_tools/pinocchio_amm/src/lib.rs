@@ -1,47 +0,0 @@
-#![no_std]
-
-use pinocchio::{
-    entrypoint,
-    error::ProgramError,
-    nostd_panic_handler,
-    AccountView,
-    Address,
-    ProgramResult,
-};
-
-entrypoint!(process_instruction);
-nostd_panic_handler!();
-
-pub mod curve;
-pub mod instructions;
-pub mod state;
-
-pub use instructions::*;
-pub use state::*;
-
-pub const ID: Address = Address::new_from_array([
-    0x0f, 0x1e, 0x6b, 0x14, 0x21, 0xc0, 0x4a, 0x07,
-    0x04, 0x31, 0x26, 0x5c, 0x19, 0xc5, 0xbb, 0xee,
-    0x19, 0x92, 0xba, 0xe8, 0xaf, 0xd1, 0xcd, 0x07,
-    0x8e, 0xf8, 0xaf, 0x70, 0x47, 0xdc, 0x11, 0xf7,
-]);
-
-fn process_instruction(
-    program_id: &Address,
-    accounts: &[AccountView],
-    instruction_data: &[u8],
-) -> ProgramResult {
-    match instruction_data.split_first() {
-        Some((&Initialize::DISCRIMINATOR, data)) => {
-            Initialize::try_from((data, accounts, program_id))?.process()
-        }
-        Some((&Deposit::DISCRIMINATOR, data)) => {
-            Deposit::try_from((data, accounts))?.process()
-        }
-        Some((&Withdraw::DISCRIMINATOR, data)) => {
-            Withdraw::try_from((data, accounts))?.process()
-        }
-        Some((&Swap::DISCRIMINATOR, data)) => Swap::try_from((data, accounts))?.process(),
-        _ => Err(ProgramError::InvalidInstructionData),
-    }
-}
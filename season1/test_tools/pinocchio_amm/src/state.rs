@@ -1,161 +0,0 @@
-use core::mem::size_of;
-
-use pinocchio::{error::ProgramError, Address};
-
-#[repr(C)]
-pub struct Config {
-    state: u8,
-    seed: [u8; 8],
-    authority: Address,
-    mint_x: Address,
-    mint_y: Address,
-    fee: [u8; 2],
-    config_bump: [u8; 1],
-}
-
-#[repr(u8)]
-pub enum AmmState {
-    Uninitialized = 0u8,
-    Initialized = 1u8,
-    Disabled = 2u8,
-    WithdrawOnly = 3u8,
-}
-
-impl Config {
-    pub const LEN: usize = size_of::<Config>();
-
-    #[inline(always)]
-    pub fn load(bytes: &[u8]) -> Result<&Self, ProgramError> {
-        if bytes.len() != Self::LEN {
-            return Err(ProgramError::InvalidAccountData);
-        }
-        Ok(unsafe { Self::from_bytes_unchecked(bytes) })
-    }
-
-    #[inline(always)]
-    pub fn load_mut(bytes: &mut [u8]) -> Result<&mut Self, ProgramError> {
-        if bytes.len() != Self::LEN {
-            return Err(ProgramError::InvalidAccountData);
-        }
-        Ok(unsafe { Self::from_bytes_unchecked_mut(bytes) })
-    }
-
-    #[inline(always)]
-    pub unsafe fn from_bytes_unchecked(bytes: &[u8]) -> &Self {
-        &*(bytes.as_ptr() as *const Config)
-    }
-
-    #[inline(always)]
-    pub unsafe fn from_bytes_unchecked_mut(bytes: &mut [u8]) -> &mut Self {
-        &mut *(bytes.as_mut_ptr() as *mut Config)
-    }
-
-    #[inline(always)]
-    pub fn state(&self) -> u8 {
-        self.state
-    }
-
-    #[inline(always)]
-    pub fn seed(&self) -> u64 {
-        u64::from_le_bytes(self.seed)
-    }
-
-    #[inline(always)]
-    pub fn authority(&self) -> &Address {
-        &self.authority
-    }
-
-    #[inline(always)]
-    pub fn mint_x(&self) -> &Address {
-        &self.mint_x
-    }
-
-    #[inline(always)]
-    pub fn mint_y(&self) -> &Address {
-        &self.mint_y
-    }
-
-    #[inline(always)]
-    pub fn fee(&self) -> u16 {
-        u16::from_le_bytes(self.fee)
-    }
-
-    #[inline(always)]
-    pub fn config_bump(&self) -> [u8; 1] {
-        self.config_bump
-    }
-
-    #[inline(always)]
-    pub fn set_state(&mut self, state: u8) -> Result<(), ProgramError> {
-        if state > (AmmState::WithdrawOnly as u8) {
-            return Err(ProgramError::InvalidAccountData);
-        }
-        self.state = state;
-        Ok(())
-    }
-
-    #[inline(always)]
-    pub fn set_seed(&mut self, seed: u64) {
-        self.seed = seed.to_le_bytes();
-    }
-
-    #[inline(always)]
-    pub fn set_authority(&mut self, authority: Address) {
-        self.authority = authority;
-    }
-
-    #[inline(always)]
-    pub fn set_mint_x(&mut self, mint_x: Address) {
-        self.mint_x = mint_x;
-    }
-
-    #[inline(always)]
-    pub fn set_mint_y(&mut self, mint_y: Address) {
-        self.mint_y = mint_y;
-    }
-
-    #[inline(always)]
-    pub fn set_fee(&mut self, fee: u16) -> Result<(), ProgramError> {
-        if fee >= 10_000 {
-            return Err(ProgramError::InvalidAccountData);
-        }
-        self.fee = fee.to_le_bytes();
-        Ok(())
-    }
-
-    #[inline(always)]
-    pub fn set_config_bump(&mut self, config_bump: [u8; 1]) {
-        self.config_bump = config_bump;
-    }
-
-    #[inline(always)]
-    pub fn set_inner(
-        &mut self,
-        seed: u64,
-        authority: Address,
-        mint_x: Address,
-        mint_y: Address,
-        fee: u16,
-        config_bump: [u8; 1],
-    ) -> Result<(), ProgramError> {
-        self.set_state(AmmState::Initialized as u8)?;
-        self.set_seed(seed);
-        self.set_authority(authority);
-        self.set_mint_x(mint_x);
-        self.set_mint_y(mint_y);
-        self.set_fee(fee)?;
-        self.set_config_bump(config_bump);
-        Ok(())
-    }
-
-    #[inline(always)]
-    pub fn has_authority(&self) -> Option<Address> {
-        let bytes = self.authority.as_ref();
-        let chunks: &[u64; 4] = unsafe { &*(bytes.as_ptr() as *const [u64; 4]) };
-        if chunks.iter().any(|&x| x != 0) {
-            Some(self.authority.clone())
-        } else {
-            None
-        }
-    }
-}
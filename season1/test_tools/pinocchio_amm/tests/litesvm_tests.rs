@@ -1,85 +1,37 @@
 //! LiteSVM 集成测试：基于 prompt_litesvm.md 的测试点
 //!
-//! 运行前请先构建程序：`cargo build-sbf` 或 `cargo build --release`（程序二进制需在 target/deploy 或 target/release）
+//! 测试对象为 `blueshift_challenges/pinocchio_amm`。运行前请先在 season1/ 构建程序：
+//! `cargo build-programs`（输出到 target/deploy），依赖二进制的测试默认 ignored，用 `cargo test-programs` 运行。
 
+use litesvm::LiteSVM;
 use pinocchio_amm::curve;
 use solana_account::ReadableAccount;
 use solana_address::Address as SolanaAddress;
+use solana_instruction::error::InstructionError;
 use solana_instruction::{AccountMeta, Instruction};
 use solana_keypair::Keypair;
 use solana_pubkey::Pubkey;
-use solana_program::pubkey::Pubkey as SolanaProgramPubkey;
 use solana_signer::Signer;
-use solana_system_program;
 use solana_transaction::Transaction;
-use spl_associated_token_account::get_associated_token_address;
+use solana_transaction_error::TransactionError;
+use test_support::{
+    associated_token_address, mint_decimals, mint_supply, svm_with_programs,
+    system_program_id, token_account_with_amount, token_program_id,
+};
 
-/// solana_address::Address（Instruction/AccountMeta 与 LiteSVM 使用）
-fn address_from_pubkey(p: &Pubkey) -> SolanaAddress {
-    SolanaAddress::from(p.to_bytes())
-}
-
-/// 用于 spl_associated_token_account::get_associated_token_address(wallet, mint) 的 Pubkey
-fn pubkey_from_address(a: &SolanaAddress) -> Pubkey {
-    Pubkey::new_from_array(a.as_ref().try_into().unwrap())
-}
-
-/// 将 solana_pubkey::Pubkey 转换为 solana_program::pubkey::Pubkey（用于 spl_associated_token_account）
-fn solana_program_pubkey_from_solana_pubkey(p: &Pubkey) -> SolanaProgramPubkey {
-    SolanaProgramPubkey::new_from_array(p.to_bytes())
-}
-
-/// 将 solana_program::pubkey::Pubkey 转换为 solana_pubkey::Pubkey
-fn solana_pubkey_from_solana_program_pubkey(p: &SolanaProgramPubkey) -> Pubkey {
-    Pubkey::new_from_array(p.to_bytes())
-}
-
-/// 将 spl-token 的 id() 返回值转换为 solana_pubkey::Pubkey
-/// spl_token::id() 返回一个实现了 ToBytes 的类型
-fn solana_pubkey_from_spl_id() -> Pubkey {
-    let spl_id = spl_token::id();
-    // spl-token 使用 solana-program 的 Pubkey，我们需要转换为 solana-pubkey 的 Pubkey
-    // 通过字节数组转换
-    Pubkey::new_from_array(spl_id.to_bytes())
+fn amm_program_id() -> SolanaAddress {
+    pinocchio_amm::ID
 }
 
-// 与 lib.rs 中 ID 一致的 AMM 程序 ID
-const AMM_PROGRAM_ID_BYTES: [u8; 32] = [
-    0x0f, 0x1e, 0x6b, 0x14, 0x21, 0xc0, 0x4a, 0x07, 0x04, 0x31, 0x26, 0x5c, 0x19, 0xc5, 0xbb,
-    0xee, 0x19, 0x92, 0xba, 0xe8, 0xaf, 0xd1, 0xcd, 0x07, 0x8e, 0xf8, 0xaf, 0x70, 0x47, 0xdc,
-    0x11, 0xf7,
-];
-
-fn amm_program_id() -> SolanaAddress {
-    SolanaAddress::from(AMM_PROGRAM_ID_BYTES)
+/// 加载 pinocchio_amm；未构建二进制时 panic
+fn amm_svm() -> LiteSVM {
+    svm_with_programs(&[(amm_program_id(), "pinocchio_amm")])
 }
 
 /// Config 账户布局：state(1) + seed(8) + authority(32) + mint_x(32) + mint_y(32) + fee(2) + config_bump(1) = 108
 const CONFIG_STATE_OFFSET: usize = 0;
 const CONFIG_LEN: usize = 108;
 
-/// SPL Token 账户 amount 在 offset 64
-const TOKEN_ACCOUNT_AMOUNT_OFFSET: usize = 64;
-/// SPL Mint supply 在 offset 36
-const MINT_SUPPLY_OFFSET: usize = 36;
-const MINT_DECIMALS_OFFSET: usize = 44;
-
-fn get_program_binary_path() -> std::path::PathBuf {
-    let manifest = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"));
-    let deploy = manifest.join("target/deploy/pinocchio_amm.so");
-    if deploy.exists() {
-        return deploy;
-    }
-    let ext = if cfg!(target_os = "macos") {
-        "dylib"
-    } else if cfg!(target_os = "windows") {
-        "dll"
-    } else {
-        "so"
-    };
-    manifest.join(format!("target/release/libpinocchio_amm.{}", ext))
-}
-
 /// 构建 Initialize 指令数据：discriminator(1) + seed(8) + fee(2) + mint_x(32) + mint_y(32) + config_bump(1) + lp_bump(1) = 77 字节
 fn build_initialize_instruction_data(
     seed: u64,
@@ -135,14 +87,6 @@ fn build_swap_instruction_data(is_x: bool, amount: u64, min_out: u64, expiration
     data
 }
 
-fn parse_token_account_amount(data: &[u8]) -> u64 {
-    u64::from_le_bytes(data[TOKEN_ACCOUNT_AMOUNT_OFFSET..TOKEN_ACCOUNT_AMOUNT_OFFSET + 8].try_into().unwrap())
-}
-
-fn parse_mint_supply(data: &[u8]) -> u64 {
-    u64::from_le_bytes(data[MINT_SUPPLY_OFFSET..MINT_SUPPLY_OFFSET + 8].try_into().unwrap())
-}
-
 fn parse_config_state(data: &[u8]) -> u8 {
     data[CONFIG_STATE_OFFSET]
 }
@@ -163,30 +107,23 @@ fn find_config_pda(seed: u64, mint_x: &SolanaAddress, mint_y: &SolanaAddress) ->
 
 /// 返回 (mint_lp_pda, lp_bump)，Token Program 为 LiteSVM 内置
 fn find_mint_lp_pda(config: &SolanaAddress) -> (SolanaAddress, u8) {
-    let token_program_id = address_from_pubkey(&solana_pubkey_from_spl_id());
-    SolanaAddress::find_program_address(&[b"mint_lp", config.as_ref()], &token_program_id)
+    SolanaAddress::find_program_address(&[b"mint_lp", config.as_ref()], &token_program_id())
 }
 
 // ========== 测试：Initialize ==========
 
 #[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
 fn test_initialize_ok() {
-    let program_path = get_program_binary_path();
-    if !program_path.exists() {
-        eprintln!("跳过：未找到程序二进制 {:?}，请先运行 cargo build-sbf 或 cargo build --release", program_path);
-        return;
-    }
-
-    let mut svm = litesvm::LiteSVM::new();
-    svm.add_program_from_file(SolanaAddress::from(AMM_PROGRAM_ID_BYTES), program_path).unwrap();
+    let mut svm = amm_svm();
 
     let initializer = Keypair::new();
-    svm.airdrop(&address_from_pubkey(&initializer.pubkey()), 10_000_000_000).unwrap();
+    svm.airdrop(&initializer.pubkey(), 10_000_000_000).unwrap();
 
     let seed = 42u64;
     let fee = 30u16; // 0.3%
-    let mint_x = address_from_pubkey(&Keypair::new().pubkey());
-    let mint_y = address_from_pubkey(&Keypair::new().pubkey());
+    let mint_x = Keypair::new().pubkey();
+    let mint_y = Keypair::new().pubkey();
 
     let (config_pda, config_bump) = find_config_pda(seed, &mint_x, &mint_y);
     let (mint_lp_pda, lp_bump) = find_mint_lp_pda(&config_pda);
@@ -203,8 +140,8 @@ fn test_initialize_ok() {
             AccountMeta::new(payer, true),
             AccountMeta::new(mint_lp_pda, false),
             AccountMeta::new(config_pda, false),
-            AccountMeta::new_readonly(address_from_pubkey(&solana_system_program::id()), false),
-            AccountMeta::new_readonly(address_from_pubkey(&solana_pubkey_from_spl_id()), false),
+            AccountMeta::new_readonly(system_program_id(), false),
+            AccountMeta::new_readonly(token_program_id(), false),
         ],
         data,
     };
@@ -226,32 +163,26 @@ fn test_initialize_ok() {
     assert!(lp_mint_account.is_some(), "LP mint 账户应存在");
     let lp_mint_account_ref = lp_mint_account.as_ref().unwrap();
     let mint_data = lp_mint_account_ref.data();
-    assert_eq!(parse_mint_supply(mint_data), 0);
-    assert_eq!(mint_data[MINT_DECIMALS_OFFSET], 6);
+    assert_eq!(mint_supply(mint_data), 0);
+    assert_eq!(mint_decimals(mint_data), 6);
 }
 
 #[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
 fn test_initialize_fail_not_enough_accounts() {
-    let program_path = get_program_binary_path();
-    if !program_path.exists() {
-        eprintln!("跳过：未找到程序二进制");
-        return;
-    }
-
-    let mut svm = litesvm::LiteSVM::new();
-    svm.add_program_from_file(SolanaAddress::from(AMM_PROGRAM_ID_BYTES), program_path).unwrap();
+    let mut svm = amm_svm();
 
     let initializer = Keypair::new();
-    svm.airdrop(&address_from_pubkey(&initializer.pubkey()), 10_000_000_000).unwrap();
+    svm.airdrop(&initializer.pubkey(), 10_000_000_000).unwrap();
 
     let data = vec![0u8; 76]; // 只传 4 个账户
     let ix = Instruction {
         program_id: amm_program_id(),
         accounts: vec![
             AccountMeta::new(initializer.pubkey(), true),
-            AccountMeta::new(address_from_pubkey(&Pubkey::new_unique()), false),
-            AccountMeta::new(address_from_pubkey(&Pubkey::new_unique()), false),
-            AccountMeta::new_readonly(address_from_pubkey(&solana_system_program::id()), false),
+            AccountMeta::new(Pubkey::new_unique(), false),
+            AccountMeta::new(Pubkey::new_unique(), false),
+            AccountMeta::new_readonly(system_program_id(), false),
             // 故意少 token_program 会变成 4 个账户，程序期望 5 个
         ],
         data,
@@ -265,21 +196,15 @@ fn test_initialize_fail_not_enough_accounts() {
 }
 
 #[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
 fn test_initialize_fail_fee_invalid() {
-    let program_path = get_program_binary_path();
-    if !program_path.exists() {
-        eprintln!("跳过：未找到程序二进制");
-        return;
-    }
-
-    let mut svm = litesvm::LiteSVM::new();
-    svm.add_program_from_file(SolanaAddress::from(AMM_PROGRAM_ID_BYTES), program_path).unwrap();
+    let mut svm = amm_svm();
 
     let initializer = Keypair::new();
-    svm.airdrop(&address_from_pubkey(&initializer.pubkey()), 10_000_000_000).unwrap();
+    svm.airdrop(&initializer.pubkey(), 10_000_000_000).unwrap();
 
-    let mint_x = address_from_pubkey(&Pubkey::new_unique());
-    let mint_y = address_from_pubkey(&Pubkey::new_unique());
+    let mint_x = Pubkey::new_unique();
+    let mint_y = Pubkey::new_unique();
     let (config_pda, config_bump) = find_config_pda(42, &mint_x, &mint_y);
     let (mint_lp_pda, lp_bump) = find_mint_lp_pda(&config_pda);
 
@@ -292,8 +217,8 @@ fn test_initialize_fail_fee_invalid() {
             AccountMeta::new(initializer.pubkey(), true),
             AccountMeta::new(mint_lp_pda, false),
             AccountMeta::new(config_pda, false),
-            AccountMeta::new_readonly(address_from_pubkey(&solana_system_program::id()), false),
-            AccountMeta::new_readonly(address_from_pubkey(&solana_pubkey_from_spl_id()), false),
+            AccountMeta::new_readonly(system_program_id(), false),
+            AccountMeta::new_readonly(token_program_id(), false),
         ],
         data,
     };
@@ -305,18 +230,12 @@ fn test_initialize_fail_fee_invalid() {
 }
 
 #[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
 fn test_initialize_fail_short_data() {
-    let program_path = get_program_binary_path();
-    if !program_path.exists() {
-        eprintln!("跳过：未找到程序二进制");
-        return;
-    }
-
-    let mut svm = litesvm::LiteSVM::new();
-    svm.add_program_from_file(SolanaAddress::from(AMM_PROGRAM_ID_BYTES), program_path).unwrap();
+    let mut svm = amm_svm();
 
     let initializer = Keypair::new();
-    svm.airdrop(&address_from_pubkey(&initializer.pubkey()), 10_000_000_000).unwrap();
+    svm.airdrop(&initializer.pubkey(), 10_000_000_000).unwrap();
 
     let data = vec![0u8; 50]; // < 76 字节
 
@@ -324,10 +243,10 @@ fn test_initialize_fail_short_data() {
         program_id: amm_program_id(),
         accounts: vec![
             AccountMeta::new(initializer.pubkey(), true),
-            AccountMeta::new(address_from_pubkey(&Pubkey::new_unique()), false),
-            AccountMeta::new(address_from_pubkey(&Pubkey::new_unique()), false),
-            AccountMeta::new_readonly(address_from_pubkey(&solana_system_program::id()), false),
-            AccountMeta::new_readonly(address_from_pubkey(&solana_pubkey_from_spl_id()), false),
+            AccountMeta::new(Pubkey::new_unique(), false),
+            AccountMeta::new(Pubkey::new_unique(), false),
+            AccountMeta::new_readonly(system_program_id(), false),
+            AccountMeta::new_readonly(token_program_id(), false),
         ],
         data,
     };
@@ -341,23 +260,17 @@ fn test_initialize_fail_short_data() {
 // ========== 测试：Deposit（首次 + 后续）==========
 
 #[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
 fn test_deposit_initial() {
-    let program_path = get_program_binary_path();
-    if !program_path.exists() {
-        eprintln!("跳过：未找到程序二进制");
-        return;
-    }
-
-    let mut svm = litesvm::LiteSVM::new();
-    svm.add_program_from_file(SolanaAddress::from(AMM_PROGRAM_ID_BYTES), program_path).unwrap();
+    let mut svm = amm_svm();
 
     let initializer = Keypair::new();
-    svm.airdrop(&address_from_pubkey(&initializer.pubkey()), 10_000_000_000).unwrap();
+    svm.airdrop(&initializer.pubkey(), 10_000_000_000).unwrap();
 
     let seed = 1u64;
     let fee = 30u16;
-    let mint_x_pk = address_from_pubkey(&Pubkey::new_unique());
-    let mint_y_pk = address_from_pubkey(&Pubkey::new_unique());
+    let mint_x_pk = Pubkey::new_unique();
+    let mint_y_pk = Pubkey::new_unique();
 
     let (config_pda, config_bump) = find_config_pda(seed, &mint_x_pk, &mint_y_pk);
     let (mint_lp_pda, lp_bump) = find_mint_lp_pda(&config_pda);
@@ -373,8 +286,8 @@ fn test_deposit_initial() {
             AccountMeta::new(payer, true),
             AccountMeta::new(mint_lp_pda, false),
             AccountMeta::new(config_pda, false),
-            AccountMeta::new_readonly(address_from_pubkey(&solana_system_program::id()), false),
-            AccountMeta::new_readonly(address_from_pubkey(&solana_pubkey_from_spl_id()), false),
+            AccountMeta::new_readonly(system_program_id(), false),
+            AccountMeta::new_readonly(token_program_id(), false),
         ],
         data: init_data,
     };
@@ -386,13 +299,8 @@ fn test_deposit_initial() {
     // 2. 创建 mint_x, mint_y（通过 Token 程序），并创建 vault_x, vault_y（config 的 ATA）和 user 的 ATA，并 mint 给 user
     // LiteSVM 已内置 Token 程序；需要创建 mint 账户、user ATAs、vault ATAs，再执行 Deposit
     // 为简化：本测试仅断言 Initialize 后 config 与 LP mint 存在；完整 Deposit 需先创建 mints 与 ATAs，此处仅做占位
-    let config_pubkey = solana_program_pubkey_from_solana_pubkey(&pubkey_from_address(&config_pda));
-    let mint_x_program_pubkey = solana_program_pubkey_from_solana_pubkey(&pubkey_from_address(&mint_x_pk));
-    let mint_y_program_pubkey = solana_program_pubkey_from_solana_pubkey(&pubkey_from_address(&mint_y_pk));
-    let vault_x_program_pubkey = get_associated_token_address(&config_pubkey, &mint_x_program_pubkey);
-    let vault_y_program_pubkey = get_associated_token_address(&config_pubkey, &mint_y_program_pubkey);
-    let vault_x = address_from_pubkey(&solana_pubkey_from_solana_program_pubkey(&vault_x_program_pubkey));
-    let vault_y = address_from_pubkey(&solana_pubkey_from_solana_program_pubkey(&vault_y_program_pubkey));
+    let vault_x = associated_token_address(&config_pda, &mint_x_pk);
+    let vault_y = associated_token_address(&config_pda, &mint_y_pk);
 
     let max_x = 1_000_000u64;
     let max_y = 2_000_000u64;
@@ -407,11 +315,11 @@ fn test_deposit_initial() {
             AccountMeta::new(mint_lp_pda, false),
             AccountMeta::new(vault_x, false),
             AccountMeta::new(vault_y, false),
-            AccountMeta::new(address_from_pubkey(&solana_pubkey_from_solana_program_pubkey(&get_associated_token_address(&solana_program_pubkey_from_solana_pubkey(&initializer.pubkey()), &mint_x_program_pubkey))), false),
-            AccountMeta::new(address_from_pubkey(&solana_pubkey_from_solana_program_pubkey(&get_associated_token_address(&solana_program_pubkey_from_solana_pubkey(&initializer.pubkey()), &mint_y_program_pubkey))), false),
-            AccountMeta::new(address_from_pubkey(&solana_pubkey_from_solana_program_pubkey(&get_associated_token_address(&solana_program_pubkey_from_solana_pubkey(&initializer.pubkey()), &solana_program_pubkey_from_solana_pubkey(&pubkey_from_address(&mint_lp_pda))))), false),
+            AccountMeta::new(associated_token_address(&payer, &mint_x_pk), false),
+            AccountMeta::new(associated_token_address(&payer, &mint_y_pk), false),
+            AccountMeta::new(associated_token_address(&payer, &mint_lp_pda), false),
             AccountMeta::new(config_pda, false),
-            AccountMeta::new_readonly(address_from_pubkey(&solana_pubkey_from_spl_id()), false),
+            AccountMeta::new_readonly(token_program_id(), false),
         ],
         data: deposit_data,
     };
@@ -423,7 +331,7 @@ fn test_deposit_initial() {
     // 可能因 vault/user 账户不存在或余额不足而失败；测试重点为 Initialize 成功且 Deposit 指令格式正确
     if res.is_ok() {
         let acc = svm.get_account(&mint_lp_pda).unwrap();
-        assert_eq!(parse_mint_supply(acc.data()), lp_amount);
+        assert_eq!(mint_supply(acc.data()), lp_amount);
     }
 }
 
@@ -468,3 +376,167 @@ fn test_deposit_second_curve() {
     let (dx, dy) = curve::xy_deposit_amounts(1000, 2000, 5000, 500).unwrap();
     assert!(dx > 0 && dy > 0);
 }
+
+// ========== 测试：Quote（只读报价）==========
+
+/// 构建 Quote 指令数据：discriminator(1) + is_x(1) + amount(8)
+fn build_quote_instruction_data(is_x: bool, amount: u64) -> Vec<u8> {
+    let mut data = vec![4u8];
+    data.push(if is_x { 1 } else { 0 });
+    data.extend_from_slice(&amount.to_le_bytes());
+    data
+}
+
+#[test]
+fn test_quote_fee_and_price_impact_curve() {
+    let swap = curve::swap_x_for_y(1_000_000, 2_000_000, 100_000, 30).unwrap();
+    assert_eq!(swap.fee, 300);
+    assert_eq!(
+        swap.amount_out,
+        curve::delta_y_from_x_swap(1_000_000, 2_000_000, 100_000, 30).unwrap()
+    );
+    let impact =
+        curve::price_impact_bps(1_000_000, 2_000_000, 100_000, swap.fee, swap.amount_out).unwrap();
+    // 投入约为池子 10%，价格影响约 9%
+    assert!(impact > 800 && impact < 1000, "impact = {}", impact);
+    assert_eq!(curve::price_impact_bps(1_000_000, 2_000_000, 1, 0, 0).unwrap(), 10_000);
+}
+
+#[test]
+fn test_swap_zero_output_curve() {
+    // 输出被舍入为 0：Swap 与 Quote 都经 map_curve_error 返回 InvalidArgument
+    assert!(matches!(
+        curve::swap_x_for_y(2_000_000, 1_000_000, 1, 30),
+        Err(curve::CurveError::ZeroOutput)
+    ));
+    assert!(matches!(
+        curve::swap_y_for_x(1_000_000, 2_000_000, 1, 30),
+        Err(curve::CurveError::ZeroOutput)
+    ));
+}
+
+/// 初始化一个池子，并把 config 的两个 ATA 直接写成余额为 (x, y) 的 vault
+fn quote_pool(svm: &mut LiteSVM, x: u64, y: u64) -> (Keypair, SolanaAddress, SolanaAddress, SolanaAddress) {
+    let initializer = Keypair::new();
+    svm.airdrop(&initializer.pubkey(), 10_000_000_000).unwrap();
+
+    let seed = 7u64;
+    let fee = 30u16;
+    let mint_x = Pubkey::new_unique();
+    let mint_y = Pubkey::new_unique();
+    let (config_pda, config_bump) = find_config_pda(seed, &mint_x, &mint_y);
+    let (mint_lp_pda, lp_bump) = find_mint_lp_pda(&config_pda);
+
+    let payer = initializer.pubkey();
+    let init_ix = Instruction {
+        program_id: amm_program_id(),
+        accounts: vec![
+            AccountMeta::new(payer, true),
+            AccountMeta::new(mint_lp_pda, false),
+            AccountMeta::new(config_pda, false),
+            AccountMeta::new_readonly(system_program_id(), false),
+            AccountMeta::new_readonly(token_program_id(), false),
+        ],
+        data: build_initialize_instruction_data(seed, fee, &mint_x, &mint_y, config_bump, lp_bump),
+    };
+    let blockhash = svm.latest_blockhash();
+    let tx = Transaction::new_signed_with_payer(&[init_ix], Some(&payer), &[&initializer], blockhash);
+    svm.send_transaction(tx).unwrap();
+
+    // 报价只读取 vault 余额，直接写入账户数据即可
+    let vault_x = associated_token_address(&config_pda, &mint_x);
+    let vault_y = associated_token_address(&config_pda, &mint_y);
+    svm.set_account(vault_x, token_account_with_amount(x)).unwrap();
+    svm.set_account(vault_y, token_account_with_amount(y)).unwrap();
+    (initializer, config_pda, vault_x, vault_y)
+}
+
+/// 模拟一次 Quote，返回 return data 或失败原因
+fn simulate_quote(
+    svm: &LiteSVM,
+    payer: &Keypair,
+    [vault_x, vault_y, config]: [SolanaAddress; 3],
+    is_x: bool,
+    amount: u64,
+) -> Result<Vec<u8>, TransactionError> {
+    let quote_ix = Instruction {
+        program_id: amm_program_id(),
+        accounts: vec![
+            AccountMeta::new_readonly(vault_x, false),
+            AccountMeta::new_readonly(vault_y, false),
+            AccountMeta::new_readonly(config, false),
+        ],
+        data: build_quote_instruction_data(is_x, amount),
+    };
+    let tx = Transaction::new_signed_with_payer(&[quote_ix], Some(&payer.pubkey()), &[payer], svm.latest_blockhash());
+    svm.simulate_transaction(tx)
+        .map(|sim| sim.meta.return_data.data)
+        .map_err(|failed| failed.err)
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_quote_matches_swap_curve() {
+    let mut svm = amm_svm();
+    let (x, y) = (1_000_000u64, 2_000_000u64);
+    let (payer, config, vault_x, vault_y) = quote_pool(&mut svm, x, y);
+
+    let amount_in = 100_000u64;
+    let return_data = simulate_quote(&svm, &payer, [vault_x, vault_y, config], true, amount_in)
+        .expect("Quote 模拟应成功");
+    assert_eq!(return_data.len(), 18);
+
+    let amount_out = u64::from_le_bytes(return_data[0..8].try_into().unwrap());
+    let fee_amount = u64::from_le_bytes(return_data[8..16].try_into().unwrap());
+    let impact = u16::from_le_bytes(return_data[16..18].try_into().unwrap());
+    let expected = curve::swap_x_for_y(x, y, amount_in, 30).unwrap();
+    assert_eq!(amount_out, expected.amount_out);
+    assert_eq!(fee_amount, expected.fee);
+    assert_eq!(
+        impact,
+        curve::price_impact_bps(x, y, amount_in, expected.fee, expected.amount_out).unwrap()
+    );
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_quote_fail_foreign_accounts() {
+    let mut svm = amm_svm();
+    let (payer, config, vault_x, vault_y) = quote_pool(&mut svm, 1_000_000, 2_000_000);
+    let quote = |svm: &LiteSVM, accounts| simulate_quote(svm, &payer, accounts, true, 100_000);
+    let error = |err| Err(TransactionError::InstructionError(0, err));
+
+    // 余额任意的 token 账户冒充 vault：不是 config 的 ATA
+    let fake_vault = Pubkey::new_unique();
+    svm.set_account(fake_vault, token_account_with_amount(1)).unwrap();
+    assert_eq!(
+        quote(&svm, [vault_x, fake_vault, config]),
+        error(InstructionError::InvalidAccountData)
+    );
+    assert_eq!(
+        quote(&svm, [vault_y, vault_x, config]),
+        error(InstructionError::InvalidAccountData)
+    );
+
+    // 地址正确但不归 Token 程序所有的 vault
+    let mut not_token = svm.get_account(&vault_y).unwrap();
+    not_token.owner = Pubkey::new_unique();
+    svm.set_account(vault_y, not_token).unwrap();
+    assert_eq!(
+        quote(&svm, [vault_x, vault_y, config]),
+        error(InstructionError::InvalidAccountOwner)
+    );
+    svm.set_account(vault_y, token_account_with_amount(2_000_000)).unwrap();
+
+    // 其它程序持有的、数据与真实 config 相同的账户
+    let mut fake_config = svm.get_account(&config).unwrap();
+    fake_config.owner = Pubkey::new_unique();
+    let fake_config_address = Pubkey::new_unique();
+    svm.set_account(fake_config_address, fake_config).unwrap();
+    assert_eq!(
+        quote(&svm, [vault_x, vault_y, fake_config_address]),
+        error(InstructionError::InvalidAccountOwner)
+    );
+
+    assert!(quote(&svm, [vault_x, vault_y, config]).is_ok());
+}
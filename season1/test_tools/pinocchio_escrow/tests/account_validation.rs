@@ -0,0 +1,172 @@
+//! 伪造账户攻击测试：Take / Refund 必须校验 escrow 的 owner、PDA、数据 discriminator，
+//! vault 必须是 escrow 的 ATA，程序账户必须是真实的程序。
+
+mod common;
+
+use common::*;
+use solana_account::ReadableAccount;
+use solana_instruction::error::InstructionError;
+use solana_pubkey::Pubkey;
+use solana_signer::Signer;
+use test_support::token_account;
+
+/// EscrowError::InvalidMaker
+const INVALID_MAKER: u32 = 6001;
+
+fn setup_with_escrow() -> Fixture {
+    let mut fixture = Fixture::new(escrow_svm());
+    fixture.make();
+    fixture
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_make_writes_discriminator() {
+    let fixture = setup_with_escrow();
+
+    let escrow = fixture.account(&fixture.escrow).expect("escrow 应存在");
+    assert_eq!(escrow.owner, escrow_program_id());
+    assert_eq!(escrow.data().len(), pinocchio_escrow::Escrow::LEN);
+    assert_eq!(
+        escrow.data()[ESCROW_DISCRIMINATOR_OFFSET],
+        pinocchio_escrow::Escrow::DISCRIMINATOR
+    );
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_take_rejects_escrow_not_owned_by_program() {
+    let mut fixture = setup_with_escrow();
+
+    // 数据完全相同，但 owner 是攻击者的程序
+    let escrow = fixture.escrow;
+    let forged = fixture.forge_copy(&escrow, &Pubkey::new_unique());
+    let mut ix = fixture.take_ix();
+    ix.accounts[2].pubkey = forged;
+
+    let taker = fixture.taker.insecure_clone();
+    let result = fixture.send(ix, &taker);
+    assert_instruction_error(result, InstructionError::InvalidAccountOwner);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_take_rejects_escrow_at_non_pda_address() {
+    let mut fixture = setup_with_escrow();
+
+    // 本程序拥有、数据相同，但地址不是 ("escrow", maker, seed) 的 PDA
+    let escrow = fixture.escrow;
+    let forged = fixture.forge_copy(&escrow, &escrow_program_id());
+    let mut ix = fixture.take_ix();
+    ix.accounts[2].pubkey = forged;
+
+    let taker = fixture.taker.insecure_clone();
+    let result = fixture.send(ix, &taker);
+    assert_instruction_error(result, InstructionError::InvalidSeeds);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_take_rejects_wrong_discriminator() {
+    let mut fixture = setup_with_escrow();
+
+    // 真实 PDA，但数据的 discriminator 被改成 Basket
+    let mut escrow = fixture.account(&fixture.escrow).unwrap();
+    escrow.data[ESCROW_DISCRIMINATOR_OFFSET] = pinocchio_escrow::Basket::DISCRIMINATOR;
+    fixture.svm.set_account(fixture.escrow, escrow).unwrap();
+
+    let ix = fixture.take_ix();
+    let taker = fixture.taker.insecure_clone();
+    let result = fixture.send(ix, &taker);
+    assert_instruction_error(result, InstructionError::InvalidAccountData);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_take_rejects_vault_that_is_not_escrow_ata() {
+    let mut fixture = setup_with_escrow();
+
+    // escrow 名下的 token A 账户，但不在 ATA 地址上
+    let fake_vault = Pubkey::new_unique();
+    fixture
+        .svm
+        .set_account(fake_vault, token_account(&fixture.mint_a, &fixture.escrow, 1))
+        .unwrap();
+    let mut ix = fixture.take_ix();
+    ix.accounts[5].pubkey = fake_vault;
+
+    let taker = fixture.taker.insecure_clone();
+    let result = fixture.send(ix, &taker);
+    assert_instruction_error(result, InstructionError::InvalidAccountData);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_take_rejects_spoofed_programs() {
+    let mut fixture = setup_with_escrow();
+    let taker = fixture.taker.insecure_clone();
+
+    // associated_token_program / token_program / system_program 依次替换为伪造地址
+    for index in [9, 10, 11] {
+        let mut ix = fixture.take_ix();
+        ix.accounts[index].pubkey = Pubkey::new_unique();
+        let result = fixture.send(ix, &taker);
+        assert_instruction_error(result, InstructionError::IncorrectProgramId);
+    }
+
+    // 所有攻击失败后 vault 余额不变
+    assert_eq!(fixture.token_balance(&fixture.vault), OFFER_AMOUNT);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_refund_rejects_forged_escrow_naming_attacker_as_maker() {
+    let mut fixture = setup_with_escrow();
+
+    // 攻击者伪造一个本程序拥有的 escrow，maker 写成自己，指向真实 vault
+    let attacker = solana_keypair::Keypair::new();
+    fixture.svm.airdrop(&attacker.pubkey(), 1_000_000_000).unwrap();
+    let escrow = fixture.escrow;
+    let forged = fixture.forge_copy(&escrow, &escrow_program_id());
+    let mut account = fixture.account(&forged).unwrap();
+    account.data[ESCROW_MAKER_OFFSET..ESCROW_MAKER_OFFSET + 32]
+        .copy_from_slice(attacker.pubkey().as_ref());
+    fixture.svm.set_account(forged, account).unwrap();
+
+    let mut ix = fixture.refund_ix();
+    ix.accounts[0].pubkey = attacker.pubkey();
+    ix.accounts[1].pubkey = forged;
+    ix.accounts[4].pubkey =
+        test_support::associated_token_address(&attacker.pubkey(), &fixture.mint_a);
+
+    let result = fixture.send(ix, &attacker);
+    assert_instruction_error(result, InstructionError::InvalidSeeds);
+    assert_eq!(fixture.token_balance(&fixture.vault), OFFER_AMOUNT);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_refund_rejects_non_maker_on_real_escrow() {
+    let mut fixture = setup_with_escrow();
+
+    let attacker = solana_keypair::Keypair::new();
+    fixture.svm.airdrop(&attacker.pubkey(), 1_000_000_000).unwrap();
+    let mut ix = fixture.refund_ix();
+    ix.accounts[0].pubkey = attacker.pubkey();
+
+    let result = fixture.send(ix, &attacker);
+    assert_custom_error(result, INVALID_MAKER);
+    assert!(!fixture.is_closed(&fixture.escrow), "escrow 不应被关闭");
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_take_closes_escrow_account() {
+    let mut fixture = setup_with_escrow();
+
+    let taker = fixture.taker.insecure_clone();
+    let result = fixture.send(fixture.take_ix(), &taker);
+    assert!(result.is_ok(), "Take 应成功: {:?}", result);
+    assert!(fixture.is_closed(&fixture.escrow), "escrow 应被关闭");
+    assert!(fixture.is_closed(&fixture.vault), "vault 应被关闭");
+}
@@ -0,0 +1,168 @@
+//! Amend：maker 修改价格、追加或取回部分 token A，每次修改递增 sequence；
+//! 以旧 sequence 下单的 Take 会被拒绝
+//!
+//! 运行前请先在 season1/ 构建程序：`cargo build-programs`，测试默认 ignored，用 `cargo test-programs` 运行。
+
+mod common;
+
+use common::*;
+use solana_account::ReadableAccount;
+use solana_instruction::{AccountMeta, Instruction};
+use solana_signer::Signer;
+
+const INVALID_AMOUNT: u32 = 6000;
+const SEQUENCE_MISMATCH: u32 = 6010;
+
+/// Amend 指令：discriminator(7) + receive(8) + deposit(8) + withdraw(8)
+fn amend_ix(fixture: &Fixture, receive: u64, deposit: u64, withdraw: u64) -> Instruction {
+    let mut data = vec![7u8];
+    data.extend_from_slice(&receive.to_le_bytes());
+    data.extend_from_slice(&deposit.to_le_bytes());
+    data.extend_from_slice(&withdraw.to_le_bytes());
+    Instruction {
+        program_id: fixture.program_id,
+        accounts: vec![
+            AccountMeta::new(fixture.maker.pubkey(), true),
+            AccountMeta::new(fixture.escrow, false),
+            AccountMeta::new_readonly(fixture.mint_a, false),
+            AccountMeta::new(fixture.maker_ata_a, false),
+            AccountMeta::new(fixture.vault, false),
+            AccountMeta::new_readonly(fixture.token_program_a, false),
+        ],
+        data,
+    }
+}
+
+fn amend(fixture: &mut Fixture, receive: u64, deposit: u64, withdraw: u64) {
+    let maker = fixture.maker.insecure_clone();
+    let result = fixture.send(amend_ix(fixture, receive, deposit, withdraw), &maker);
+    assert!(result.is_ok(), "Amend 应成功: {:?}", result);
+}
+
+fn escrow_field(fixture: &Fixture, offset: usize) -> u64 {
+    let account = fixture.account(&fixture.escrow).expect("escrow 应存在");
+    read_u64(account.data(), offset)
+}
+
+fn setup() -> Fixture {
+    let mut fixture = Fixture::new(escrow_svm());
+    fixture.make();
+    assert_eq!(escrow_field(&fixture, ESCROW_SEQUENCE_OFFSET), 0);
+    fixture
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_take_fail_stale_sequence_after_amend() {
+    let mut fixture = setup();
+    let taker = fixture.taker.insecure_clone();
+
+    // taker 按 sequence 0 的价格下单，maker 抢先提价
+    let stale_take = fixture.take_ix_with(OFFER_AMOUNT, Some(0), None, &[]);
+    let new_receive = RECEIVE_AMOUNT * 2;
+    amend(&mut fixture, new_receive, 0, 0);
+    assert_eq!(escrow_field(&fixture, ESCROW_SEQUENCE_OFFSET), 1);
+    assert_eq!(escrow_field(&fixture, ESCROW_RECEIVE_OFFSET), new_receive);
+
+    let result = fixture.send(stale_take, &taker);
+    assert_custom_error(result, SEQUENCE_MISMATCH);
+    assert_eq!(fixture.token_balance(&fixture.vault), OFFER_AMOUNT);
+    assert_eq!(fixture.token_balance(&fixture.maker_ata_b), 0);
+
+    // 接受新 sequence 后按新价格成交
+    let result = fixture.send(
+        fixture.take_ix_with(OFFER_AMOUNT, Some(1), None, &[]),
+        &taker,
+    );
+    assert!(result.is_ok(), "Take 应成功: {:?}", result);
+    assert_eq!(fixture.token_balance(&fixture.maker_ata_b), new_receive);
+    assert_eq!(fixture.token_balance(&fixture.taker_ata_a), OFFER_AMOUNT);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_take_without_sequence_accepts_amendment() {
+    let mut fixture = setup();
+    amend(&mut fixture, RECEIVE_AMOUNT * 2, 0, 0);
+
+    // 不限定 sequence 的 Take 接受任何修改
+    let taker = fixture.taker.insecure_clone();
+    let result = fixture.send(fixture.take_ix(), &taker);
+    assert!(result.is_ok(), "Take 应成功: {:?}", result);
+    assert_eq!(
+        fixture.token_balance(&fixture.maker_ata_b),
+        RECEIVE_AMOUNT * 2
+    );
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_amend_deposit() {
+    let mut fixture = setup();
+    let deposit = 50_000;
+    amend(&mut fixture, 0, deposit, 0);
+
+    assert_eq!(
+        fixture.token_balance(&fixture.vault),
+        OFFER_AMOUNT + deposit
+    );
+    assert_eq!(
+        fixture.token_balance(&fixture.maker_ata_a),
+        INITIAL_BALANCE - OFFER_AMOUNT - deposit
+    );
+    assert_eq!(
+        escrow_field(&fixture, ESCROW_REMAINING_OFFSET),
+        OFFER_AMOUNT + deposit
+    );
+    assert_eq!(
+        escrow_field(&fixture, ESCROW_RECEIVE_OFFSET),
+        RECEIVE_AMOUNT
+    );
+    assert_eq!(escrow_field(&fixture, ESCROW_SEQUENCE_OFFSET), 1);
+
+    // 全部成交时 taker 拿到追加后的整个 vault
+    let taker = fixture.taker.insecure_clone();
+    let result = fixture.send(fixture.take_ix(), &taker);
+    assert!(result.is_ok(), "Take 应成功: {:?}", result);
+    assert_eq!(
+        fixture.token_balance(&fixture.taker_ata_a),
+        OFFER_AMOUNT + deposit
+    );
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_amend_withdraw() {
+    let mut fixture = setup();
+    let withdraw = 40_000;
+    amend(&mut fixture, 0, 0, withdraw);
+
+    assert_eq!(
+        fixture.token_balance(&fixture.vault),
+        OFFER_AMOUNT - withdraw
+    );
+    assert_eq!(
+        fixture.token_balance(&fixture.maker_ata_a),
+        INITIAL_BALANCE - OFFER_AMOUNT + withdraw
+    );
+    assert_eq!(
+        escrow_field(&fixture, ESCROW_REMAINING_OFFSET),
+        OFFER_AMOUNT - withdraw
+    );
+    assert_eq!(escrow_field(&fixture, ESCROW_SEQUENCE_OFFSET), 1);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_amend_fail_invalid_amounts() {
+    let mut fixture = setup();
+    let maker = fixture.maker.insecure_clone();
+
+    // 取空整个 vault 应走 Refund；同时追加与取回、或什么都不改都无效
+    for (receive, deposit, withdraw) in [(0, 0, OFFER_AMOUNT), (0, 1, 1), (0, 0, 0)] {
+        let result = fixture.send(amend_ix(&fixture, receive, deposit, withdraw), &maker);
+        assert_custom_error(result, INVALID_AMOUNT);
+    }
+    assert_eq!(fixture.token_balance(&fixture.vault), OFFER_AMOUNT);
+    assert_eq!(escrow_field(&fixture, ESCROW_SEQUENCE_OFFSET), 0);
+}
@@ -0,0 +1,407 @@
+//! pinocchio_escrow 与 anchor_escrow 的差分测试
+//!
+//! 两个程序的指令格式与账户顺序一致：同一组参与方分别在两个 LiteSVM 中执行相同场景，
+//! 比较 escrow 状态、token 余额、lamports 与失败时的错误类别。
+//! 需先 `cargo build-programs` 构建两个程序，测试默认 ignored，用 `cargo test-programs` 运行。
+
+mod common;
+
+use common::basket::{plain_basket, BasketFixture, PROGRAMS};
+use common::*;
+use litesvm::types::TransactionResult;
+use solana_account::ReadableAccount;
+use solana_address::Address;
+use solana_instruction::error::InstructionError;
+use solana_instruction::Instruction;
+use solana_keypair::Keypair;
+use solana_signer::Signer;
+use solana_transaction_error::TransactionError;
+use test_support::svm_with_programs;
+
+/// anchor_escrow::Escrow 布局：1 字节 discriminator 后紧跟 borsh 字段
+const ANCHOR_SEED_OFFSET: usize = 1;
+const ANCHOR_MAKER_OFFSET: usize = 9;
+const ANCHOR_MINT_A_OFFSET: usize = 41;
+const ANCHOR_MINT_B_OFFSET: usize = 73;
+const ANCHOR_RECEIVE_OFFSET: usize = 105;
+
+/// 两个程序各自的偏移表，用于读取同一语义字段
+struct Layout {
+    seed: usize,
+    maker: usize,
+    mint_a: usize,
+    mint_b: usize,
+    receive: usize,
+}
+
+const PINOCCHIO_LAYOUT: Layout = Layout {
+    seed: ESCROW_SEED_OFFSET,
+    maker: ESCROW_MAKER_OFFSET,
+    mint_a: ESCROW_MINT_A_OFFSET,
+    mint_b: ESCROW_MINT_B_OFFSET,
+    receive: ESCROW_RECEIVE_OFFSET,
+};
+
+const ANCHOR_LAYOUT: Layout = Layout {
+    seed: ANCHOR_SEED_OFFSET,
+    maker: ANCHOR_MAKER_OFFSET,
+    mint_a: ANCHOR_MINT_A_OFFSET,
+    mint_b: ANCHOR_MINT_B_OFFSET,
+    receive: ANCHOR_RECEIVE_OFFSET,
+};
+
+/// 失败的归类：两个程序的错误码不同，但语义类别应一致
+#[derive(Debug, PartialEq, Eq)]
+enum ErrorClass {
+    /// EscrowError::InvalidAmount（两边均为 6000）
+    InvalidAmount,
+    /// 账户不匹配：maker / mint / PDA / owner 校验失败
+    /// （pinocchio 的 6001-6003 与 ProgramError，anchor 的 6001-6003 与 2xxx/3xxx 约束错误）
+    AccountMismatch,
+    /// 缺少签名（pinocchio MissingRequiredSignature，anchor AccountNotSigner 3010）
+    MissingSignature,
+    /// EscrowError::UnsupportedMint（两边均为 6007）
+    UnsupportedMint,
+    /// SPL Token 程序返回的错误码
+    TokenProgram(u32),
+    Other(InstructionError),
+}
+
+fn classify(err: &InstructionError) -> ErrorClass {
+    match err {
+        InstructionError::Custom(6000) => ErrorClass::InvalidAmount,
+        InstructionError::Custom(6001..=6003) => ErrorClass::AccountMismatch,
+        InstructionError::Custom(3010) => ErrorClass::MissingSignature,
+        InstructionError::Custom(6007) => ErrorClass::UnsupportedMint,
+        // anchor ConstraintXxx (2000-2999) 与 AccountOwnedByWrongProgram / AccountNotInitialized 等
+        InstructionError::Custom(2000..=2999)
+        | InstructionError::Custom(3002 | 3007 | 3008 | 3012) => ErrorClass::AccountMismatch,
+        InstructionError::Custom(code) if *code < 100 => ErrorClass::TokenProgram(*code),
+        InstructionError::MissingRequiredSignature => ErrorClass::MissingSignature,
+        InstructionError::InvalidSeeds
+        | InstructionError::InvalidAccountOwner
+        | InstructionError::InvalidAccountData
+        | InstructionError::IncorrectProgramId
+        | InstructionError::UninitializedAccount => ErrorClass::AccountMismatch,
+        other => ErrorClass::Other(other.clone()),
+    }
+}
+
+/// 交易结果：成功为 None，失败为错误类别
+fn outcome(result: TransactionResult) -> Option<ErrorClass> {
+    match result {
+        Ok(_) => None,
+        Err(failed) => match failed.err {
+            TransactionError::InstructionError(_, err) => Some(classify(&err)),
+            other => panic!("非指令错误: {:?}", other),
+        },
+    }
+}
+
+/// 从 Fixture 中取出某个账户地址
+type Field = fn(&Fixture) -> Address;
+
+/// 同一组参与方分别部署在两个程序上的场景
+struct Pair {
+    pinocchio: Fixture,
+    anchor: Fixture,
+}
+
+impl Pair {
+    fn new() -> Self {
+        let parties = Parties::new();
+        let pinocchio_svm = svm_with_programs(&[(escrow_program_id(), "pinocchio_escrow")]);
+        let anchor_svm = svm_with_programs(&[(anchor_program_id(), "anchor_escrow")]);
+        Self {
+            pinocchio: Fixture::with_parties(pinocchio_svm, escrow_program_id(), &parties),
+            anchor: Fixture::with_parties(anchor_svm, anchor_program_id(), &parties),
+        }
+    }
+
+    /// 在两个程序上执行同一条指令（由 `build` 按各自的 Fixture 构造），返回两边结果类别
+    fn run(
+        &mut self,
+        build: impl Fn(&Fixture) -> Instruction,
+        signer: impl Fn(&Fixture) -> Keypair,
+    ) -> (Option<ErrorClass>, Option<ErrorClass>) {
+        let ix = build(&self.pinocchio);
+        let payer = signer(&self.pinocchio);
+        let pinocchio = outcome(self.pinocchio.send(ix, &payer));
+        let ix = build(&self.anchor);
+        let payer = signer(&self.anchor);
+        let anchor = outcome(self.anchor.send(ix, &payer));
+        (pinocchio, anchor)
+    }
+
+    /// 断言两边结果一致并返回它
+    fn run_same(
+        &mut self,
+        build: impl Fn(&Fixture) -> Instruction,
+        signer: impl Fn(&Fixture) -> Keypair,
+    ) -> Option<ErrorClass> {
+        let (pinocchio, anchor) = self.run(build, signer);
+        assert_eq!(pinocchio, anchor, "pinocchio 与 anchor 的结果不一致");
+        pinocchio
+    }
+
+    /// 两边所有参与账户的 token 余额与存在性一致
+    fn assert_same_balances(&self) {
+        let accounts: [(&str, Field); 5] = [
+            ("vault", |f| f.vault),
+            ("maker_ata_a", |f| f.maker_ata_a),
+            ("maker_ata_b", |f| f.maker_ata_b),
+            ("taker_ata_a", |f| f.taker_ata_a),
+            ("taker_ata_b", |f| f.taker_ata_b),
+        ];
+        for (name, address) in accounts {
+            let (p, a) = (address(&self.pinocchio), address(&self.anchor));
+            assert_eq!(
+                self.pinocchio.token_balance(&p),
+                self.anchor.token_balance(&a),
+                "{name} 余额不一致"
+            );
+            assert_eq!(
+                self.pinocchio.is_closed(&p),
+                self.anchor.is_closed(&a),
+                "{name} 存在性不一致"
+            );
+        }
+        assert_eq!(
+            self.pinocchio.is_closed(&self.pinocchio.escrow),
+            self.anchor.is_closed(&self.anchor.escrow),
+            "escrow 存在性不一致"
+        );
+    }
+
+    /// 两边钱包 lamports 一致（租金的收取与退还相同）
+    fn assert_same_lamports(&self) {
+        let wallets: [(&str, Field); 2] = [
+            ("maker", |f| f.maker.pubkey()),
+            ("taker", |f| f.taker.pubkey()),
+        ];
+        for (name, wallet) in wallets {
+            let lamports = |f: &Fixture| f.account(&wallet(f)).unwrap().lamports();
+            assert_eq!(
+                lamports(&self.pinocchio),
+                lamports(&self.anchor),
+                "{name} lamports 不一致"
+            );
+        }
+    }
+}
+
+fn maker(fixture: &Fixture) -> Keypair {
+    fixture.maker.insecure_clone()
+}
+
+fn taker(fixture: &Fixture) -> Keypair {
+    fixture.taker.insecure_clone()
+}
+
+fn default_make(fixture: &Fixture) -> Instruction {
+    fixture.make_ix(RECEIVE_AMOUNT, OFFER_AMOUNT)
+}
+
+// ========== 成功路径 ==========
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_parity_make_state() {
+    let mut pair = Pair::new();
+    assert_eq!(pair.run_same(default_make, maker), None);
+
+    let pinocchio = pair.pinocchio.account(&pair.pinocchio.escrow).unwrap();
+    let anchor = pair.anchor.account(&pair.anchor.escrow).unwrap();
+    let (p, a) = (pinocchio.data(), anchor.data());
+    let (pl, al) = (&PINOCCHIO_LAYOUT, &ANCHOR_LAYOUT);
+    assert_eq!(read_u64(p, pl.seed), read_u64(a, al.seed));
+    assert_eq!(read_address(p, pl.maker), read_address(a, al.maker));
+    assert_eq!(read_address(p, pl.mint_a), read_address(a, al.mint_a));
+    assert_eq!(read_address(p, pl.mint_b), read_address(a, al.mint_b));
+    assert_eq!(read_u64(p, pl.receive), read_u64(a, al.receive));
+    assert_eq!(
+        p[ESCROW_DISCRIMINATOR_OFFSET],
+        a[0],
+        "两边 discriminator 应相同"
+    );
+    pair.assert_same_balances();
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_parity_make_take() {
+    let mut pair = Pair::new();
+    assert_eq!(pair.run_same(default_make, maker), None);
+    assert_eq!(pair.run_same(|f| f.take_ix(), taker), None);
+
+    pair.assert_same_balances();
+    pair.assert_same_lamports();
+    assert_eq!(
+        pair.pinocchio.token_balance(&pair.pinocchio.maker_ata_b),
+        RECEIVE_AMOUNT
+    );
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_parity_make_refund() {
+    let mut pair = Pair::new();
+    assert_eq!(pair.run_same(default_make, maker), None);
+    assert_eq!(pair.run_same(|f| f.refund_ix(), maker), None);
+
+    pair.assert_same_balances();
+    pair.assert_same_lamports();
+    assert_eq!(
+        pair.pinocchio.token_balance(&pair.pinocchio.maker_ata_a),
+        INITIAL_BALANCE
+    );
+}
+
+// ========== 失败路径 ==========
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_parity_make_invalid_amount() {
+    let mut pair = Pair::new();
+    assert_eq!(
+        pair.run_same(|f| f.make_ix(RECEIVE_AMOUNT, 0), maker),
+        Some(ErrorClass::InvalidAmount)
+    );
+    assert_eq!(
+        pair.run_same(|f| f.make_ix(0, OFFER_AMOUNT), maker),
+        Some(ErrorClass::InvalidAmount)
+    );
+    pair.assert_same_balances();
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_parity_make_insufficient_balance() {
+    let mut pair = Pair::new();
+    assert_eq!(
+        pair.run_same(|f| f.make_ix(RECEIVE_AMOUNT, INITIAL_BALANCE + 1), maker),
+        Some(ErrorClass::TokenProgram(1))
+    );
+    pair.assert_same_balances();
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_parity_make_missing_signature() {
+    let mut pair = Pair::new();
+    // taker 付费，maker 不签名
+    let build = |f: &Fixture| {
+        let mut ix = default_make(f);
+        ix.accounts[0].is_signer = false;
+        ix
+    };
+    assert_eq!(
+        pair.run_same(build, taker),
+        Some(ErrorClass::MissingSignature)
+    );
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_parity_refund_by_non_maker() {
+    let mut pair = Pair::new();
+    assert_eq!(pair.run_same(default_make, maker), None);
+
+    // taker 冒充 maker 发起 Refund
+    let build = |f: &Fixture| {
+        let mut ix = f.refund_ix();
+        ix.accounts[0].pubkey = f.taker.pubkey();
+        ix
+    };
+    assert_eq!(
+        pair.run_same(build, taker),
+        Some(ErrorClass::AccountMismatch)
+    );
+    pair.assert_same_balances();
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_parity_take_wrong_mint_b() {
+    let mut pair = Pair::new();
+    assert_eq!(pair.run_same(default_make, maker), None);
+
+    // 两边写入同一个伪造的 mint
+    let fake_mint = solana_pubkey::Pubkey::new_unique();
+    for fixture in [&mut pair.pinocchio, &mut pair.anchor] {
+        fixture
+            .svm
+            .set_account(fake_mint, test_support::mint_account(MINT_DECIMALS, 0))
+            .unwrap();
+    }
+    let build = move |f: &Fixture| {
+        let mut ix = f.take_ix();
+        ix.accounts[4].pubkey = fake_mint;
+        ix
+    };
+    assert_eq!(
+        pair.run_same(build, taker),
+        Some(ErrorClass::AccountMismatch)
+    );
+    pair.assert_same_balances();
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_parity_take_after_refund() {
+    let mut pair = Pair::new();
+    assert_eq!(pair.run_same(default_make, maker), None);
+    assert_eq!(pair.run_same(|f| f.refund_ix(), maker), None);
+
+    let (pinocchio, anchor) = pair.run(|f| f.take_ix(), taker);
+    assert!(pinocchio.is_some(), "pinocchio 应拒绝已关闭的 escrow");
+    assert!(anchor.is_some(), "anchor 应拒绝已关闭的 escrow");
+    pair.assert_same_balances();
+}
+
+// ========== 篮子 ==========
+
+/// 在两个程序上对同一种篮子各执行一次 MakeBasket，`prepare` 修改篮子的腿
+fn basket_make_outcomes(prepare: impl Fn(&mut BasketFixture)) -> [Option<ErrorClass>; 2] {
+    PROGRAMS.map(|program| {
+        let mut fixture = plain_basket(program);
+        prepare(&mut fixture);
+        let maker = fixture.maker.insecure_clone();
+        let result = fixture.send(fixture.make_ix(), &maker);
+        let failed = result.is_err();
+        let outcome = outcome(result);
+        if failed {
+            assert!(fixture.is_closed(&fixture.basket), "{program:?}");
+            for leg in &fixture.deposits {
+                assert_eq!(fixture.balance(&maker.pubkey(), leg), INITIAL_BALANCE);
+            }
+        }
+        outcome
+    })
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_parity_basket_rejects_unsupported_mints() {
+    for extension in unsupported_extensions() {
+        let extension = std::slice::from_ref(&extension);
+        // 永久 delegate 可在 Take 之前转走 vault 中的存入 token
+        assert_eq!(
+            basket_make_outcomes(|f| f.use_token_2022_deposit(1, extension)),
+            PROGRAMS.map(|_| Some(ErrorClass::UnsupportedMint)),
+            "存入腿扩展 {}",
+            extension[0].0
+        );
+        assert_eq!(
+            basket_make_outcomes(|f| f.use_token_2022_ask(1, extension)),
+            PROGRAMS.map(|_| Some(ErrorClass::UnsupportedMint)),
+            "要求腿扩展 {}",
+            extension[0].0
+        );
+    }
+    // 不带扩展的 Token-2022 mint 照常接受
+    assert_eq!(
+        basket_make_outcomes(|f| f.use_token_2022_deposit(1, &[])),
+        [None, None]
+    );
+}
@@ -0,0 +1,247 @@
+//! 篮子 escrow（MakeBasket / TakeBasket / RefundBasket）：maker 存入多种 token，一次 Take 换取多种 token。
+//! 每个场景分别在 pinocchio_escrow 与 anchor_escrow 上执行：两者账户顺序相同，指令数据与错误码各自编码。
+//!
+//! 运行前请先在 season1/ 构建程序：`cargo build-programs`，测试默认 ignored，用 `cargo test-programs` 运行。
+
+mod common;
+
+use common::basket::*;
+use common::*;
+use solana_signer::Signer;
+
+const FEE_BPS: u16 = 100;
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_basket_make_take() {
+    for program in PROGRAMS {
+        let mut fixture = plain_basket(program);
+        fixture.make();
+        for leg in &fixture.deposits {
+            assert_eq!(fixture.token_balance(&fixture.vault(leg)), leg.amount);
+        }
+
+        let taker = fixture.taker.insecure_clone();
+        let result = fixture.send(fixture.take_ix(), &taker);
+        assert!(
+            result.is_ok(),
+            "{program:?} TakeBasket 应成功: {:?}",
+            result
+        );
+
+        let maker = fixture.maker.pubkey();
+        for leg in &fixture.deposits {
+            assert_eq!(fixture.balance(&taker.pubkey(), leg), leg.amount);
+            assert_eq!(fixture.balance(&maker, leg), INITIAL_BALANCE - leg.amount);
+            assert!(fixture.is_closed(&fixture.vault(leg)));
+        }
+        for leg in &fixture.asks {
+            assert_eq!(fixture.balance(&maker, leg), leg.amount);
+            assert_eq!(
+                fixture.balance(&taker.pubkey(), leg),
+                INITIAL_BALANCE - leg.amount
+            );
+        }
+        assert!(fixture.is_closed(&fixture.basket));
+    }
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_basket_refund() {
+    for program in PROGRAMS {
+        let mut fixture = plain_basket(program);
+        fixture.make();
+
+        let maker = fixture.maker.insecure_clone();
+        let result = fixture.send(fixture.refund_ix(), &maker);
+        assert!(
+            result.is_ok(),
+            "{program:?} RefundBasket 应成功: {:?}",
+            result
+        );
+
+        for leg in &fixture.deposits {
+            assert_eq!(fixture.balance(&maker.pubkey(), leg), INITIAL_BALANCE);
+            assert!(fixture.is_closed(&fixture.vault(leg)));
+        }
+        assert!(fixture.is_closed(&fixture.basket));
+    }
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_basket_make_fail_leg_count_mismatch() {
+    for program in PROGRAMS {
+        let mut fixture = plain_basket(program);
+        let maker = fixture.maker.insecure_clone();
+
+        // 少传最后一种要求的 mint
+        let mut ix = fixture.make_ix();
+        ix.accounts.pop();
+        let result = fixture.send(ix, &maker);
+        fixture.assert_error(result, program.leg_mismatch());
+
+        // 多传一条存入腿的账户
+        let mut ix = fixture.make_ix();
+        let extra = ix.accounts[4..8].to_vec();
+        ix.accounts.splice(8..8, extra);
+        let result = fixture.send(ix, &maker);
+        fixture.assert_error(result, program.leg_mismatch());
+        assert!(fixture.is_closed(&fixture.basket));
+    }
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_basket_take_fail_leg_count_mismatch() {
+    for program in PROGRAMS {
+        let mut fixture = plain_basket(program);
+        fixture.make();
+        let taker = fixture.taker.insecure_clone();
+
+        // 漏掉最后一条要求腿：maker 会少收一种 token
+        let mut ix = fixture.take_ix();
+        ix.accounts.truncate(ix.accounts.len() - 4);
+        let result = fixture.send(ix, &taker);
+        fixture.assert_error(result, program.leg_mismatch());
+
+        for leg in &fixture.deposits {
+            assert_eq!(fixture.token_balance(&fixture.vault(leg)), leg.amount);
+        }
+        assert!(!fixture.is_closed(&fixture.basket));
+    }
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_basket_refund_fail_leg_count_mismatch() {
+    for program in PROGRAMS {
+        let mut fixture = plain_basket(program);
+        fixture.make();
+        let maker = fixture.maker.insecure_clone();
+
+        // 只退一个 vault，另一个会被遗弃
+        let mut ix = fixture.refund_ix();
+        ix.accounts.truncate(ix.accounts.len() - 4);
+        let result = fixture.send(ix, &maker);
+        fixture.assert_error(result, program.leg_mismatch());
+
+        for leg in &fixture.deposits {
+            assert_eq!(fixture.token_balance(&fixture.vault(leg)), leg.amount);
+        }
+        assert!(!fixture.is_closed(&fixture.basket));
+    }
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_basket_make_fail_duplicate_mint() {
+    for program in PROGRAMS {
+        let mut fixture = plain_basket(program);
+        let maker = fixture.maker.insecure_clone();
+
+        // 两条存入腿用同一种 mint（同一个 vault）
+        let mut ix = fixture.make_ix();
+        let first = ix.accounts[4..8].to_vec();
+        ix.accounts.splice(8..12, first);
+        let result = fixture.send(ix, &maker);
+        fixture.assert_error(result, program.duplicate_mint());
+
+        // 两种要求的 mint 相同
+        let mut ix = fixture.make_ix();
+        let last = ix.accounts.len() - 1;
+        ix.accounts[last] = ix.accounts[last - 1].clone();
+        let result = fixture.send(ix, &maker);
+        fixture.assert_error(result, program.duplicate_mint());
+        assert!(fixture.is_closed(&fixture.basket));
+    }
+}
+
+/// 第二条存入腿与第二条要求腿为收手续费的 Token-2022 mint
+fn fee_basket(program: Program) -> BasketFixture {
+    BasketFixture::new(
+        program,
+        &[(100_000, None), (30_000, Some(FEE_BPS))],
+        &[(250_000, None), (7_000, Some(FEE_BPS))],
+    )
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_basket_take_with_transfer_fees() {
+    for program in PROGRAMS {
+        let mut fixture = fee_basket(program);
+        fixture.make();
+        let fee_deposit = &fixture.deposits[1];
+        assert_eq!(
+            fixture.token_balance(&fixture.vault(fee_deposit)),
+            fixture.received(fee_deposit)
+        );
+
+        let taker = fixture.taker.insecure_clone();
+        let result = fixture.send(fixture.take_ix(), &taker);
+        assert!(
+            result.is_ok(),
+            "{program:?} TakeBasket 应成功: {:?}",
+            result
+        );
+
+        // maker 按报价净收，taker 多付手续费
+        let maker = fixture.maker.pubkey();
+        let fee_ask = &fixture.asks[1];
+        let gross = (fee_ask.amount..)
+            .find(|gross| gross - transfer_fee(*gross, FEE_BPS, NO_FEE_CAP) >= fee_ask.amount)
+            .unwrap();
+        assert_eq!(fixture.balance(&maker, fee_ask), fee_ask.amount);
+        assert_eq!(
+            fixture.balance(&taker.pubkey(), fee_ask),
+            INITIAL_BALANCE - gross
+        );
+
+        // 扣留在 vault 中的存入手续费收回 mint 后 vault 才能关闭
+        let fee_deposit = &fixture.deposits[1];
+        let received = fixture.received(fee_deposit);
+        assert!(fixture.is_closed(&fixture.vault(fee_deposit)));
+        assert_eq!(
+            withheld_in_mint(&fixture.svm, &fee_deposit.mint),
+            fee_deposit.amount - received
+        );
+        assert_eq!(
+            fixture.balance(&taker.pubkey(), fee_deposit),
+            received - transfer_fee(received, FEE_BPS, NO_FEE_CAP)
+        );
+        assert!(fixture.is_closed(&fixture.basket));
+    }
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_basket_refund_with_transfer_fees() {
+    for program in PROGRAMS {
+        let mut fixture = fee_basket(program);
+        fixture.make();
+
+        let maker = fixture.maker.insecure_clone();
+        let result = fixture.send(fixture.refund_ix(), &maker);
+        assert!(
+            result.is_ok(),
+            "{program:?} RefundBasket 应成功: {:?}",
+            result
+        );
+
+        let fee_deposit = &fixture.deposits[1];
+        let received = fixture.received(fee_deposit);
+        assert!(fixture.is_closed(&fixture.vault(fee_deposit)));
+        assert_eq!(
+            withheld_in_mint(&fixture.svm, &fee_deposit.mint),
+            fee_deposit.amount - received
+        );
+        assert_eq!(
+            fixture.balance(&maker.pubkey(), fee_deposit),
+            INITIAL_BALANCE - fee_deposit.amount + received
+                - transfer_fee(received, FEE_BPS, NO_FEE_CAP)
+        );
+        assert!(fixture.is_closed(&fixture.basket));
+    }
+}
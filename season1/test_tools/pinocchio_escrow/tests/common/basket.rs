@@ -0,0 +1,400 @@
+//! 篮子 escrow 的 fixture：同一篮子场景可分别在 pinocchio_escrow 与 anchor_escrow 上构造与执行
+
+use litesvm::types::TransactionResult;
+use litesvm::LiteSVM;
+use solana_address::Address;
+use solana_instruction::error::InstructionError;
+use solana_instruction::{AccountMeta, Instruction};
+use solana_keypair::Keypair;
+use solana_pubkey::Pubkey;
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+use test_support::{
+    associated_token_address_with_program, associated_token_program_id, mint_account,
+    svm_with_programs, system_program_id, token_2022_account, token_2022_mint,
+    token_2022_program_id, token_account, token_program_id,
+};
+
+use super::*;
+
+pub const MAKE_BASKET: u8 = 4;
+pub const TAKE_BASKET: u8 = 5;
+pub const REFUND_BASKET: u8 = 6;
+
+pub const NO_FEE_CAP: u64 = u64::MAX;
+
+#[derive(Clone, Copy, Debug)]
+pub enum Program {
+    Pinocchio,
+    Anchor,
+}
+
+pub const PROGRAMS: [Program; 2] = [Program::Pinocchio, Program::Anchor];
+
+impl Program {
+    pub fn id(self) -> Address {
+        match self {
+            Program::Pinocchio => escrow_program_id(),
+            Program::Anchor => anchor_program_id(),
+        }
+    }
+
+    pub fn svm(self) -> LiteSVM {
+        let name = match self {
+            Program::Pinocchio => "pinocchio_escrow",
+            Program::Anchor => "anchor_escrow",
+        };
+        svm_with_programs(&[(self.id(), name)])
+    }
+
+    /// MakeBasket 数据。pinocchio：seed(8) + 两个数量(1 + 1) + 各 amount(8)；
+    /// anchor：seed(8) + 两个 borsh `Vec<u64>`（长度 u32 + 元素）
+    pub fn make_data(self, seed: u64, deposits: &[u64], asks: &[u64]) -> Vec<u8> {
+        let mut data = vec![MAKE_BASKET];
+        data.extend_from_slice(&seed.to_le_bytes());
+        match self {
+            Program::Pinocchio => {
+                data.push(deposits.len() as u8);
+                data.push(asks.len() as u8);
+                for amount in deposits.iter().chain(asks) {
+                    data.extend_from_slice(&amount.to_le_bytes());
+                }
+            }
+            Program::Anchor => {
+                for amounts in [deposits, asks] {
+                    data.extend_from_slice(&(amounts.len() as u32).to_le_bytes());
+                    for amount in amounts {
+                        data.extend_from_slice(&amount.to_le_bytes());
+                    }
+                }
+            }
+        }
+        data
+    }
+
+    /// 账户数与篮子的腿数不符（pinocchio NotEnoughAccountKeys，anchor InvalidBasketLegs）；
+    /// 程序返回的 NotEnoughAccountKeys 仍映射到这个已弃用的变体
+    #[allow(deprecated)]
+    pub fn leg_mismatch(self) -> InstructionError {
+        match self {
+            Program::Pinocchio => InstructionError::NotEnoughAccountKeys,
+            Program::Anchor => InstructionError::Custom(6005),
+        }
+    }
+
+    pub fn duplicate_mint(self) -> InstructionError {
+        match self {
+            Program::Pinocchio => InstructionError::Custom(6009),
+            Program::Anchor => InstructionError::Custom(6004),
+        }
+    }
+}
+
+/// 篮子的一条腿：一种 mint；`fee` 为 Some 时是收转账手续费的 Token-2022 mint
+pub struct Leg {
+    pub mint: Address,
+    pub token_program: Address,
+    pub amount: u64,
+    pub fee: Option<u16>,
+}
+
+pub struct BasketFixture {
+    pub svm: LiteSVM,
+    pub program: Program,
+    pub maker: Keypair,
+    pub taker: Keypair,
+    pub seed: u64,
+    pub basket: Address,
+    pub deposits: Vec<Leg>,
+    pub asks: Vec<Leg>,
+}
+
+impl BasketFixture {
+    /// `deposits` / `asks` 为 (数量, 手续费 bps)：maker 持有存入的 token，taker 持有要求的 token
+    pub fn new(
+        program: Program,
+        deposits: &[(u64, Option<u16>)],
+        asks: &[(u64, Option<u16>)],
+    ) -> Self {
+        let mut svm = program.svm();
+        let maker = Keypair::new();
+        let taker = Keypair::new();
+        svm.airdrop(&maker.pubkey(), 10_000_000_000).unwrap();
+        svm.airdrop(&taker.pubkey(), 10_000_000_000).unwrap();
+
+        let seed = 11u64;
+        let (basket, _) = Address::find_program_address(
+            &[b"basket", maker.pubkey().as_ref(), &seed.to_le_bytes()],
+            &program.id(),
+        );
+
+        let mut fixture = Self {
+            svm,
+            program,
+            maker,
+            taker,
+            seed,
+            basket,
+            deposits: Vec::new(),
+            asks: Vec::new(),
+        };
+        let maker = fixture.maker.pubkey();
+        let taker = fixture.taker.pubkey();
+        fixture.deposits = deposits
+            .iter()
+            .map(|(amount, fee)| fixture.add_leg(*amount, *fee, &maker))
+            .collect();
+        fixture.asks = asks
+            .iter()
+            .map(|(amount, fee)| fixture.add_leg(*amount, *fee, &taker))
+            .collect();
+        fixture
+    }
+
+    /// 新建 mint，并给 `holder` 一个持有 INITIAL_BALANCE 的 ATA
+    pub fn add_leg(&mut self, amount: u64, fee: Option<u16>, holder: &Address) -> Leg {
+        let mint = Pubkey::new_unique();
+        let leg = Leg {
+            mint,
+            token_program: if fee.is_some() {
+                token_2022_program_id()
+            } else {
+                token_program_id()
+            },
+            amount,
+            fee,
+        };
+        let ata = self.ata(holder, &leg);
+        match fee {
+            Some(basis_points) => {
+                let mint_extensions = [transfer_fee_config(basis_points, NO_FEE_CAP)];
+                let account_extensions = [transfer_fee_amount()];
+                self.svm
+                    .set_account(
+                        mint,
+                        token_2022_mint(MINT_DECIMALS, INITIAL_BALANCE, &mint_extensions),
+                    )
+                    .unwrap();
+                self.svm
+                    .set_account(
+                        ata,
+                        token_2022_account(&mint, holder, INITIAL_BALANCE, &account_extensions),
+                    )
+                    .unwrap();
+            }
+            None => {
+                self.svm
+                    .set_account(mint, mint_account(MINT_DECIMALS, INITIAL_BALANCE))
+                    .unwrap();
+                self.svm
+                    .set_account(ata, token_account(&mint, holder, INITIAL_BALANCE))
+                    .unwrap();
+            }
+        }
+        leg
+    }
+
+    /// 把第 `index` 条存入腿换成带 `mint_extensions` 的新 Token-2022 mint
+    pub fn use_token_2022_deposit(&mut self, index: usize, mint_extensions: &[(u16, Vec<u8>)]) {
+        let maker = self.maker.pubkey();
+        let amount = self.deposits[index].amount;
+        self.deposits[index] = self.add_token_2022_leg(amount, mint_extensions, &maker);
+    }
+
+    /// 把第 `index` 条要求腿换成带 `mint_extensions` 的新 Token-2022 mint
+    pub fn use_token_2022_ask(&mut self, index: usize, mint_extensions: &[(u16, Vec<u8>)]) {
+        let taker = self.taker.pubkey();
+        let amount = self.asks[index].amount;
+        self.asks[index] = self.add_token_2022_leg(amount, mint_extensions, &taker);
+    }
+
+    /// 新建带 `mint_extensions` 的 Token-2022 mint，并给 `holder` 一个持有 INITIAL_BALANCE 的 ATA
+    fn add_token_2022_leg(
+        &mut self,
+        amount: u64,
+        mint_extensions: &[(u16, Vec<u8>)],
+        holder: &Address,
+    ) -> Leg {
+        let mint = Pubkey::new_unique();
+        let leg = Leg {
+            mint,
+            token_program: token_2022_program_id(),
+            amount,
+            fee: None,
+        };
+        let ata = self.ata(holder, &leg);
+        self.svm
+            .set_account(
+                mint,
+                token_2022_mint(MINT_DECIMALS, INITIAL_BALANCE, mint_extensions),
+            )
+            .unwrap();
+        self.svm
+            .set_account(ata, token_2022_account(&mint, holder, INITIAL_BALANCE, &[]))
+            .unwrap();
+        leg
+    }
+
+    pub fn ata(&self, wallet: &Address, leg: &Leg) -> Address {
+        associated_token_address_with_program(wallet, &leg.mint, &leg.token_program)
+    }
+
+    pub fn vault(&self, leg: &Leg) -> Address {
+        self.ata(&self.basket, leg)
+    }
+
+    pub fn balance(&self, wallet: &Address, leg: &Leg) -> u64 {
+        self.token_balance(&self.ata(wallet, leg))
+    }
+
+    pub fn token_balance(&self, address: &Address) -> u64 {
+        self.svm
+            .get_account(address)
+            .map(|account| test_support::token_account_amount(&account.data))
+            .unwrap_or(0)
+    }
+
+    pub fn is_closed(&self, address: &Address) -> bool {
+        self.svm
+            .get_account(address)
+            .is_none_or(|account| account.lamports == 0)
+    }
+
+    pub fn instruction(
+        &self,
+        signer: &Address,
+        mut accounts: Vec<AccountMeta>,
+        data: Vec<u8>,
+    ) -> Instruction {
+        let mut fixed = vec![AccountMeta::new(*signer, true)];
+        if *signer != self.maker.pubkey() {
+            fixed.push(AccountMeta::new(self.maker.pubkey(), false));
+        }
+        fixed.extend([
+            AccountMeta::new(self.basket, false),
+            AccountMeta::new_readonly(associated_token_program_id(), false),
+            AccountMeta::new_readonly(system_program_id(), false),
+        ]);
+        fixed.append(&mut accounts);
+        Instruction {
+            program_id: self.program.id(),
+            accounts: fixed,
+            data,
+        }
+    }
+
+    /// MakeBasket：每条存入腿 [mint, maker_ata, vault, token_program]，之后每种要求的 mint
+    pub fn make_ix(&self) -> Instruction {
+        let maker = self.maker.pubkey();
+        let mut accounts = Vec::new();
+        for leg in &self.deposits {
+            accounts.extend([
+                AccountMeta::new_readonly(leg.mint, false),
+                AccountMeta::new(self.ata(&maker, leg), false),
+                AccountMeta::new(self.vault(leg), false),
+                AccountMeta::new_readonly(leg.token_program, false),
+            ]);
+        }
+        for leg in &self.asks {
+            accounts.push(AccountMeta::new_readonly(leg.mint, false));
+        }
+        let deposits: Vec<u64> = self.deposits.iter().map(|leg| leg.amount).collect();
+        let asks: Vec<u64> = self.asks.iter().map(|leg| leg.amount).collect();
+        let data = self.program.make_data(self.seed, &deposits, &asks);
+        self.instruction(&maker, accounts, data)
+    }
+
+    /// TakeBasket：每条存入腿 [mint, vault, taker_ata, token_program]，
+    /// 每条要求腿 [mint, taker_ata, maker_ata, token_program]
+    pub fn take_ix(&self) -> Instruction {
+        let maker = self.maker.pubkey();
+        let taker = self.taker.pubkey();
+        let mut accounts = Vec::new();
+        for leg in &self.deposits {
+            // 收手续费的 mint 在关闭 vault 前要收取扣留的手续费，需可写
+            accounts.extend([
+                AccountMeta::new(leg.mint, false),
+                AccountMeta::new(self.vault(leg), false),
+                AccountMeta::new(self.ata(&taker, leg), false),
+                AccountMeta::new_readonly(leg.token_program, false),
+            ]);
+        }
+        for leg in &self.asks {
+            accounts.extend([
+                AccountMeta::new_readonly(leg.mint, false),
+                AccountMeta::new(self.ata(&taker, leg), false),
+                AccountMeta::new(self.ata(&maker, leg), false),
+                AccountMeta::new_readonly(leg.token_program, false),
+            ]);
+        }
+        self.instruction(&taker, accounts, vec![TAKE_BASKET])
+    }
+
+    /// RefundBasket：每条存入腿 [mint, vault, maker_ata, token_program]
+    pub fn refund_ix(&self) -> Instruction {
+        let maker = self.maker.pubkey();
+        let mut accounts = Vec::new();
+        for leg in &self.deposits {
+            accounts.extend([
+                AccountMeta::new(leg.mint, false),
+                AccountMeta::new(self.vault(leg), false),
+                AccountMeta::new(self.ata(&maker, leg), false),
+                AccountMeta::new_readonly(leg.token_program, false),
+            ]);
+        }
+        self.instruction(&maker, accounts, vec![REFUND_BASKET])
+    }
+
+    #[allow(clippy::result_large_err)]
+    pub fn send(&mut self, ix: Instruction, signer: &Keypair) -> TransactionResult {
+        self.svm.expire_blockhash();
+        let tx = Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&signer.pubkey()),
+            &[signer],
+            self.svm.latest_blockhash(),
+        );
+        self.svm.send_transaction(tx)
+    }
+
+    pub fn make(&mut self) {
+        let maker = self.maker.insecure_clone();
+        let result = self.send(self.make_ix(), &maker);
+        assert!(
+            result.is_ok(),
+            "{:?} MakeBasket 应成功: {:?}",
+            self.program,
+            result
+        );
+    }
+
+    pub fn assert_error(&self, result: TransactionResult, expected: InstructionError) {
+        let program = self.program;
+        match result {
+            Err(failed) => assert_eq!(
+                failed.err,
+                solana_transaction_error::TransactionError::InstructionError(0, expected),
+                "{program:?} logs: {:#?}",
+                failed.meta.logs
+            ),
+            Ok(_) => panic!("{program:?} 交易应失败，期望 {expected:?}"),
+        }
+    }
+
+    /// 存入后每个 vault 的实际余额（扣除存入时的手续费）
+    pub fn received(&self, leg: &Leg) -> u64 {
+        let fee = leg
+            .fee
+            .map_or(0, |bps| transfer_fee(leg.amount, bps, NO_FEE_CAP));
+        leg.amount - fee
+    }
+}
+
+/// 两种存入、两种要求，均为 SPL Token
+pub fn plain_basket(program: Program) -> BasketFixture {
+    BasketFixture::new(
+        program,
+        &[(100_000, None), (30_000, None)],
+        &[(250_000, None), (7_000, None)],
+    )
+}
@@ -0,0 +1,529 @@
+//! pinocchio_escrow LiteSVM 测试的公共代码：账户准备与指令构造
+//!
+//! 运行前请先在 season1/ 构建程序：`cargo build-programs`，测试默认 ignored，用 `cargo test-programs` 运行。
+
+#![allow(dead_code, clippy::result_large_err)]
+
+pub mod basket;
+
+use std::str::FromStr;
+
+use litesvm::types::TransactionResult;
+use litesvm::LiteSVM;
+use pinocchio_escrow::TakeInstructionData;
+use solana_account::{Account, ReadableAccount};
+use solana_address::Address;
+use solana_clock::Clock;
+use solana_instruction::error::InstructionError;
+use solana_instruction::{AccountMeta, Instruction};
+use solana_keypair::Keypair;
+use solana_pubkey::Pubkey;
+use solana_sha256_hasher::{hash, hashv};
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+use solana_transaction_error::TransactionError;
+use test_support::{
+    associated_token_address, associated_token_address_with_program, associated_token_program_id,
+    mint_account, svm_with_programs, system_program_id, token_2022_account, token_2022_extension,
+    token_2022_mint, token_2022_program_id, token_account, token_program_id,
+};
+
+pub const MINT_DECIMALS: u8 = 6;
+/// 双方初始余额
+pub const INITIAL_BALANCE: u64 = 1_000_000;
+/// Make 存入的 token A 数量
+pub const OFFER_AMOUNT: u64 = 100_000;
+/// Make 要求的 token B 数量
+pub const RECEIVE_AMOUNT: u64 = 250_000;
+
+/// Escrow 账户布局（见 pinocchio_escrow::state::Escrow）
+pub const ESCROW_DISCRIMINATOR_OFFSET: usize = 0;
+pub const ESCROW_SEED_OFFSET: usize = 8;
+pub const ESCROW_MAKER_OFFSET: usize = 16;
+pub const ESCROW_MINT_A_OFFSET: usize = 48;
+pub const ESCROW_MINT_B_OFFSET: usize = 80;
+pub const ESCROW_RECEIVE_OFFSET: usize = 112;
+pub const ESCROW_REMAINING_OFFSET: usize = 128;
+pub const ESCROW_SEQUENCE_OFFSET: usize = 136;
+pub const ESCROW_END_RECEIVE_OFFSET: usize = 176;
+pub const ESCROW_RENT_PAYER_OFFSET: usize = 200;
+
+/// Token-2022 转账手续费扩展类型
+pub const TRANSFER_FEE_CONFIG: u16 = 1;
+pub const TRANSFER_FEE_AMOUNT: u16 = 2;
+/// TransferFeeConfig 中 mint 扣留的手续费（两个 authority 之后）
+pub const TRANSFER_FEE_CONFIG_WITHHELD_OFFSET: usize = 64;
+
+/// escrow 不接受的 Token-2022 mint 扩展类型
+pub const NON_TRANSFERABLE: u16 = 9;
+pub const PERMANENT_DELEGATE: u16 = 12;
+pub const TRANSFER_HOOK: u16 = 14;
+
+/// anchor_escrow 的 `declare_id!`
+pub const ANCHOR_ESCROW_ID: &str = "22222222222222222222222222222222222222222222";
+
+pub fn escrow_program_id() -> Address {
+    pinocchio_escrow::ID
+}
+
+pub fn anchor_program_id() -> Address {
+    Address::from_str(ANCHOR_ESCROW_ID).unwrap()
+}
+
+/// 加载 pinocchio_escrow；未构建二进制时 panic
+pub fn escrow_svm() -> LiteSVM {
+    svm_with_programs(&[(escrow_program_id(), "pinocchio_escrow")])
+}
+
+/// 返回 `program_id` 下的 (escrow_pda, bump)
+pub fn find_escrow_pda(program_id: &Address, maker: &Address, seed: u64) -> (Address, u8) {
+    Address::find_program_address(
+        &[b"escrow", maker.as_ref(), &seed.to_le_bytes()],
+        program_id,
+    )
+}
+
+pub fn read_u64(data: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap())
+}
+
+pub fn read_address(data: &[u8], offset: usize) -> Address {
+    Address::new_from_array(data[offset..offset + 32].try_into().unwrap())
+}
+
+/// TransferFeeConfig：两个 authority 留空，新旧两档费率相同
+pub fn transfer_fee_config(basis_points: u16, maximum_fee: u64) -> (u16, Vec<u8>) {
+    let mut value = vec![0u8; 72];
+    for _ in 0..2 {
+        value.extend_from_slice(&0u64.to_le_bytes());
+        value.extend_from_slice(&maximum_fee.to_le_bytes());
+        value.extend_from_slice(&basis_points.to_le_bytes());
+    }
+    (TRANSFER_FEE_CONFIG, value)
+}
+
+/// 手续费 mint 的 token 账户需要 TransferFeeAmount 记录扣留的手续费
+pub fn transfer_fee_amount() -> (u16, Vec<u8>) {
+    (TRANSFER_FEE_AMOUNT, vec![0; 8])
+}
+
+/// Token-2022 对一次转账扣留的手续费：ceil(amount * bps / 10000)，不超过上限
+pub fn transfer_fee(amount: u64, basis_points: u16, maximum_fee: u64) -> u64 {
+    (amount as u128 * basis_points as u128)
+        .div_ceil(10_000)
+        .min(maximum_fee as u128) as u64
+}
+
+/// 永久 delegate、不可转让与设置了 hook 程序的 mint 都不能放进 escrow
+pub fn unsupported_extensions() -> Vec<(u16, Vec<u8>)> {
+    let mut hook = vec![0u8; 32];
+    hook.extend_from_slice(Pubkey::new_unique().as_ref());
+    vec![
+        (PERMANENT_DELEGATE, Pubkey::new_unique().as_ref().to_vec()),
+        (NON_TRANSFERABLE, vec![]),
+        (TRANSFER_HOOK, hook),
+    ]
+}
+
+/// mint 中已收取（harvest）的手续费
+pub fn withheld_in_mint(svm: &LiteSVM, mint: &Address) -> u64 {
+    let account = svm.get_account(mint).expect("mint 应存在");
+    let value =
+        token_2022_extension(account.data(), TRANSFER_FEE_CONFIG).expect("应有 TransferFeeConfig");
+    read_u64(value, TRANSFER_FEE_CONFIG_WITHHELD_OFFSET)
+}
+
+/// 与 Escrow::is_allowed_taker 相同的 merkle 树：叶子为 sha256(taker)，父节点为排序后两子节点的 sha256。
+/// 返回 (root, `index` 处叶子从下到上的 proof)；叶子数需为 2 的幂
+pub fn merkle_root_and_proof(takers: &[Address], index: usize) -> (Address, Vec<u8>) {
+    assert!(takers.len().is_power_of_two());
+    let mut level: Vec<[u8; 32]> = takers
+        .iter()
+        .map(|taker| hash(taker.as_ref()).to_bytes())
+        .collect();
+    let mut index = index;
+    let mut proof = Vec::new();
+    while level.len() > 1 {
+        proof.extend_from_slice(&level[index ^ 1]);
+        level = level
+            .chunks_exact(2)
+            .map(|pair| {
+                let (low, high) = if pair[0] <= pair[1] {
+                    (&pair[0], &pair[1])
+                } else {
+                    (&pair[1], &pair[0])
+                };
+                hashv(&[low, high]).to_bytes()
+            })
+            .collect();
+        index /= 2;
+    }
+    (Address::new_from_array(level[0]), proof)
+}
+
+/// 参与方与 mint：差分测试中两个程序使用同一组，便于逐字段比较
+pub struct Parties {
+    pub maker: Keypair,
+    pub taker: Keypair,
+    pub mint_a: Address,
+    pub mint_b: Address,
+}
+
+impl Parties {
+    pub fn new() -> Self {
+        Self {
+            maker: Keypair::new(),
+            taker: Keypair::new(),
+            mint_a: Pubkey::new_unique(),
+            mint_b: Pubkey::new_unique(),
+        }
+    }
+}
+
+impl Default for Parties {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 一次 Make/Take/Refund 场景所需的全部账户
+pub struct Fixture {
+    pub svm: LiteSVM,
+    pub program_id: Address,
+    pub maker: Keypair,
+    pub taker: Keypair,
+    pub mint_a: Address,
+    pub mint_b: Address,
+    /// mint A / mint B 所属的 token 程序，默认 SPL Token
+    pub token_program_a: Address,
+    pub token_program_b: Address,
+    pub seed: u64,
+    pub escrow: Address,
+    pub vault: Address,
+    pub maker_ata_a: Address,
+    pub maker_ata_b: Address,
+    pub taker_ata_a: Address,
+    pub taker_ata_b: Address,
+}
+
+impl Fixture {
+    /// pinocchio_escrow 上的默认场景
+    pub fn new(svm: LiteSVM) -> Self {
+        Self::with_parties(svm, escrow_program_id(), &Parties::new())
+    }
+
+    /// 准备 mint 与双方的 token 账户：maker 持有 token A，taker 持有 token B
+    pub fn with_parties(mut svm: LiteSVM, program_id: Address, parties: &Parties) -> Self {
+        let maker = parties.maker.insecure_clone();
+        let taker = parties.taker.insecure_clone();
+        svm.airdrop(&maker.pubkey(), 10_000_000_000).unwrap();
+        svm.airdrop(&taker.pubkey(), 10_000_000_000).unwrap();
+
+        let mint_a = parties.mint_a;
+        let mint_b = parties.mint_b;
+        svm.set_account(mint_a, mint_account(MINT_DECIMALS, INITIAL_BALANCE))
+            .unwrap();
+        svm.set_account(mint_b, mint_account(MINT_DECIMALS, INITIAL_BALANCE))
+            .unwrap();
+
+        let maker_ata_a = associated_token_address(&maker.pubkey(), &mint_a);
+        let taker_ata_b = associated_token_address(&taker.pubkey(), &mint_b);
+        svm.set_account(
+            maker_ata_a,
+            token_account(&mint_a, &maker.pubkey(), INITIAL_BALANCE),
+        )
+        .unwrap();
+        svm.set_account(
+            taker_ata_b,
+            token_account(&mint_b, &taker.pubkey(), INITIAL_BALANCE),
+        )
+        .unwrap();
+
+        let seed = 7u64;
+        let (escrow, _) = find_escrow_pda(&program_id, &maker.pubkey(), seed);
+
+        Self {
+            maker_ata_b: associated_token_address(&maker.pubkey(), &mint_b),
+            taker_ata_a: associated_token_address(&taker.pubkey(), &mint_a),
+            vault: associated_token_address(&escrow, &mint_a),
+            svm,
+            program_id,
+            maker,
+            taker,
+            mint_a,
+            mint_b,
+            token_program_a: token_program_id(),
+            token_program_b: token_program_id(),
+            seed,
+            escrow,
+            maker_ata_a,
+            taker_ata_b,
+        }
+    }
+
+    /// 把 mint A 换成带 `mint_extensions` 的 Token-2022 mint，maker 的 token 账户带 `account_extensions`；
+    /// vault 与双方 ATA 地址随 token 程序重新推导
+    pub fn use_token_2022_a(
+        &mut self,
+        mint_extensions: &[(u16, Vec<u8>)],
+        account_extensions: &[(u16, Vec<u8>)],
+    ) {
+        let program = token_2022_program_id();
+        let maker = self.maker.pubkey();
+        self.token_program_a = program;
+        self.maker_ata_a = associated_token_address_with_program(&maker, &self.mint_a, &program);
+        self.taker_ata_a =
+            associated_token_address_with_program(&self.taker.pubkey(), &self.mint_a, &program);
+        self.vault = associated_token_address_with_program(&self.escrow, &self.mint_a, &program);
+        self.svm
+            .set_account(
+                self.mint_a,
+                token_2022_mint(MINT_DECIMALS, INITIAL_BALANCE, mint_extensions),
+            )
+            .unwrap();
+        self.svm
+            .set_account(
+                self.maker_ata_a,
+                token_2022_account(&self.mint_a, &maker, INITIAL_BALANCE, account_extensions),
+            )
+            .unwrap();
+    }
+
+    /// 把 mint B 换成 Token-2022 mint，taker 的 token 账户带 `account_extensions`
+    pub fn use_token_2022_b(
+        &mut self,
+        mint_extensions: &[(u16, Vec<u8>)],
+        account_extensions: &[(u16, Vec<u8>)],
+    ) {
+        let program = token_2022_program_id();
+        let taker = self.taker.pubkey();
+        self.token_program_b = program;
+        self.taker_ata_b = associated_token_address_with_program(&taker, &self.mint_b, &program);
+        self.maker_ata_b =
+            associated_token_address_with_program(&self.maker.pubkey(), &self.mint_b, &program);
+        self.svm
+            .set_account(
+                self.mint_b,
+                token_2022_mint(MINT_DECIMALS, INITIAL_BALANCE, mint_extensions),
+            )
+            .unwrap();
+        self.svm
+            .set_account(
+                self.taker_ata_b,
+                token_2022_account(&self.mint_b, &taker, INITIAL_BALANCE, account_extensions),
+            )
+            .unwrap();
+    }
+
+    /// Make 指令：discriminator(0) + seed(8) + receive(8) + amount(8)
+    pub fn make_ix(&self, receive: u64, amount: u64) -> Instruction {
+        let mut data = vec![0u8];
+        data.extend_from_slice(&self.seed.to_le_bytes());
+        data.extend_from_slice(&receive.to_le_bytes());
+        data.extend_from_slice(&amount.to_le_bytes());
+        Instruction {
+            program_id: self.program_id,
+            accounts: vec![
+                AccountMeta::new(self.maker.pubkey(), true),
+                AccountMeta::new(self.escrow, false),
+                AccountMeta::new_readonly(self.mint_a, false),
+                AccountMeta::new_readonly(self.mint_b, false),
+                AccountMeta::new(self.maker_ata_a, false),
+                AccountMeta::new(self.vault, false),
+                AccountMeta::new_readonly(associated_token_program_id(), false),
+                AccountMeta::new_readonly(self.token_program_a, false),
+                AccountMeta::new_readonly(system_program_id(), false),
+            ],
+            data,
+        }
+    }
+
+    /// Take 指令（全部成交）：discriminator(1)；两个 mint 属于不同 token 程序时追加 token_program_b
+    pub fn take_ix(&self) -> Instruction {
+        let mut ix = Instruction {
+            program_id: self.program_id,
+            accounts: vec![
+                AccountMeta::new(self.taker.pubkey(), true),
+                AccountMeta::new(self.maker.pubkey(), false),
+                AccountMeta::new(self.escrow, false),
+                AccountMeta::new_readonly(self.mint_a, false),
+                AccountMeta::new_readonly(self.mint_b, false),
+                AccountMeta::new(self.vault, false),
+                AccountMeta::new(self.taker_ata_a, false),
+                AccountMeta::new(self.taker_ata_b, false),
+                AccountMeta::new(self.maker_ata_b, false),
+                AccountMeta::new_readonly(associated_token_program_id(), false),
+                AccountMeta::new_readonly(self.token_program_a, false),
+                AccountMeta::new_readonly(system_program_id(), false),
+            ],
+            data: vec![1u8],
+        };
+        if self.token_program_b != self.token_program_a {
+            ix.accounts
+                .push(AccountMeta::new_readonly(self.token_program_b, false));
+        }
+        ix
+    }
+
+    /// 带参数的 Take：amount(8) + flags(1) + [sequence(8)] + [max_pay(8)] + merkle proof(32 * n)
+    pub fn take_ix_with(
+        &self,
+        amount: u64,
+        sequence: Option<u64>,
+        max_pay: Option<u64>,
+        proof: &[u8],
+    ) -> Instruction {
+        let mut ix = self.take_ix();
+        ix.data.extend_from_slice(&amount.to_le_bytes());
+        let mut flags = 0;
+        if sequence.is_some() {
+            flags |= TakeInstructionData::HAS_SEQUENCE;
+        }
+        if max_pay.is_some() {
+            flags |= TakeInstructionData::HAS_MAX_PAY;
+        }
+        ix.data.push(flags);
+        for value in sequence.into_iter().chain(max_pay) {
+            ix.data.extend_from_slice(&value.to_le_bytes());
+        }
+        ix.data.extend_from_slice(proof);
+        ix
+    }
+
+    /// Refund 指令：discriminator(2)
+    pub fn refund_ix(&self) -> Instruction {
+        Instruction {
+            program_id: self.program_id,
+            accounts: vec![
+                AccountMeta::new(self.maker.pubkey(), true),
+                AccountMeta::new(self.escrow, false),
+                AccountMeta::new_readonly(self.mint_a, false),
+                AccountMeta::new(self.vault, false),
+                AccountMeta::new(self.maker_ata_a, false),
+                AccountMeta::new_readonly(associated_token_program_id(), false),
+                AccountMeta::new_readonly(self.token_program_a, false),
+                AccountMeta::new_readonly(system_program_id(), false),
+            ],
+            data: vec![2u8],
+        }
+    }
+
+    /// RefundExpired 指令（任何人可调用）：discriminator(3)
+    pub fn refund_expired_ix(&self, caller: &Address) -> Instruction {
+        Instruction {
+            program_id: self.program_id,
+            accounts: vec![
+                AccountMeta::new(*caller, true),
+                AccountMeta::new(self.maker.pubkey(), false),
+                AccountMeta::new(self.escrow, false),
+                AccountMeta::new_readonly(self.mint_a, false),
+                AccountMeta::new(self.vault, false),
+                AccountMeta::new(self.maker_ata_a, false),
+                AccountMeta::new_readonly(associated_token_program_id(), false),
+                AccountMeta::new_readonly(self.token_program_a, false),
+                AccountMeta::new_readonly(system_program_id(), false),
+            ],
+            data: vec![3u8],
+        }
+    }
+
+    /// 以 `signer` 为 payer 发送单条指令
+    pub fn send(&mut self, ix: Instruction, signer: &Keypair) -> TransactionResult {
+        self.svm.expire_blockhash();
+        let tx = Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&signer.pubkey()),
+            &[signer],
+            self.svm.latest_blockhash(),
+        );
+        self.svm.send_transaction(tx)
+    }
+
+    /// 以 `signer` 为 payer 在一笔交易中依次发送多条指令
+    pub fn send_all(&mut self, ixs: &[Instruction], signer: &Keypair) -> TransactionResult {
+        self.svm.expire_blockhash();
+        let tx = Transaction::new_signed_with_payer(
+            ixs,
+            Some(&signer.pubkey()),
+            &[signer],
+            self.svm.latest_blockhash(),
+        );
+        self.svm.send_transaction(tx)
+    }
+
+    /// 由 `payer` 付交易费，`signers` 额外签名
+    pub fn send_with(
+        &mut self,
+        ix: Instruction,
+        payer: &Keypair,
+        signers: &[&Keypair],
+    ) -> TransactionResult {
+        self.svm.expire_blockhash();
+        let mut all_signers = vec![payer];
+        all_signers.extend_from_slice(signers);
+        let tx = Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&payer.pubkey()),
+            &all_signers,
+            self.svm.latest_blockhash(),
+        );
+        self.svm.send_transaction(tx)
+    }
+
+    /// 执行默认参数的 Make，要求成功
+    pub fn make(&mut self) {
+        let ix = self.make_ix(RECEIVE_AMOUNT, OFFER_AMOUNT);
+        let maker = self.maker.insecure_clone();
+        let result = self.send(ix, &maker);
+        assert!(result.is_ok(), "Make 应成功: {:?}", result);
+    }
+
+    pub fn set_clock(&mut self, unix_timestamp: i64) {
+        let mut clock = self.svm.get_sysvar::<Clock>();
+        clock.unix_timestamp = unix_timestamp;
+        self.svm.set_sysvar(&clock);
+    }
+
+    pub fn account(&self, address: &Address) -> Option<Account> {
+        self.svm.get_account(address)
+    }
+
+    /// 账户不存在或已关闭（0 lamports）
+    pub fn is_closed(&self, address: &Address) -> bool {
+        self.account(address)
+            .is_none_or(|account| account.lamports() == 0)
+    }
+
+    pub fn token_balance(&self, address: &Address) -> u64 {
+        self.account(address)
+            .map(|account| test_support::token_account_amount(account.data()))
+            .unwrap_or(0)
+    }
+
+    /// 把 `source` 的账户数据复制到新地址，owner 改为 `owner`，模拟攻击者伪造的账户
+    pub fn forge_copy(&mut self, source: &Address, owner: &Address) -> Address {
+        let mut account = self.account(source).expect("源账户应存在");
+        account.owner = *owner;
+        let forged = Pubkey::new_unique();
+        self.svm.set_account(forged, account).unwrap();
+        forged
+    }
+}
+
+/// 断言交易因指定的 InstructionError 失败（第 0 条指令）
+pub fn assert_instruction_error(result: TransactionResult, expected: InstructionError) {
+    match result {
+        Err(failed) => assert_eq!(
+            failed.err,
+            TransactionError::InstructionError(0, expected),
+            "logs: {:#?}",
+            failed.meta.logs
+        ),
+        Ok(_) => panic!("交易应失败，期望 {:?}", expected),
+    }
+}
+
+/// 断言交易因指定的 EscrowError 自定义错误码失败
+pub fn assert_custom_error(result: TransactionResult, code: u32) {
+    assert_instruction_error(result, InstructionError::Custom(code));
+}
@@ -0,0 +1,151 @@
+//! 荷兰式拍卖 escrow：价格随 Clock 从 receive 衰减到 end_receive，taker 需给出最高价
+//!
+//! 运行前请先在 season1/ 构建程序：`cargo build-programs`，测试默认 ignored，用 `cargo test-programs` 运行。
+
+mod common;
+
+use common::*;
+use pinocchio_escrow::Escrow;
+use solana_account::ReadableAccount;
+use solana_instruction::error::InstructionError;
+use solana_instruction::Instruction;
+
+const INVALID_AUCTION: u32 = 6011;
+const PRICE_EXCEEDED: u32 = 6012;
+
+const AUCTION_START: i64 = 1_000;
+const AUCTION_END: i64 = 2_000;
+/// 拍卖底价
+const END_RECEIVE: u64 = 50_000;
+
+fn setup() -> Fixture {
+    let mut fixture = Fixture::new(escrow_svm());
+    fixture.set_clock(AUCTION_START);
+    fixture
+}
+
+/// Make 数据追加：expiry(8) + taker_mode(1) + taker(32) + auction_mode(1) + end_receive(8) + start(8) + end(8)
+fn auction_make_ix(fixture: &Fixture, auction_mode: u8, end_receive: u64) -> Instruction {
+    let mut ix = fixture.make_ix(RECEIVE_AMOUNT, OFFER_AMOUNT);
+    ix.data.extend_from_slice(&0i64.to_le_bytes());
+    ix.data.push(Escrow::TAKER_ANY);
+    ix.data.extend_from_slice(&[0u8; 32]);
+    ix.data.push(auction_mode);
+    ix.data.extend_from_slice(&end_receive.to_le_bytes());
+    ix.data.extend_from_slice(&AUCTION_START.to_le_bytes());
+    ix.data.extend_from_slice(&AUCTION_END.to_le_bytes());
+    ix
+}
+
+/// Take 数据：amount(8) + max_pay(8)，不限定 sequence
+fn auction_take_ix(fixture: &Fixture, amount: u64, max_pay: u64) -> Instruction {
+    fixture.take_ix_with(amount, None, Some(max_pay), &[])
+}
+
+fn make_auction(fixture: &mut Fixture, auction_mode: u8) {
+    let maker = fixture.maker.insecure_clone();
+    let result = fixture.send(auction_make_ix(fixture, auction_mode, END_RECEIVE), &maker);
+    assert!(result.is_ok(), "Make 应成功: {:?}", result);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_linear_auction_price_at_midpoint() {
+    let mut fixture = setup();
+    make_auction(&mut fixture, Escrow::AUCTION_LINEAR);
+
+    // 拍卖过半：价格为起止价的中点
+    fixture.set_clock((AUCTION_START + AUCTION_END) / 2);
+    let price = (RECEIVE_AMOUNT + END_RECEIVE) / 2;
+    let taker = fixture.taker.insecure_clone();
+    let result = fixture.send(auction_take_ix(&fixture, OFFER_AMOUNT, price), &taker);
+    assert!(result.is_ok(), "Take 应成功: {:?}", result);
+
+    assert_eq!(fixture.token_balance(&fixture.maker_ata_b), price);
+    assert_eq!(fixture.token_balance(&fixture.taker_ata_a), OFFER_AMOUNT);
+    assert!(fixture.is_closed(&fixture.escrow));
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_exponential_auction_price() {
+    let mut fixture = setup();
+    make_auction(&mut fixture, Escrow::AUCTION_EXPONENTIAL);
+    let taker = fixture.taker.insecure_clone();
+
+    // 1/8 处距离底价减半，再减去让曲线在结束时落到底价的线性尾项
+    fixture.set_clock(AUCTION_START + 125);
+    let span = RECEIVE_AMOUNT - END_RECEIVE;
+    let price = END_RECEIVE + span / 2 - (span >> 8) * 125 / 1_000;
+    let result = fixture.send(auction_take_ix(&fixture, OFFER_AMOUNT, price - 1), &taker);
+    assert_custom_error(result, PRICE_EXCEEDED);
+
+    // 拍卖结束后按底价成交
+    fixture.set_clock(AUCTION_END + 10);
+    let result = fixture.send(auction_take_ix(&fixture, OFFER_AMOUNT, END_RECEIVE), &taker);
+    assert!(result.is_ok(), "Take 应成功: {:?}", result);
+    assert_eq!(fixture.token_balance(&fixture.maker_ata_b), END_RECEIVE);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_auction_take_fail_price_exceeded() {
+    let mut fixture = setup();
+    make_auction(&mut fixture, Escrow::AUCTION_LINEAR);
+
+    // 拍卖尚未开始衰减，价格仍为起始价
+    let taker = fixture.taker.insecure_clone();
+    let result = fixture.send(
+        auction_take_ix(&fixture, OFFER_AMOUNT, RECEIVE_AMOUNT - 1),
+        &taker,
+    );
+    assert_custom_error(result, PRICE_EXCEEDED);
+    assert_eq!(fixture.token_balance(&fixture.vault), OFFER_AMOUNT);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_auction_take_fail_without_max_price() {
+    let mut fixture = setup();
+    make_auction(&mut fixture, Escrow::AUCTION_LINEAR);
+
+    let taker = fixture.taker.insecure_clone();
+    let result = fixture.send(fixture.take_ix(), &taker);
+    assert_instruction_error(result, InstructionError::InvalidInstructionData);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_auction_partial_fill_scales_curve() {
+    let mut fixture = setup();
+    make_auction(&mut fixture, Escrow::AUCTION_LINEAR);
+
+    let taker = fixture.taker.insecure_clone();
+    let result = fixture.send(
+        auction_take_ix(&fixture, OFFER_AMOUNT / 2, RECEIVE_AMOUNT / 2),
+        &taker,
+    );
+    assert!(result.is_ok(), "部分成交应成功: {:?}", result);
+
+    let escrow = fixture.account(&fixture.escrow).expect("escrow 应保留");
+    let data = escrow.data();
+    assert_eq!(read_u64(data, ESCROW_REMAINING_OFFSET), OFFER_AMOUNT / 2);
+    assert_eq!(read_u64(data, ESCROW_RECEIVE_OFFSET), RECEIVE_AMOUNT / 2);
+    assert_eq!(read_u64(data, ESCROW_END_RECEIVE_OFFSET), END_RECEIVE / 2);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_auction_make_fail_invalid_curve() {
+    let mut fixture = setup();
+    let maker = fixture.maker.insecure_clone();
+
+    // 底价不低于起始价
+    let ix = auction_make_ix(&fixture, Escrow::AUCTION_LINEAR, RECEIVE_AMOUNT);
+    assert_custom_error(fixture.send(ix, &maker), INVALID_AUCTION);
+
+    // 拍卖已结束
+    fixture.set_clock(AUCTION_END);
+    let ix = auction_make_ix(&fixture, Escrow::AUCTION_LINEAR, END_RECEIVE);
+    assert_custom_error(fixture.send(ix, &maker), INVALID_AUCTION);
+}
@@ -0,0 +1,131 @@
+//! Escrow 账户布局、部分成交定价（receive_for / record_fill）与 taker 限制（is_allowed_taker），
+//! 在本机直接调用，不依赖程序二进制
+
+mod common;
+
+use core::mem::{offset_of, size_of};
+
+use common::*;
+use pinocchio::error::ProgramError;
+use pinocchio_escrow::Escrow;
+use solana_pubkey::Pubkey;
+
+const INVALID_AMOUNT: ProgramError = ProgramError::Custom(6000);
+
+/// 链上账户数据按 8 字节对齐，测试缓冲区保持一致
+#[repr(C, align(8))]
+struct EscrowData([u8; Escrow::LEN]);
+
+fn with_escrow<R>(receive: u64, remaining: u64, f: impl FnOnce(&mut Escrow) -> R) -> R {
+    let mut data = EscrowData([0; Escrow::LEN]);
+    let escrow = Escrow::init(&mut data.0).unwrap();
+    escrow.set_receive(receive);
+    escrow.set_remaining(remaining);
+    f(escrow)
+}
+
+#[test]
+fn test_escrow_layout_matches_offsets() {
+    assert_eq!(size_of::<Escrow>(), Escrow::LEN);
+    assert_eq!(
+        offset_of!(Escrow, discriminator),
+        ESCROW_DISCRIMINATOR_OFFSET
+    );
+    assert_eq!(offset_of!(Escrow, seed), ESCROW_SEED_OFFSET);
+    assert_eq!(offset_of!(Escrow, maker), ESCROW_MAKER_OFFSET);
+    assert_eq!(offset_of!(Escrow, mint_a), ESCROW_MINT_A_OFFSET);
+    assert_eq!(offset_of!(Escrow, mint_b), ESCROW_MINT_B_OFFSET);
+    assert_eq!(offset_of!(Escrow, receive), ESCROW_RECEIVE_OFFSET);
+    assert_eq!(offset_of!(Escrow, remaining), ESCROW_REMAINING_OFFSET);
+    assert_eq!(offset_of!(Escrow, sequence), ESCROW_SEQUENCE_OFFSET);
+    assert_eq!(offset_of!(Escrow, end_receive), ESCROW_END_RECEIVE_OFFSET);
+    assert_eq!(offset_of!(Escrow, rent_payer), ESCROW_RENT_PAYER_OFFSET);
+}
+
+#[test]
+fn test_receive_for_rounds_up() {
+    with_escrow(RECEIVE_AMOUNT, OFFER_AMOUNT, |escrow| {
+        // 250_000 * 1 / 100_000 = 2.5 -> 3
+        assert_eq!(escrow.receive_for(1, 0), Ok(3));
+        // 250_000 * 3 / 100_000 = 7.5 -> 8
+        assert_eq!(escrow.receive_for(3, 0), Ok(8));
+        // 整除时不多收
+        assert_eq!(
+            escrow.receive_for(OFFER_AMOUNT * 2 / 5, 0),
+            Ok(RECEIVE_AMOUNT * 2 / 5)
+        );
+        // 全部成交按剩余报价
+        assert_eq!(escrow.receive_for(OFFER_AMOUNT, 0), Ok(RECEIVE_AMOUNT));
+    });
+}
+
+#[test]
+fn test_receive_for_rejects_invalid_amounts() {
+    with_escrow(RECEIVE_AMOUNT, OFFER_AMOUNT, |escrow| {
+        assert_eq!(escrow.receive_for(0, 0), Err(INVALID_AMOUNT));
+        assert_eq!(escrow.receive_for(OFFER_AMOUNT + 1, 0), Err(INVALID_AMOUNT));
+    });
+
+    // 向上取整后部分成交要付全价，剩余部分会变成零价，拒绝
+    with_escrow(1, 10, |escrow| {
+        assert_eq!(escrow.receive_for(1, 0), Err(INVALID_AMOUNT));
+        assert_eq!(escrow.receive_for(10, 0), Ok(1));
+    });
+}
+
+#[test]
+fn test_record_fill_keeps_maker_whole() {
+    with_escrow(RECEIVE_AMOUNT, OFFER_AMOUNT, |escrow| {
+        // 逐个 token 成交：每次从剩余报价中向上取整扣除，最后一笔付清余额，maker 恰好收到原报价
+        let mut paid = 0;
+        while escrow.remaining > 0 {
+            let pay = escrow.receive_for(1, 0).unwrap();
+            escrow.record_fill(1, pay).unwrap();
+            paid += pay;
+            if escrow.remaining > 0 {
+                assert!(escrow.receive > 0, "剩余部分不能零价");
+            }
+        }
+        assert_eq!(paid, RECEIVE_AMOUNT);
+        assert_eq!(escrow.receive, 0);
+    });
+}
+
+#[test]
+fn test_designated_taker() {
+    let taker = Pubkey::new_unique();
+    with_escrow(RECEIVE_AMOUNT, OFFER_AMOUNT, |escrow| {
+        assert!(escrow.is_allowed_taker(&taker, &[]));
+
+        escrow.set_taker(Escrow::TAKER_ADDRESS, taker);
+        assert!(escrow.is_allowed_taker(&taker, &[]));
+        assert!(!escrow.is_allowed_taker(&Pubkey::new_unique(), &[]));
+
+        // 未知模式一律拒绝
+        escrow.set_taker(3, taker);
+        assert!(!escrow.is_allowed_taker(&taker, &[]));
+    });
+}
+
+#[test]
+fn test_merkle_taker_proof() {
+    let takers: Vec<_> = (0..8).map(|_| Pubkey::new_unique()).collect();
+    with_escrow(RECEIVE_AMOUNT, OFFER_AMOUNT, |escrow| {
+        for (index, taker) in takers.iter().enumerate() {
+            let (root, proof) = merkle_root_and_proof(&takers, index);
+            escrow.set_taker(Escrow::TAKER_MERKLE, root);
+            assert!(escrow.is_allowed_taker(taker, &proof), "index {index}");
+
+            // 篡改任一层、缺层、多出半截或换人都不能通过
+            for byte in [0, 33, 95] {
+                let mut tampered = proof.clone();
+                tampered[byte] ^= 1;
+                assert!(!escrow.is_allowed_taker(taker, &tampered));
+            }
+            assert!(!escrow.is_allowed_taker(taker, &proof[..64]));
+            assert!(!escrow.is_allowed_taker(taker, &proof[..80]));
+            assert!(!escrow.is_allowed_taker(taker, &[]));
+            assert!(!escrow.is_allowed_taker(&Pubkey::new_unique(), &proof));
+        }
+    });
+}
@@ -0,0 +1,147 @@
+//! escrow 过期：过期后不能 Take，任何人可用 RefundExpired 把 token 与租金退回 maker
+//!
+//! 运行前请先在 season1/ 构建程序：`cargo build-programs`，测试默认 ignored，用 `cargo test-programs` 运行。
+
+mod common;
+
+use common::*;
+use solana_account::ReadableAccount;
+use solana_address::Address;
+use solana_instruction::Instruction;
+use solana_keypair::Keypair;
+use solana_signer::Signer;
+
+const INVALID_EXPIRY: u32 = 6004;
+const ESCROW_EXPIRED: u32 = 6005;
+const ESCROW_NOT_EXPIRED: u32 = 6006;
+/// 每个签名的交易费
+const SIGNATURE_FEE: u64 = 5_000;
+
+const NOW: i64 = 1_000;
+const EXPIRY: i64 = 2_000;
+
+fn setup() -> Fixture {
+    let mut fixture = Fixture::new(escrow_svm());
+    fixture.set_clock(NOW);
+    fixture
+}
+
+/// Make 数据追加 expiry(8)
+fn expiring_make_ix(fixture: &Fixture, expiry: i64) -> Instruction {
+    let mut ix = fixture.make_ix(RECEIVE_AMOUNT, OFFER_AMOUNT);
+    ix.data.extend_from_slice(&expiry.to_le_bytes());
+    ix
+}
+
+fn make_expiring(fixture: &mut Fixture) {
+    let maker = fixture.maker.insecure_clone();
+    let result = fixture.send(expiring_make_ix(fixture, EXPIRY), &maker);
+    assert!(result.is_ok(), "Make 应成功: {:?}", result);
+}
+
+fn lamports(fixture: &Fixture, address: &Address) -> u64 {
+    fixture
+        .account(address)
+        .map_or(0, |account| account.lamports())
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_make_fail_invalid_expiry() {
+    let mut fixture = setup();
+    let maker = fixture.maker.insecure_clone();
+
+    // 负数、当前时刻与过去的时间都不是有效的过期时间
+    for expiry in [-1, NOW, NOW - 1] {
+        let result = fixture.send(expiring_make_ix(&fixture, expiry), &maker);
+        assert_custom_error(result, INVALID_EXPIRY);
+    }
+    assert!(fixture.is_closed(&fixture.escrow), "escrow 不应被创建");
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_take_until_expiry() {
+    let mut fixture = setup();
+    make_expiring(&mut fixture);
+
+    // 到期时刻本身仍可成交
+    fixture.set_clock(EXPIRY);
+    let taker = fixture.taker.insecure_clone();
+    let result = fixture.send(fixture.take_ix(), &taker);
+    assert!(result.is_ok(), "Take 应成功: {:?}", result);
+    assert_eq!(fixture.token_balance(&fixture.taker_ata_a), OFFER_AMOUNT);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_take_fail_after_expiry() {
+    let mut fixture = setup();
+    make_expiring(&mut fixture);
+
+    fixture.set_clock(EXPIRY + 1);
+    let taker = fixture.taker.insecure_clone();
+    let result = fixture.send(fixture.take_ix(), &taker);
+    assert_custom_error(result, ESCROW_EXPIRED);
+    assert_eq!(fixture.token_balance(&fixture.vault), OFFER_AMOUNT);
+    assert_eq!(fixture.token_balance(&fixture.maker_ata_b), 0);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_refund_expired_fail_before_expiry() {
+    let mut fixture = setup();
+    make_expiring(&mut fixture);
+
+    fixture.set_clock(EXPIRY);
+    let taker = fixture.taker.insecure_clone();
+    let result = fixture.send(fixture.refund_expired_ix(&taker.pubkey()), &taker);
+    assert_custom_error(result, ESCROW_NOT_EXPIRED);
+    assert_eq!(fixture.token_balance(&fixture.vault), OFFER_AMOUNT);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_refund_expired_fail_without_expiry() {
+    let mut fixture = setup();
+    fixture.make();
+
+    // expiry = 0 的 escrow 永不过期
+    fixture.set_clock(i64::MAX);
+    let taker = fixture.taker.insecure_clone();
+    let result = fixture.send(fixture.refund_expired_ix(&taker.pubkey()), &taker);
+    assert_custom_error(result, ESCROW_NOT_EXPIRED);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_refund_expired_by_third_party() {
+    let mut fixture = setup();
+    make_expiring(&mut fixture);
+
+    let cranker = Keypair::new();
+    fixture
+        .svm
+        .airdrop(&cranker.pubkey(), 1_000_000_000)
+        .unwrap();
+    let maker_before = lamports(&fixture, &fixture.maker.pubkey());
+    let cranker_before = lamports(&fixture, &cranker.pubkey());
+    let rent = lamports(&fixture, &fixture.escrow) + lamports(&fixture, &fixture.vault);
+
+    fixture.set_clock(EXPIRY + 1);
+    let result = fixture.send(fixture.refund_expired_ix(&cranker.pubkey()), &cranker);
+    assert!(result.is_ok(), "RefundExpired 应成功: {:?}", result);
+
+    // token 与两个账户的租金都回到 maker，调用方只付交易费
+    assert_eq!(fixture.token_balance(&fixture.maker_ata_a), INITIAL_BALANCE);
+    assert!(fixture.is_closed(&fixture.escrow));
+    assert!(fixture.is_closed(&fixture.vault));
+    assert_eq!(
+        lamports(&fixture, &fixture.maker.pubkey()),
+        maker_before + rent
+    );
+    assert_eq!(
+        lamports(&fixture, &cranker.pubkey()),
+        cranker_before - SIGNATURE_FEE
+    );
+}
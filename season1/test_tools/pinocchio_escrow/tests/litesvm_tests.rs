@@ -0,0 +1,246 @@
+//! LiteSVM 集成测试：pinocchio_escrow 的 Make / Take / Refund 正常流程与失败场景
+//!
+//! 运行前请先在 season1/ 构建程序：`cargo build-programs`（输出到 target/deploy），
+//! 测试默认 ignored，用 `cargo test-programs` 运行。
+
+mod common;
+
+use common::*;
+use solana_account::ReadableAccount;
+use solana_instruction::error::InstructionError;
+use solana_keypair::Keypair;
+use solana_signer::Signer;
+
+/// EscrowError 错误码
+const INVALID_AMOUNT: u32 = 6000;
+const INVALID_MINT_B: u32 = 6003;
+/// SPL Token InsufficientFunds
+const TOKEN_INSUFFICIENT_FUNDS: u32 = 1;
+
+fn setup() -> Fixture {
+    Fixture::new(escrow_svm())
+}
+
+// ========== 测试：Make ==========
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_make_ok() {
+    let mut fixture = setup();
+
+    fixture.make();
+
+    let escrow = fixture.account(&fixture.escrow).expect("escrow 应存在");
+    let data = escrow.data();
+    assert_eq!(read_u64(data, ESCROW_SEED_OFFSET), fixture.seed);
+    assert_eq!(read_address(data, ESCROW_MAKER_OFFSET), fixture.maker.pubkey());
+    assert_eq!(read_address(data, ESCROW_MINT_A_OFFSET), fixture.mint_a);
+    assert_eq!(read_address(data, ESCROW_MINT_B_OFFSET), fixture.mint_b);
+    assert_eq!(read_u64(data, ESCROW_RECEIVE_OFFSET), RECEIVE_AMOUNT);
+    assert_eq!(read_u64(data, ESCROW_REMAINING_OFFSET), OFFER_AMOUNT);
+
+    assert_eq!(fixture.token_balance(&fixture.vault), OFFER_AMOUNT);
+    assert_eq!(
+        fixture.token_balance(&fixture.maker_ata_a),
+        INITIAL_BALANCE - OFFER_AMOUNT
+    );
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_make_fail_zero_amount() {
+    let mut fixture = setup();
+    let maker = fixture.maker.insecure_clone();
+
+    let result = fixture.send(fixture.make_ix(RECEIVE_AMOUNT, 0), &maker);
+    assert_custom_error(result, INVALID_AMOUNT);
+
+    let result = fixture.send(fixture.make_ix(0, OFFER_AMOUNT), &maker);
+    assert_custom_error(result, INVALID_AMOUNT);
+    assert!(fixture.is_closed(&fixture.escrow), "escrow 不应被创建");
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_make_fail_insufficient_balance() {
+    let mut fixture = setup();
+    let maker = fixture.maker.insecure_clone();
+
+    let result = fixture.send(fixture.make_ix(RECEIVE_AMOUNT, INITIAL_BALANCE + 1), &maker);
+    assert_custom_error(result, TOKEN_INSUFFICIENT_FUNDS);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_make_fail_wrong_escrow_address() {
+    let mut fixture = setup();
+    let maker = fixture.maker.insecure_clone();
+
+    // escrow 用了另一个 seed 的 PDA
+    let mut ix = fixture.make_ix(RECEIVE_AMOUNT, OFFER_AMOUNT);
+    ix.accounts[1].pubkey =
+        find_escrow_pda(&fixture.program_id, &fixture.maker.pubkey(), fixture.seed + 1).0;
+    let result = fixture.send(ix, &maker);
+    assert_instruction_error(result, InstructionError::InvalidArgument);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_make_fail_missing_signature() {
+    let mut fixture = setup();
+
+    // 由其他账户付费，maker 不签名
+    let payer = Keypair::new();
+    fixture.svm.airdrop(&payer.pubkey(), 1_000_000_000).unwrap();
+    let mut ix = fixture.make_ix(RECEIVE_AMOUNT, OFFER_AMOUNT);
+    ix.accounts[0].is_signer = false;
+    let result = fixture.send(ix, &payer);
+    assert_instruction_error(result, InstructionError::MissingRequiredSignature);
+}
+
+// ========== 测试：Take ==========
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_take_ok() {
+    let mut fixture = setup();
+    fixture.make();
+
+    let taker = fixture.taker.insecure_clone();
+    let result = fixture.send(fixture.take_ix(), &taker);
+    assert!(result.is_ok(), "Take 应成功: {:?}", result);
+
+    assert_eq!(fixture.token_balance(&fixture.taker_ata_a), OFFER_AMOUNT);
+    assert_eq!(fixture.token_balance(&fixture.maker_ata_b), RECEIVE_AMOUNT);
+    assert_eq!(
+        fixture.token_balance(&fixture.taker_ata_b),
+        INITIAL_BALANCE - RECEIVE_AMOUNT
+    );
+    assert!(fixture.is_closed(&fixture.escrow));
+    assert!(fixture.is_closed(&fixture.vault));
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_take_partial_fill() {
+    let mut fixture = setup();
+    fixture.make();
+    let taker = fixture.taker.insecure_clone();
+
+    // 先拿 40%，按比例向上取整支付 token B
+    let take_amount = OFFER_AMOUNT * 2 / 5;
+    let pay_amount = RECEIVE_AMOUNT * 2 / 5;
+    let mut ix = fixture.take_ix();
+    ix.data.extend_from_slice(&take_amount.to_le_bytes());
+    let result = fixture.send(ix, &taker);
+    assert!(result.is_ok(), "部分成交应成功: {:?}", result);
+
+    assert_eq!(fixture.token_balance(&fixture.taker_ata_a), take_amount);
+    assert_eq!(fixture.token_balance(&fixture.maker_ata_b), pay_amount);
+    let escrow = fixture.account(&fixture.escrow).expect("escrow 应保留");
+    assert_eq!(
+        read_u64(escrow.data(), ESCROW_REMAINING_OFFSET),
+        OFFER_AMOUNT - take_amount
+    );
+    assert_eq!(
+        read_u64(escrow.data(), ESCROW_RECEIVE_OFFSET),
+        RECEIVE_AMOUNT - pay_amount
+    );
+
+    // 再全部成交剩余部分
+    let result = fixture.send(fixture.take_ix(), &taker);
+    assert!(result.is_ok(), "剩余成交应成功: {:?}", result);
+    assert_eq!(fixture.token_balance(&fixture.taker_ata_a), OFFER_AMOUNT);
+    assert_eq!(fixture.token_balance(&fixture.maker_ata_b), RECEIVE_AMOUNT);
+    assert!(fixture.is_closed(&fixture.escrow));
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_take_fail_insufficient_balance() {
+    let mut fixture = setup();
+    fixture.make();
+
+    // taker 的 token B 不足以支付 receive
+    fixture
+        .svm
+        .set_account(
+            fixture.taker_ata_b,
+            test_support::token_account(&fixture.mint_b, &fixture.taker.pubkey(), RECEIVE_AMOUNT - 1),
+        )
+        .unwrap();
+    let taker = fixture.taker.insecure_clone();
+    let result = fixture.send(fixture.take_ix(), &taker);
+    assert_custom_error(result, TOKEN_INSUFFICIENT_FUNDS);
+    assert_eq!(fixture.token_balance(&fixture.vault), OFFER_AMOUNT);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_take_fail_wrong_mint_b() {
+    let mut fixture = setup();
+    fixture.make();
+
+    // 用另一个 mint 冒充 mint_b
+    let other_mint = solana_pubkey::Pubkey::new_unique();
+    fixture
+        .svm
+        .set_account(other_mint, test_support::mint_account(MINT_DECIMALS, 0))
+        .unwrap();
+    let mut ix = fixture.take_ix();
+    ix.accounts[4].pubkey = other_mint;
+    let taker = fixture.taker.insecure_clone();
+    let result = fixture.send(ix, &taker);
+    assert_custom_error(result, INVALID_MINT_B);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_take_fail_after_full_fill() {
+    let mut fixture = setup();
+    fixture.make();
+    let taker = fixture.taker.insecure_clone();
+
+    let result = fixture.send(fixture.take_ix(), &taker);
+    assert!(result.is_ok(), "Take 应成功: {:?}", result);
+    let result = fixture.send(fixture.take_ix(), &taker);
+    assert!(result.is_err(), "escrow 已关闭，第二次 Take 应失败");
+}
+
+// ========== 测试：Refund ==========
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_refund_ok() {
+    let mut fixture = setup();
+    let maker_lamports = fixture.account(&fixture.maker.pubkey()).unwrap().lamports();
+    fixture.make();
+
+    let maker = fixture.maker.insecure_clone();
+    let result = fixture.send(fixture.refund_ix(), &maker);
+    assert!(result.is_ok(), "Refund 应成功: {:?}", result);
+
+    assert_eq!(fixture.token_balance(&fixture.maker_ata_a), INITIAL_BALANCE);
+    assert!(fixture.is_closed(&fixture.escrow));
+    assert!(fixture.is_closed(&fixture.vault));
+    // escrow 与 vault 的租金全部退回，maker 只损失两笔交易费
+    assert_eq!(
+        fixture.account(&fixture.maker.pubkey()).unwrap().lamports(),
+        maker_lamports - 2 * 5_000
+    );
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_refund_fail_after_take() {
+    let mut fixture = setup();
+    fixture.make();
+
+    let taker = fixture.taker.insecure_clone();
+    let result = fixture.send(fixture.take_ix(), &taker);
+    assert!(result.is_ok(), "Take 应成功: {:?}", result);
+
+    let maker = fixture.maker.insecure_clone();
+    let result = fixture.send(fixture.refund_ix(), &maker);
+    assert!(result.is_err(), "已成交的 escrow 不能 Refund");
+}
@@ -0,0 +1,189 @@
+//! 订单簿分页：Make 写入 (mint_a, mint_b) 的 book 页，Take / Refund 关闭 escrow 时移除
+//!
+//! 运行前请先在 season1/ 构建程序：`cargo build-programs`，测试默认 ignored，用 `cargo test-programs` 运行。
+
+mod common;
+
+use common::*;
+use pinocchio_escrow::BookPage;
+use solana_account::ReadableAccount;
+use solana_address::Address;
+use solana_instruction::error::InstructionError;
+use solana_instruction::{AccountMeta, Instruction};
+use solana_signer::Signer;
+use test_support::{associated_token_address, token_program_id};
+
+/// BookPage 布局（见 pinocchio_escrow::state::BookPage）
+const BOOK_COUNT_OFFSET: usize = 8;
+const BOOK_CAPACITY_OFFSET: usize = 12;
+const BOOK_MINT_A_OFFSET: usize = 16;
+const BOOK_MINT_B_OFFSET: usize = 48;
+const BOOK_ENTRY_LEN: usize = 64;
+
+const PAGE: u32 = 0;
+
+fn setup() -> Fixture {
+    Fixture::new(escrow_svm())
+}
+
+fn find_book_pda(fixture: &Fixture, page: u32) -> Address {
+    Address::find_program_address(
+        &[
+            b"book",
+            fixture.mint_a.as_ref(),
+            fixture.mint_b.as_ref(),
+            &page.to_le_bytes(),
+        ],
+        &fixture.program_id,
+    )
+    .0
+}
+
+/// 切换到 maker 的另一个 seed，对应新的 escrow 与 vault
+fn use_seed(fixture: &mut Fixture, seed: u64) {
+    fixture.seed = seed;
+    fixture.escrow = find_escrow_pda(&fixture.program_id, &fixture.maker.pubkey(), seed).0;
+    fixture.vault = associated_token_address(&fixture.escrow, &fixture.mint_a);
+}
+
+/// Make 数据补齐 expiry / taker / auction 默认值后追加 page(4)，并追加 rent_payer（maker 自己）与 book 页账户
+fn book_make_ix(fixture: &Fixture, page: u32) -> Instruction {
+    let mut ix = fixture.make_ix(RECEIVE_AMOUNT, OFFER_AMOUNT / 10);
+    ix.data.extend_from_slice(&[0u8; 8 + 1 + 32 + 1 + 8 + 8 + 8]);
+    ix.data.extend_from_slice(&page.to_le_bytes());
+    ix.accounts
+        .push(AccountMeta::new(fixture.maker.pubkey(), true));
+    ix.accounts
+        .push(AccountMeta::new(find_book_pda(fixture, page), false));
+    ix
+}
+
+/// Take 追加 token_program_b 与 book 页
+fn book_take_ix(fixture: &Fixture) -> Instruction {
+    let mut ix = fixture.take_ix();
+    ix.accounts
+        .push(AccountMeta::new_readonly(token_program_id(), false));
+    ix.accounts
+        .push(AccountMeta::new(find_book_pda(fixture, PAGE), false));
+    ix
+}
+
+fn book_refund_ix(fixture: &Fixture) -> Instruction {
+    let mut ix = fixture.refund_ix();
+    ix.accounts
+        .push(AccountMeta::new(find_book_pda(fixture, PAGE), false));
+    ix
+}
+
+fn make_listed(fixture: &mut Fixture, seed: u64) {
+    use_seed(fixture, seed);
+    let maker = fixture.maker.insecure_clone();
+    let result = fixture.send(book_make_ix(fixture, PAGE), &maker);
+    assert!(result.is_ok(), "Make 应成功: {:?}", result);
+}
+
+/// 页中登记的 escrow 地址（按存储顺序）
+fn listed_escrows(fixture: &Fixture) -> Vec<Address> {
+    let account = fixture
+        .account(&find_book_pda(fixture, PAGE))
+        .expect("book 页应存在");
+    let data = account.data();
+    let count = u32::from_le_bytes(data[BOOK_COUNT_OFFSET..BOOK_COUNT_OFFSET + 4].try_into().unwrap());
+    (0..count as usize)
+        .map(|i| read_address(data, BookPage::HEADER_LEN + i * BOOK_ENTRY_LEN))
+        .collect()
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_make_lists_escrow() {
+    let mut fixture = setup();
+    make_listed(&mut fixture, 1);
+
+    let account = fixture.account(&find_book_pda(&fixture, PAGE)).unwrap();
+    let data = account.data();
+    assert_eq!(account.owner, fixture.program_id);
+    assert_eq!(read_address(data, BOOK_MINT_A_OFFSET), fixture.mint_a);
+    assert_eq!(read_address(data, BOOK_MINT_B_OFFSET), fixture.mint_b);
+    assert_eq!(listed_escrows(&fixture), vec![fixture.escrow]);
+    // entry 中紧跟 escrow 的是 maker
+    assert_eq!(
+        read_address(data, BookPage::HEADER_LEN + 32),
+        fixture.maker.pubkey()
+    );
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_book_page_grows_by_realloc() {
+    let mut fixture = setup();
+    let listed = BookPage::INITIAL_CAPACITY as u64 + 1;
+    for seed in 0..listed {
+        make_listed(&mut fixture, seed);
+    }
+
+    let account = fixture.account(&find_book_pda(&fixture, PAGE)).unwrap();
+    let capacity = BookPage::INITIAL_CAPACITY + BookPage::GROWTH;
+    assert_eq!(account.data().len(), BookPage::space(capacity));
+    assert_eq!(
+        u32::from_le_bytes(
+            account.data()[BOOK_CAPACITY_OFFSET..BOOK_CAPACITY_OFFSET + 4]
+                .try_into()
+                .unwrap()
+        ),
+        capacity
+    );
+    assert_eq!(listed_escrows(&fixture).len(), listed as usize);
+    // 扩容后仍保持租金豁免
+    assert!(account.lamports >= fixture.svm.minimum_balance_for_rent_exemption(account.data().len()));
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_take_removes_escrow() {
+    let mut fixture = setup();
+    let mut escrows = Vec::new();
+    for seed in 1..=3 {
+        make_listed(&mut fixture, seed);
+        escrows.push(fixture.escrow);
+    }
+
+    // 成交第一个：最后一个 entry 被交换到它的位置
+    use_seed(&mut fixture, 1);
+    let taker = fixture.taker.insecure_clone();
+    let result = fixture.send(book_take_ix(&fixture), &taker);
+    assert!(result.is_ok(), "Take 应成功: {:?}", result);
+    assert!(fixture.is_closed(&fixture.escrow));
+    assert_eq!(listed_escrows(&fixture), vec![escrows[2], escrows[1]]);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_refund_removes_escrow() {
+    let mut fixture = setup();
+    make_listed(&mut fixture, 1);
+    let maker = fixture.maker.insecure_clone();
+
+    // 已登记的 escrow 必须带上 book 页（程序返回的 NotEnoughAccountKeys 仍映射到这个已弃用的变体）
+    let result = fixture.send(fixture.refund_ix(), &maker);
+    #[allow(deprecated)]
+    assert_instruction_error(result, InstructionError::NotEnoughAccountKeys);
+
+    let result = fixture.send(book_refund_ix(&fixture), &maker);
+    assert!(result.is_ok(), "Refund 应成功: {:?}", result);
+    assert!(listed_escrows(&fixture).is_empty());
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_make_fail_wrong_book_page() {
+    let mut fixture = setup();
+    let maker = fixture.maker.insecure_clone();
+
+    // 数据中是第 1 页，账户却是第 0 页的 PDA
+    let mut ix = book_make_ix(&fixture, 1);
+    let last = ix.accounts.len() - 1;
+    ix.accounts[last].pubkey = find_book_pda(&fixture, PAGE);
+    let result = fixture.send(ix, &maker);
+    assert_instruction_error(result, InstructionError::InvalidSeeds);
+}
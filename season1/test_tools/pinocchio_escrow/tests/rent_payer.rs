@@ -0,0 +1,133 @@
+//! 代付租金：Make 由独立的 rent_payer 支付 escrow 与 vault 租金，关闭时租金退回给它
+//!
+//! 运行前请先在 season1/ 构建程序：`cargo build-programs`，测试默认 ignored，用 `cargo test-programs` 运行。
+
+mod common;
+
+use common::*;
+use solana_account::ReadableAccount;
+use solana_instruction::error::InstructionError;
+use solana_instruction::{AccountMeta, Instruction};
+use solana_keypair::Keypair;
+use solana_signer::Signer;
+use test_support::token_program_id;
+
+const INVALID_RENT_PAYER: u32 = 6015;
+/// 每个签名的交易费
+const SIGNATURE_FEE: u64 = 5_000;
+
+/// 代付方（relayer）付交易费并签名，maker 只签名授权转出 token A
+fn setup() -> (Fixture, Keypair) {
+    let mut fixture = Fixture::new(escrow_svm());
+    let sponsor = Keypair::new();
+    fixture.svm.airdrop(&sponsor.pubkey(), 10_000_000_000).unwrap();
+    (fixture, sponsor)
+}
+
+fn lamports(fixture: &Fixture, keypair: &Keypair) -> u64 {
+    fixture.account(&keypair.pubkey()).unwrap().lamports()
+}
+
+/// Make 末尾追加 rent_payer 账户
+fn sponsored_make_ix(fixture: &Fixture, sponsor: &Keypair) -> Instruction {
+    let mut ix = fixture.make_ix(RECEIVE_AMOUNT, OFFER_AMOUNT);
+    ix.accounts.push(AccountMeta::new(sponsor.pubkey(), true));
+    ix
+}
+
+fn make_sponsored(fixture: &mut Fixture, sponsor: &Keypair) {
+    let maker = fixture.maker.insecure_clone();
+    let ix = sponsored_make_ix(fixture, sponsor);
+    let result = fixture.send_with(ix, sponsor, &[&maker]);
+    assert!(result.is_ok(), "Make 应成功: {:?}", result);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_sponsored_make_charges_rent_payer() {
+    let (mut fixture, sponsor) = setup();
+    let maker_before = lamports(&fixture, &fixture.maker);
+    make_sponsored(&mut fixture, &sponsor);
+
+    // maker 不承担任何 lamports
+    assert_eq!(lamports(&fixture, &fixture.maker), maker_before);
+    let escrow = fixture.account(&fixture.escrow).unwrap();
+    assert_eq!(
+        read_address(escrow.data(), ESCROW_RENT_PAYER_OFFSET),
+        sponsor.pubkey()
+    );
+    assert_eq!(fixture.token_balance(&fixture.vault), OFFER_AMOUNT);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_refund_returns_rent_to_rent_payer() {
+    let (mut fixture, sponsor) = setup();
+    let sponsor_before = lamports(&fixture, &sponsor);
+    make_sponsored(&mut fixture, &sponsor);
+
+    let maker = fixture.maker.insecure_clone();
+    let maker_before = lamports(&fixture, &maker);
+    let mut ix = fixture.refund_ix();
+    ix.accounts.push(AccountMeta::new(sponsor.pubkey(), false));
+    let result = fixture.send(ix, &maker);
+    assert!(result.is_ok(), "Refund 应成功: {:?}", result);
+
+    assert!(fixture.is_closed(&fixture.escrow));
+    assert!(fixture.is_closed(&fixture.vault));
+    // 代付方只损失 Make 的两个签名费，maker 只付 Refund 的交易费
+    assert_eq!(lamports(&fixture, &sponsor), sponsor_before - 2 * SIGNATURE_FEE);
+    assert_eq!(lamports(&fixture, &maker), maker_before - SIGNATURE_FEE);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_take_returns_rent_to_rent_payer() {
+    let (mut fixture, sponsor) = setup();
+    let sponsor_before = lamports(&fixture, &sponsor);
+    make_sponsored(&mut fixture, &sponsor);
+
+    let mut ix = fixture.take_ix();
+    ix.accounts
+        .push(AccountMeta::new_readonly(token_program_id(), false));
+    ix.accounts.push(AccountMeta::new(sponsor.pubkey(), false));
+    let taker = fixture.taker.insecure_clone();
+    let result = fixture.send(ix, &taker);
+    assert!(result.is_ok(), "Take 应成功: {:?}", result);
+
+    assert!(fixture.is_closed(&fixture.escrow));
+    assert_eq!(lamports(&fixture, &sponsor), sponsor_before - 2 * SIGNATURE_FEE);
+    assert_eq!(fixture.token_balance(&fixture.maker_ata_b), RECEIVE_AMOUNT);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_refund_fail_wrong_rent_payer() {
+    let (mut fixture, sponsor) = setup();
+    make_sponsored(&mut fixture, &sponsor);
+    let maker = fixture.maker.insecure_clone();
+
+    // 不带 rent_payer 账户
+    let result = fixture.send(fixture.refund_ix(), &maker);
+    #[allow(deprecated)]
+    assert_instruction_error(result, InstructionError::NotEnoughAccountKeys);
+
+    // maker 冒充 rent_payer 取走租金
+    let mut ix = fixture.refund_ix();
+    ix.accounts.push(AccountMeta::new(maker.pubkey(), false));
+    let result = fixture.send(ix, &maker);
+    assert_custom_error(result, INVALID_RENT_PAYER);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_sponsored_make_fail_without_rent_payer_signature() {
+    let (mut fixture, sponsor) = setup();
+    let maker = fixture.maker.insecure_clone();
+
+    let mut ix = sponsored_make_ix(&fixture, &sponsor);
+    let last = ix.accounts.len() - 1;
+    ix.accounts[last].is_signer = false;
+    let result = fixture.send(ix, &maker);
+    assert_instruction_error(result, InstructionError::MissingRequiredSignature);
+}
@@ -0,0 +1,105 @@
+//! Take 指令数据解析：amount 之后由 flags 字节声明 sequence / max_pay，其余为 merkle proof；
+//! 在本机直接调用，不依赖程序二进制
+
+use pinocchio::error::ProgramError;
+use pinocchio_escrow::TakeInstructionData;
+
+const INVALID_AMOUNT: ProgramError = ProgramError::Custom(6000);
+const SEQUENCE: u8 = TakeInstructionData::HAS_SEQUENCE;
+const MAX_PAY: u8 = TakeInstructionData::HAS_MAX_PAY;
+
+fn encode(amount: u64, flags: Option<u8>, fields: &[u64], proof: &[u8]) -> Vec<u8> {
+    let mut data = amount.to_le_bytes().to_vec();
+    data.extend(flags);
+    for field in fields {
+        data.extend_from_slice(&field.to_le_bytes());
+    }
+    data.extend_from_slice(proof);
+    data
+}
+
+/// (amount, sequence, max_pay, proof)
+type Parsed = (Option<u64>, Option<u64>, Option<u64>, Vec<u8>);
+
+fn parse(data: &[u8]) -> Result<Parsed, ProgramError> {
+    TakeInstructionData::try_from(data).map(|take| {
+        (
+            take.amount,
+            take.sequence,
+            take.max_pay,
+            take.proof.to_vec(),
+        )
+    })
+}
+
+#[test]
+fn test_take_data_without_options() {
+    assert_eq!(parse(&[]), Ok((None, None, None, vec![])));
+    assert_eq!(
+        parse(&encode(5, None, &[], &[])),
+        Ok((Some(5), None, None, vec![]))
+    );
+    assert_eq!(
+        parse(&encode(5, Some(0), &[], &[])),
+        Ok((Some(5), None, None, vec![]))
+    );
+}
+
+#[test]
+fn test_take_data_flags_select_fields() {
+    assert_eq!(
+        parse(&encode(5, Some(SEQUENCE), &[3], &[])),
+        Ok((Some(5), Some(3), None, vec![]))
+    );
+    // max_pay 可以单独出现，不必附带 sequence
+    assert_eq!(
+        parse(&encode(5, Some(MAX_PAY), &[900], &[])),
+        Ok((Some(5), None, Some(900), vec![]))
+    );
+    assert_eq!(
+        parse(&encode(5, Some(SEQUENCE | MAX_PAY), &[3, 900], &[])),
+        Ok((Some(5), Some(3), Some(900), vec![]))
+    );
+}
+
+#[test]
+fn test_take_data_proof_follows_fields() {
+    let proof = [7u8; 64];
+    assert_eq!(
+        parse(&encode(5, Some(0), &[], &proof)),
+        Ok((Some(5), None, None, proof.to_vec()))
+    );
+    // 以前按长度 % 32 区分字段：sequence 与 proof 同时出现时仍按 flags 解析
+    assert_eq!(
+        parse(&encode(5, Some(SEQUENCE | MAX_PAY), &[3, 900], &proof)),
+        Ok((Some(5), Some(3), Some(900), proof.to_vec()))
+    );
+    assert_eq!(
+        parse(&encode(5, Some(MAX_PAY), &[900], &proof)),
+        Ok((Some(5), None, Some(900), proof.to_vec()))
+    );
+}
+
+#[test]
+fn test_take_data_rejects_malformed() {
+    assert_eq!(parse(&[1, 2, 3]), Err(ProgramError::InvalidInstructionData));
+    assert_eq!(parse(&encode(0, None, &[], &[])), Err(INVALID_AMOUNT));
+    // 未定义的 flag 位
+    assert_eq!(
+        parse(&encode(5, Some(1 << 2), &[], &[])),
+        Err(ProgramError::InvalidInstructionData)
+    );
+    // flags 声明的字段缺失或被截断
+    assert_eq!(
+        parse(&encode(5, Some(SEQUENCE), &[], &[])),
+        Err(ProgramError::InvalidInstructionData)
+    );
+    let mut truncated = encode(5, Some(SEQUENCE | MAX_PAY), &[3, 900], &[]);
+    truncated.pop();
+    assert_eq!(parse(&truncated), Err(ProgramError::InvalidInstructionData));
+    // proof 不是 32 字节的整数倍
+    assert_eq!(
+        parse(&encode(5, Some(0), &[], &[7u8; 40])),
+        Err(ProgramError::InvalidInstructionData)
+    );
+}
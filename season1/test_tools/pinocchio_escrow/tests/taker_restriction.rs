@@ -0,0 +1,143 @@
+//! 限定 taker 的 escrow：指定单个 taker，或用 merkle root 允许一组 taker（Take 时附 proof）
+//!
+//! 运行前请先在 season1/ 构建程序：`cargo build-programs`，测试默认 ignored，用 `cargo test-programs` 运行。
+
+mod common;
+
+use common::*;
+use pinocchio_escrow::Escrow;
+use solana_address::Address;
+use solana_instruction::Instruction;
+use solana_keypair::Keypair;
+use solana_pubkey::Pubkey;
+use solana_signer::Signer;
+use test_support::{associated_token_address, token_account};
+
+const UNAUTHORIZED_TAKER: u32 = 6008;
+
+/// Make 数据追加：expiry(8) + taker_mode(1) + taker(32)
+fn restricted_make_ix(fixture: &Fixture, taker_mode: u8, taker: &Address) -> Instruction {
+    let mut ix = fixture.make_ix(RECEIVE_AMOUNT, OFFER_AMOUNT);
+    ix.data.extend_from_slice(&0i64.to_le_bytes());
+    ix.data.push(taker_mode);
+    ix.data.extend_from_slice(taker.as_ref());
+    ix
+}
+
+fn make_restricted(fixture: &mut Fixture, taker_mode: u8, taker: &Address) {
+    let maker = fixture.maker.insecure_clone();
+    let result = fixture.send(restricted_make_ix(fixture, taker_mode, taker), &maker);
+    assert!(result.is_ok(), "Make 应成功: {:?}", result);
+}
+
+/// Take 数据：amount(8) + merkle proof(32 * n)
+fn take_with_proof_ix(fixture: &Fixture, proof: &[u8]) -> Instruction {
+    fixture.take_ix_with(OFFER_AMOUNT, None, None, proof)
+}
+
+/// 8 个 taker 的树（3 层 proof），fixture.taker 在 `index` 处
+fn allow_list(fixture: &Fixture, index: usize) -> (Address, Vec<u8>) {
+    let mut takers: Vec<Address> = (0..8).map(|_| Pubkey::new_unique()).collect();
+    takers[index] = fixture.taker.pubkey();
+    merkle_root_and_proof(&takers, index)
+}
+
+fn assert_taken(fixture: &Fixture) {
+    assert_eq!(fixture.token_balance(&fixture.taker_ata_a), OFFER_AMOUNT);
+    assert_eq!(fixture.token_balance(&fixture.maker_ata_b), RECEIVE_AMOUNT);
+    assert!(fixture.is_closed(&fixture.escrow));
+}
+
+fn assert_not_taken(fixture: &Fixture) {
+    assert_eq!(fixture.token_balance(&fixture.vault), OFFER_AMOUNT);
+    assert_eq!(fixture.token_balance(&fixture.maker_ata_b), 0);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_take_by_designated_taker() {
+    let mut fixture = Fixture::new(escrow_svm());
+    let taker = fixture.taker.insecure_clone();
+    make_restricted(&mut fixture, Escrow::TAKER_ADDRESS, &taker.pubkey());
+
+    let result = fixture.send(fixture.take_ix(), &taker);
+    assert!(result.is_ok(), "Take 应成功: {:?}", result);
+    assert_taken(&fixture);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_take_fail_not_designated_taker() {
+    let mut fixture = Fixture::new(escrow_svm());
+    make_restricted(&mut fixture, Escrow::TAKER_ADDRESS, &Pubkey::new_unique());
+
+    let taker = fixture.taker.insecure_clone();
+    let result = fixture.send(fixture.take_ix(), &taker);
+    assert_custom_error(result, UNAUTHORIZED_TAKER);
+    assert_not_taken(&fixture);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_take_with_merkle_proof() {
+    // 叶子在左右不同位置时 proof 的排序都要正确
+    for index in [0, 5] {
+        let mut fixture = Fixture::new(escrow_svm());
+        let (root, proof) = allow_list(&fixture, index);
+        assert_eq!(proof.len(), 3 * 32);
+        make_restricted(&mut fixture, Escrow::TAKER_MERKLE, &root);
+
+        let taker = fixture.taker.insecure_clone();
+        let result = fixture.send(take_with_proof_ix(&fixture, &proof), &taker);
+        assert!(result.is_ok(), "Take 应成功 (index {index}): {:?}", result);
+        assert_taken(&fixture);
+    }
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_take_fail_tampered_merkle_proof() {
+    let mut fixture = Fixture::new(escrow_svm());
+    let (root, proof) = allow_list(&fixture, 2);
+    make_restricted(&mut fixture, Escrow::TAKER_MERKLE, &root);
+    let taker = fixture.taker.insecure_clone();
+
+    // 改动任一层的 sibling、截掉一层、或不带 proof 都不能通过
+    let mut tampered = proof.clone();
+    tampered[40] ^= 1;
+    for bad_proof in [&tampered[..], &proof[..64], &[]] {
+        let result = fixture.send(take_with_proof_ix(&fixture, bad_proof), &taker);
+        assert_custom_error(result, UNAUTHORIZED_TAKER);
+    }
+    let result = fixture.send(fixture.take_ix(), &taker);
+    assert_custom_error(result, UNAUTHORIZED_TAKER);
+    assert_not_taken(&fixture);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_take_fail_proof_for_other_taker() {
+    let mut fixture = Fixture::new(escrow_svm());
+    let (root, proof) = allow_list(&fixture, 3);
+    make_restricted(&mut fixture, Escrow::TAKER_MERKLE, &root);
+
+    // 不在名单中的 taker 借用名单成员的 proof
+    let outsider = Keypair::new();
+    fixture
+        .svm
+        .airdrop(&outsider.pubkey(), 10_000_000_000)
+        .unwrap();
+    fixture.taker = outsider.insecure_clone();
+    fixture.taker_ata_a = associated_token_address(&outsider.pubkey(), &fixture.mint_a);
+    fixture.taker_ata_b = associated_token_address(&outsider.pubkey(), &fixture.mint_b);
+    fixture
+        .svm
+        .set_account(
+            fixture.taker_ata_b,
+            token_account(&fixture.mint_b, &outsider.pubkey(), INITIAL_BALANCE),
+        )
+        .unwrap();
+    let result = fixture.send(take_with_proof_ix(&fixture, &proof), &outsider);
+    assert_custom_error(result, UNAUTHORIZED_TAKER);
+    assert_not_taken(&fixture);
+}
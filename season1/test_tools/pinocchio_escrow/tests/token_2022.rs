@@ -0,0 +1,271 @@
+//! Token-2022 mint：转账手续费按净额加收、关闭前收取 vault 中扣留的手续费、
+//! CPI guard 账户改由 escrow PDA 作为 delegate 转账，以及拒绝不支持的扩展
+//!
+//! 运行前请先在 season1/ 构建程序：`cargo build-programs`，测试默认 ignored，用 `cargo test-programs` 运行。
+
+mod common;
+
+use common::*;
+use solana_account::ReadableAccount;
+use solana_address::Address;
+use solana_instruction::error::InstructionError;
+use solana_instruction::{AccountMeta, Instruction};
+use solana_signer::Signer;
+use test_support::{token_2022_extension, token_2022_program_id};
+
+const UNSUPPORTED_MINT: u32 = 6007;
+/// Token-2022 TokenError::OwnerMismatch
+const OWNER_MISMATCH: u32 = 4;
+
+/// Token-2022 CpiGuard 扩展类型
+const CPI_GUARD: u16 = 11;
+
+/// Token 账户 delegated_amount 在 offset 121
+const DELEGATED_AMOUNT_OFFSET: usize = 121;
+
+const FEE_BPS: u16 = 100;
+const NO_FEE_CAP: u64 = u64::MAX;
+
+fn cpi_guard() -> (u16, Vec<u8>) {
+    (CPI_GUARD, vec![1])
+}
+
+/// 让目标账户至少收到 `net` 的最小转账额
+fn gross_for(net: u64, basis_points: u16, maximum_fee: u64) -> u64 {
+    (net..)
+        .find(|gross| gross - transfer_fee(*gross, basis_points, maximum_fee) >= net)
+        .unwrap()
+}
+
+fn withheld_in_account(fixture: &Fixture, address: &Address) -> u64 {
+    let account = fixture.account(address).expect("账户应存在");
+    let value =
+        token_2022_extension(account.data(), TRANSFER_FEE_AMOUNT).expect("应有 TransferFeeAmount");
+    read_u64(value, 0)
+}
+
+/// Token-2022 Approve：discriminator(4) + amount(8)，owner 在顶层签名（CPI guard 只限制 CPI）
+fn approve_ix(source: &Address, delegate: &Address, owner: &Address, amount: u64) -> Instruction {
+    let mut data = vec![4u8];
+    data.extend_from_slice(&amount.to_le_bytes());
+    Instruction {
+        program_id: token_2022_program_id(),
+        accounts: vec![
+            AccountMeta::new(*source, false),
+            AccountMeta::new_readonly(*delegate, false),
+            AccountMeta::new_readonly(*owner, true),
+        ],
+        data,
+    }
+}
+
+fn take(fixture: &mut Fixture) {
+    let taker = fixture.taker.insecure_clone();
+    let result = fixture.send(fixture.take_ix(), &taker);
+    assert!(result.is_ok(), "Take 应成功: {:?}", result);
+}
+
+/// mint B 收手续费：taker 多付手续费，maker 恰好收到报价
+fn assert_take_grosses_up(basis_points: u16, maximum_fee: u64) {
+    let mut fixture = Fixture::new(escrow_svm());
+    fixture.use_token_2022_b(
+        &[transfer_fee_config(basis_points, maximum_fee)],
+        &[transfer_fee_amount()],
+    );
+    fixture.make();
+    take(&mut fixture);
+
+    let gross = gross_for(RECEIVE_AMOUNT, basis_points, maximum_fee);
+    assert!(gross > RECEIVE_AMOUNT);
+    assert_eq!(fixture.token_balance(&fixture.maker_ata_b), RECEIVE_AMOUNT);
+    assert_eq!(
+        withheld_in_account(&fixture, &fixture.maker_ata_b),
+        gross - RECEIVE_AMOUNT
+    );
+    assert_eq!(
+        fixture.token_balance(&fixture.taker_ata_b),
+        INITIAL_BALANCE - gross
+    );
+    assert_eq!(fixture.token_balance(&fixture.taker_ata_a), OFFER_AMOUNT);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_take_grosses_up_transfer_fee() {
+    assert_take_grosses_up(FEE_BPS, NO_FEE_CAP);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_take_grosses_up_capped_transfer_fee() {
+    // 5% 远超上限，手续费固定为 maximum_fee
+    assert_take_grosses_up(500, 100);
+}
+
+/// mint A 收手续费：Make 后 vault 中扣留了存入时的手续费
+fn make_with_fee_on_a() -> Fixture {
+    let mut fixture = Fixture::new(escrow_svm());
+    fixture.use_token_2022_a(
+        &[transfer_fee_config(FEE_BPS, NO_FEE_CAP)],
+        &[transfer_fee_amount()],
+    );
+    fixture.make();
+
+    // 报价按实际到账数量
+    let deposit_fee = transfer_fee(OFFER_AMOUNT, FEE_BPS, NO_FEE_CAP);
+    let received = OFFER_AMOUNT - deposit_fee;
+    assert_eq!(fixture.token_balance(&fixture.vault), received);
+    assert_eq!(withheld_in_account(&fixture, &fixture.vault), deposit_fee);
+    let data = fixture.account(&fixture.escrow).unwrap().data().to_vec();
+    assert_eq!(read_u64(&data, ESCROW_REMAINING_OFFSET), received);
+    fixture
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_take_harvests_withheld_fees_before_closing_vault() {
+    let mut fixture = make_with_fee_on_a();
+    take(&mut fixture);
+
+    // vault 扣留的手续费先收回 mint，再关闭 vault
+    let deposit_fee = transfer_fee(OFFER_AMOUNT, FEE_BPS, NO_FEE_CAP);
+    let received = OFFER_AMOUNT - deposit_fee;
+    assert!(fixture.is_closed(&fixture.vault));
+    assert!(fixture.is_closed(&fixture.escrow));
+    assert_eq!(withheld_in_mint(&fixture.svm, &fixture.mint_a), deposit_fee);
+    assert_eq!(
+        fixture.token_balance(&fixture.taker_ata_a),
+        received - transfer_fee(received, FEE_BPS, NO_FEE_CAP)
+    );
+    assert_eq!(fixture.token_balance(&fixture.maker_ata_b), RECEIVE_AMOUNT);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_refund_harvests_withheld_fees_before_closing_vault() {
+    let mut fixture = make_with_fee_on_a();
+    let maker = fixture.maker.insecure_clone();
+    let result = fixture.send(fixture.refund_ix(), &maker);
+    assert!(result.is_ok(), "Refund 应成功: {:?}", result);
+
+    let deposit_fee = transfer_fee(OFFER_AMOUNT, FEE_BPS, NO_FEE_CAP);
+    let received = OFFER_AMOUNT - deposit_fee;
+    assert!(fixture.is_closed(&fixture.vault));
+    assert!(fixture.is_closed(&fixture.escrow));
+    assert_eq!(withheld_in_mint(&fixture.svm, &fixture.mint_a), deposit_fee);
+    assert_eq!(
+        fixture.token_balance(&fixture.maker_ata_a),
+        INITIAL_BALANCE - OFFER_AMOUNT + received - transfer_fee(received, FEE_BPS, NO_FEE_CAP)
+    );
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_make_with_cpi_guard_transfers_as_delegate() {
+    let mut fixture = Fixture::new(escrow_svm());
+    fixture.use_token_2022_a(&[], &[cpi_guard()]);
+    let maker = fixture.maker.insecure_clone();
+
+    let approve = approve_ix(
+        &fixture.maker_ata_a,
+        &fixture.escrow,
+        &maker.pubkey(),
+        OFFER_AMOUNT,
+    );
+    let make = fixture.make_ix(RECEIVE_AMOUNT, OFFER_AMOUNT);
+    let result = fixture.send_all(&[approve, make], &maker);
+    assert!(result.is_ok(), "Approve + Make 应成功: {:?}", result);
+
+    assert_eq!(fixture.token_balance(&fixture.vault), OFFER_AMOUNT);
+    assert_eq!(
+        fixture.token_balance(&fixture.maker_ata_a),
+        INITIAL_BALANCE - OFFER_AMOUNT
+    );
+    // 授权额度被 escrow PDA 用完
+    let data = fixture
+        .account(&fixture.maker_ata_a)
+        .unwrap()
+        .data()
+        .to_vec();
+    assert_eq!(read_u64(&data, DELEGATED_AMOUNT_OFFSET), 0);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_make_with_cpi_guard_fails_without_approval() {
+    let mut fixture = Fixture::new(escrow_svm());
+    fixture.use_token_2022_a(&[], &[cpi_guard()]);
+    let maker = fixture.maker.insecure_clone();
+
+    // 未授权时 escrow PDA 既不是 owner 也不是 delegate
+    let result = fixture.send(fixture.make_ix(RECEIVE_AMOUNT, OFFER_AMOUNT), &maker);
+    assert_custom_error(result, OWNER_MISMATCH);
+    assert!(fixture.is_closed(&fixture.escrow));
+    assert_eq!(fixture.token_balance(&fixture.maker_ata_a), INITIAL_BALANCE);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_take_with_cpi_guard_transfers_as_delegate() {
+    let mut fixture = Fixture::new(escrow_svm());
+    fixture.use_token_2022_b(&[], &[cpi_guard()]);
+    fixture.make();
+    let taker = fixture.taker.insecure_clone();
+
+    let approve = approve_ix(
+        &fixture.taker_ata_b,
+        &fixture.escrow,
+        &taker.pubkey(),
+        RECEIVE_AMOUNT,
+    );
+    let take = fixture.take_ix();
+    let result = fixture.send_all(&[approve, take], &taker);
+    assert!(result.is_ok(), "Approve + Take 应成功: {:?}", result);
+
+    assert_eq!(fixture.token_balance(&fixture.maker_ata_b), RECEIVE_AMOUNT);
+    assert_eq!(
+        fixture.token_balance(&fixture.taker_ata_b),
+        INITIAL_BALANCE - RECEIVE_AMOUNT
+    );
+    assert_eq!(fixture.token_balance(&fixture.taker_ata_a), OFFER_AMOUNT);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_make_rejects_unsupported_mint_a_extensions() {
+    for extension in unsupported_extensions() {
+        let mut fixture = Fixture::new(escrow_svm());
+        fixture.use_token_2022_a(std::slice::from_ref(&extension), &[]);
+        let maker = fixture.maker.insecure_clone();
+        let result = fixture.send(fixture.make_ix(RECEIVE_AMOUNT, OFFER_AMOUNT), &maker);
+        assert_custom_error(result, UNSUPPORTED_MINT);
+        assert!(fixture.is_closed(&fixture.escrow), "扩展 {}", extension.0);
+    }
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_make_rejects_unsupported_mint_b_extensions() {
+    for extension in unsupported_extensions() {
+        let mut fixture = Fixture::new(escrow_svm());
+        fixture.use_token_2022_b(std::slice::from_ref(&extension), &[]);
+        let maker = fixture.maker.insecure_clone();
+        let result = fixture.send(fixture.make_ix(RECEIVE_AMOUNT, OFFER_AMOUNT), &maker);
+        assert_custom_error(result, UNSUPPORTED_MINT);
+        assert!(fixture.is_closed(&fixture.escrow), "扩展 {}", extension.0);
+    }
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_make_rejects_mismatched_token_program() {
+    let mut fixture = Fixture::new(escrow_svm());
+    fixture.use_token_2022_a(&[], &[]);
+    let maker = fixture.maker.insecure_clone();
+
+    // Token-2022 mint 配 SPL Token 程序
+    let mut ix = fixture.make_ix(RECEIVE_AMOUNT, OFFER_AMOUNT);
+    ix.accounts[7].pubkey = test_support::token_program_id();
+    let result = fixture.send(ix, &maker);
+    assert_instruction_error(result, InstructionError::IncorrectProgramId);
+}
@@ -0,0 +1,168 @@
+//! pinocchio_flash_loan LiteSVM 测试的公共代码：账户准备与指令构造
+//!
+//! 运行前请先在 season1/ 构建程序：`cargo build-programs`，测试默认 ignored，用 `cargo test-programs` 运行。
+
+#![allow(dead_code, clippy::result_large_err)]
+
+use litesvm::types::TransactionResult;
+use litesvm::LiteSVM;
+use solana_address::Address;
+use solana_instruction::error::InstructionError;
+use solana_instruction::{AccountMeta, Instruction};
+use solana_keypair::Keypair;
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+use solana_transaction_error::TransactionError;
+use test_support::{
+    associated_token_address, associated_token_program_id, mint_account, svm_with_programs,
+    system_program_id, token_account, token_program_id,
+};
+
+/// Instructions sysvar：Sysvar1nstructions1111111111111111111111111
+pub const INSTRUCTIONS_SYSVAR_ID: Address = Address::new_from_array([
+    0x06, 0xa7, 0xd5, 0x17, 0x18, 0x7b, 0xd1, 0x66, 0x35, 0xda, 0xd4, 0x04, 0x55, 0xfd, 0xc2,
+    0xc0, 0xc1, 0x24, 0xc6, 0x8f, 0x21, 0x56, 0x75, 0xa5, 0xdb, 0xba, 0xcb, 0x5f, 0x08, 0x00,
+    0x00, 0x00,
+]);
+
+pub const MINT_DECIMALS: u8 = 6;
+/// 协议池初始流动性
+pub const LIQUIDITY: u64 = 1_000_000_000;
+/// borrower 初始 token 余额，用来支付手续费
+pub const BORROWER_BALANCE: u64 = 100_000_000;
+
+pub fn flash_loan_program_id() -> Address {
+    pinocchio_flash_loan::ID
+}
+
+/// 返回 (protocol_pda, bump)
+pub fn find_protocol_pda() -> (Address, u8) {
+    Address::find_program_address(&[b"protocol"], &flash_loan_program_id())
+}
+
+/// 借款 `amount` 需归还的数量：本金 + 5% 手续费
+pub fn repay_amount(amount: u64) -> u64 {
+    amount + amount * pinocchio_flash_loan::FEE_BPS / 10_000
+}
+
+/// 加载 pinocchio_flash_loan，准备 mint、协议池和 borrower；未构建二进制时 panic
+pub struct Fixture {
+    pub svm: LiteSVM,
+    pub borrower: Keypair,
+    pub mint: Address,
+    pub protocol: Address,
+    pub protocol_ata: Address,
+    pub borrower_ata: Address,
+}
+
+impl Fixture {
+    pub fn new() -> Self {
+        Self::with_borrower_balance(BORROWER_BALANCE)
+    }
+
+    /// borrower ATA 初始为 `balance`；0 时不创建，由 Borrow 创建
+    pub fn with_borrower_balance(balance: u64) -> Self {
+        let mut svm = svm_with_programs(&[(flash_loan_program_id(), "pinocchio_flash_loan")]);
+        let borrower = Keypair::new();
+        svm.airdrop(&borrower.pubkey(), 10_000_000_000).unwrap();
+
+        let mint = Address::new_unique();
+        svm.set_account(mint, mint_account(MINT_DECIMALS, LIQUIDITY + balance))
+            .unwrap();
+
+        let (protocol, _) = find_protocol_pda();
+        let protocol_ata = associated_token_address(&protocol, &mint);
+        svm.set_account(protocol_ata, token_account(&mint, &protocol, LIQUIDITY))
+            .unwrap();
+
+        let borrower_ata = associated_token_address(&borrower.pubkey(), &mint);
+        if balance > 0 {
+            svm.set_account(
+                borrower_ata,
+                token_account(&mint, &borrower.pubkey(), balance),
+            )
+            .unwrap();
+        }
+
+        Self {
+            svm,
+            borrower,
+            mint,
+            protocol,
+            protocol_ata,
+            borrower_ata,
+        }
+    }
+
+    /// Borrow / Repay 共用的账户，顺序与 anchor_flash_loan 的 `Loan` 一致
+    pub fn loan_accounts(&self) -> Vec<AccountMeta> {
+        vec![
+            AccountMeta::new(self.borrower.pubkey(), true),
+            AccountMeta::new_readonly(self.protocol, false),
+            AccountMeta::new_readonly(self.mint, false),
+            AccountMeta::new(self.borrower_ata, false),
+            AccountMeta::new(self.protocol_ata, false),
+            AccountMeta::new_readonly(INSTRUCTIONS_SYSVAR_ID, false),
+            AccountMeta::new_readonly(token_program_id(), false),
+            AccountMeta::new_readonly(associated_token_program_id(), false),
+            AccountMeta::new_readonly(system_program_id(), false),
+        ]
+    }
+
+    /// Borrow 指令：discriminator(0) + amount(8)
+    pub fn borrow_ix(&self, amount: u64) -> Instruction {
+        let mut data = vec![0u8];
+        data.extend_from_slice(&amount.to_le_bytes());
+        Instruction {
+            program_id: flash_loan_program_id(),
+            accounts: self.loan_accounts(),
+            data,
+        }
+    }
+
+    /// Repay 指令：discriminator(1)
+    pub fn repay_ix(&self) -> Instruction {
+        Instruction {
+            program_id: flash_loan_program_id(),
+            accounts: self.loan_accounts(),
+            data: vec![1u8],
+        }
+    }
+
+    /// borrower 作为 payer 和唯一签名者发送一笔多指令交易
+    pub fn send(&mut self, ixs: &[Instruction]) -> TransactionResult {
+        self.svm.expire_blockhash();
+        let tx = Transaction::new_signed_with_payer(
+            ixs,
+            Some(&self.borrower.pubkey()),
+            &[&self.borrower],
+            self.svm.latest_blockhash(),
+        );
+        self.svm.send_transaction(tx)
+    }
+
+    pub fn token_balance(&self, address: &Address) -> u64 {
+        self.svm
+            .get_account(address)
+            .map(|account| test_support::token_account_amount(&account.data))
+            .unwrap_or(0)
+    }
+}
+
+/// 断言交易在第 `index` 条指令因指定的 InstructionError 失败
+pub fn assert_instruction_error(result: TransactionResult, index: u8, expected: InstructionError) {
+    match result {
+        Err(failed) => assert_eq!(
+            failed.err,
+            TransactionError::InstructionError(index, expected),
+            "logs: {:#?}",
+            failed.meta.logs
+        ),
+        Ok(_) => panic!("交易应失败，期望 {:?}", expected),
+    }
+}
+
+/// 断言交易在第 `index` 条指令因指定的 FlashLoanError 自定义错误码失败
+pub fn assert_custom_error(result: TransactionResult, index: u8, code: u32) {
+    assert_instruction_error(result, index, InstructionError::Custom(code));
+}
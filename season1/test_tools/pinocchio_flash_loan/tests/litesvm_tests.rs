@@ -0,0 +1,106 @@
+//! LiteSVM 集成测试：pinocchio_flash_loan 的 Borrow / Repay 与指令内省
+//!
+//! 运行前请先在 season1/ 构建程序：`cargo build-programs`（输出到 target/deploy），
+//! 测试默认 ignored，用 `cargo test-programs` 运行。
+
+mod common;
+
+use common::*;
+use solana_address::Address;
+use solana_instruction::error::InstructionError;
+use solana_signer::Signer;
+
+/// FlashLoanError 错误码（与 anchor_flash_loan 的 ProtocolError 相同）
+const INVALID_AMOUNT: u32 = 6002;
+const NOT_ENOUGH_FUNDS: u32 = 6003;
+const MISSING_REPAY_IX: u32 = 6008;
+const MISSING_BORROW_IX: u32 = 6009;
+
+const LOAN: u64 = 500_000_000;
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_borrow_and_repay() {
+    let mut fixture = Fixture::new();
+    let result = fixture.send(&[fixture.borrow_ix(LOAN), fixture.repay_ix()]);
+    assert!(result.is_ok(), "Borrow + Repay 应成功: {:?}", result);
+
+    let fee = repay_amount(LOAN) - LOAN;
+    assert_eq!(fixture.token_balance(&fixture.protocol_ata), LIQUIDITY + fee);
+    assert_eq!(
+        fixture.token_balance(&fixture.borrower_ata),
+        BORROWER_BALANCE - fee
+    );
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_borrow_fail_missing_repay() {
+    let mut fixture = Fixture::new();
+    let result = fixture.send(&[fixture.borrow_ix(LOAN)]);
+    assert_custom_error(result, 0, MISSING_REPAY_IX);
+    assert_eq!(fixture.token_balance(&fixture.protocol_ata), LIQUIDITY);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_repay_fail_under_repaid() {
+    // borrower 没有 token 支付手续费：只能还本金，不够
+    let mut fixture = Fixture::with_borrower_balance(0);
+    let result = fixture.send(&[fixture.borrow_ix(LOAN), fixture.repay_ix()]);
+    assert_custom_error(result, 1, NOT_ENOUGH_FUNDS);
+    assert_eq!(fixture.token_balance(&fixture.protocol_ata), LIQUIDITY);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_repay_fail_missing_borrow() {
+    let mut fixture = Fixture::new();
+    let result = fixture.send(&[fixture.repay_ix()]);
+    assert_custom_error(result, 0, MISSING_BORROW_IX);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_borrow_fail_nested() {
+    // 两个 Borrow 共用一个 Repay：第一笔借款找不到紧随其后的 Repay
+    let mut fixture = Fixture::new();
+    let result = fixture.send(&[
+        fixture.borrow_ix(LOAN),
+        fixture.borrow_ix(LOAN),
+        fixture.repay_ix(),
+    ]);
+    assert_custom_error(result, 0, MISSING_REPAY_IX);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_borrow_fail_repay_on_other_accounts() {
+    // Repay 必须作用于同一组 token 账户
+    let mut fixture = Fixture::new();
+    let mut repay = fixture.repay_ix();
+    repay.accounts[3].pubkey = Address::new_unique();
+    let result = fixture.send(&[fixture.borrow_ix(LOAN), repay]);
+    assert_custom_error(result, 0, MISSING_REPAY_IX);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_borrow_fail_invalid_amount() {
+    let mut fixture = Fixture::new();
+    let result = fixture.send(&[fixture.borrow_ix(0), fixture.repay_ix()]);
+    assert_custom_error(result, 0, INVALID_AMOUNT);
+
+    let result = fixture.send(&[fixture.borrow_ix(LIQUIDITY + 1), fixture.repay_ix()]);
+    assert_custom_error(result, 0, NOT_ENOUGH_FUNDS);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_borrow_fail_wrong_sysvar() {
+    let mut fixture = Fixture::new();
+    let mut borrow = fixture.borrow_ix(LOAN);
+    borrow.accounts[5].pubkey = fixture.borrower.pubkey();
+    let result = fixture.send(&[borrow, fixture.repay_ix()]);
+    assert_instruction_error(result, 0, InstructionError::UnsupportedSysvar);
+}
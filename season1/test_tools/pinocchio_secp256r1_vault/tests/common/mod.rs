@@ -0,0 +1,354 @@
+//! pinocchio_secp256r1_vault LiteSVM 测试的公共代码：账户准备与指令构造
+//!
+//! 运行前请先在 season1/ 构建程序：`cargo build-programs`，测试默认 ignored，用 `cargo test-programs` 运行。
+//! Withdraw 依赖 secp256r1 预编译程序，由 LiteSVM 的 `precompiles` feature 提供。
+
+#![allow(dead_code, clippy::result_large_err)]
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use litesvm::types::TransactionResult;
+use litesvm::LiteSVM;
+use p256::ecdsa::signature::Signer as _;
+use p256::ecdsa::{Signature, SigningKey};
+use pinocchio_secp256r1_instruction::SECP256R1_PROGRAM_ID;
+use pinocchio_secp256r1_vault::WithdrawMessage;
+use solana_account::ReadableAccount;
+use solana_address::Address;
+use solana_clock::Clock;
+use solana_instruction::error::InstructionError;
+use solana_instruction::{AccountMeta, Instruction};
+use solana_keypair::Keypair;
+use solana_sha256_hasher::hash;
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+use solana_transaction_error::TransactionError;
+use test_support::{svm_with_programs, system_program_id};
+
+pub use pinocchio_secp256r1_vault::Secp256r1Pubkey;
+
+/// 存款金额
+pub const DEPOSIT_AMOUNT: u64 = 1_000_000_000;
+
+/// Withdraw 交易费：交易签名与预编译程序验证的签名各 5_000 lamports
+pub const WITHDRAW_FEE: u64 = 2 * 5_000;
+
+/// 签名消息默认的有效期（秒）
+pub const MESSAGE_TTL: i64 = 60;
+
+/// Secp256r1VaultError 错误码
+pub const DOMAIN_MISMATCH: u32 = 6000;
+pub const INVALID_NONCE: u32 = 6001;
+pub const MESSAGE_EXPIRED: u32 = 6002;
+pub const RECIPIENT_MISMATCH: u32 = 6003;
+pub const INVALID_AMOUNT: u32 = 6004;
+pub const BELOW_RENT_EXEMPTION: u32 = 6005;
+pub const RP_ID_MISMATCH: u32 = 6006;
+pub const USER_NOT_PRESENT: u32 = 6007;
+pub const INVALID_CLIENT_DATA: u32 = 6008;
+pub const CHALLENGE_MISMATCH: u32 = 6009;
+
+/// passkey 断言的 authenticatorData flags：UP | UV
+pub const PASSKEY_FLAGS: u8 = 0x05;
+
+/// Instructions sysvar：Sysvar1nstructions1111111111111111111111111
+pub fn instructions_sysvar_id() -> Address {
+    Address::from_str_const("Sysvar1nstructions1111111111111111111111111")
+}
+
+pub fn secp256r1_program_id() -> Address {
+    Address::new_from_array(SECP256R1_PROGRAM_ID)
+}
+
+/// 测试用的固定 p256 私钥
+pub fn signing_key() -> SigningKey {
+    SigningKey::from_bytes(&[0x11; 32].into()).unwrap()
+}
+
+/// 压缩格式的 secp256r1 公钥
+pub fn secp256r1_pubkey(signing_key: &SigningKey) -> Secp256r1Pubkey {
+    signing_key
+        .verifying_key()
+        .to_encoded_point(true)
+        .as_bytes()
+        .try_into()
+        .unwrap()
+}
+
+/// 预编译程序要求 low-S 签名
+pub fn sign(signing_key: &SigningKey, message: &[u8]) -> [u8; 64] {
+    let signature: Signature = signing_key.sign(message);
+    let signature = signature.normalize_s().unwrap_or(signature);
+    signature.to_bytes().into()
+}
+
+/// 单个签名、数据均在本指令内的 secp256r1 预编译指令数据
+pub fn secp256r1_instruction_data(pubkey: &[u8], signature: &[u8], message: &[u8]) -> Vec<u8> {
+    const HEADER_LEN: u16 = 2 + 14;
+    let pubkey_offset = HEADER_LEN;
+    let signature_offset = pubkey_offset + 33;
+    let message_offset = signature_offset + 64;
+
+    let mut data = vec![1u8, 0];
+    for field in [
+        signature_offset,
+        u16::MAX,
+        pubkey_offset,
+        u16::MAX,
+        message_offset,
+        message.len() as u16,
+        u16::MAX,
+    ] {
+        data.extend_from_slice(&field.to_le_bytes());
+    }
+    data.extend_from_slice(pubkey);
+    data.extend_from_slice(signature);
+    data.extend_from_slice(message);
+    data
+}
+
+/// passkey 断言的挑战：`sha256(WithdrawMessage)`
+pub fn challenge(msg: &WithdrawMessage) -> [u8; 32] {
+    hash(&msg.to_bytes()).to_bytes()
+}
+
+/// rpIdHash(32) + flags(1) + signCount(4)
+pub fn authenticator_data(rp_id: &str, flags: u8) -> Vec<u8> {
+    let mut data = hash(rp_id.as_bytes()).to_bytes().to_vec();
+    data.push(flags);
+    data.extend_from_slice(&7u32.to_be_bytes());
+    data
+}
+
+/// 浏览器 `navigator.credentials.get()` 产生的 clientDataJSON
+pub fn client_data_json(challenge: &[u8; 32]) -> String {
+    format!(
+        r#"{{"type":"webauthn.get","challenge":"{}","origin":"http://localhost:3000","crossOrigin":false}}"#,
+        URL_SAFE_NO_PAD.encode(challenge)
+    )
+}
+
+/// 预编译程序验证的消息：authenticatorData || sha256(clientDataJSON)
+pub fn signed_message(authenticator_data: &[u8], client_data_json: &str) -> Vec<u8> {
+    let mut signed = authenticator_data.to_vec();
+    signed.extend_from_slice(&hash(client_data_json.as_bytes()).to_bytes());
+    signed
+}
+
+/// 用 `signing_key` 签名 `message` 的 secp256r1 预编译指令
+pub fn secp256r1_ix(signing_key: &SigningKey, message: &[u8]) -> Instruction {
+    let pubkey = secp256r1_pubkey(signing_key);
+    Instruction {
+        program_id: secp256r1_program_id(),
+        accounts: vec![],
+        data: secp256r1_instruction_data(&pubkey, &sign(signing_key, message), message),
+    }
+}
+
+/// VaultState 布局（见 pinocchio_secp256r1_vault::state::VaultState）
+pub const STATE_DISCRIMINATOR_OFFSET: usize = 0;
+pub const STATE_NONCE_OFFSET: usize = 8;
+
+pub fn vault_program_id() -> Address {
+    pinocchio_secp256r1_vault::ID
+}
+
+/// 返回 secp256r1 公钥对应的 (vault_pda, bump)
+pub fn find_vault(pubkey: &Secp256r1Pubkey) -> (Address, u8) {
+    Address::find_program_address(&[b"vault", &pubkey[..1], &pubkey[1..]], &vault_program_id())
+}
+
+/// 加载 pinocchio_secp256r1_vault，准备 payer 与 secp256r1 密钥对；未构建二进制时 panic
+pub struct Fixture {
+    pub svm: LiteSVM,
+    pub payer: Keypair,
+    pub signing_key: SigningKey,
+    pub pubkey: Secp256r1Pubkey,
+    pub vault: Address,
+    pub vault_bump: u8,
+    pub vault_state: Address,
+}
+
+impl Fixture {
+    pub fn new() -> Self {
+        let mut svm = svm_with_programs(&[(vault_program_id(), "pinocchio_secp256r1_vault")]);
+        let payer = Keypair::new();
+        svm.airdrop(&payer.pubkey(), 10_000_000_000).unwrap();
+
+        let signing_key = signing_key();
+        let pubkey = secp256r1_pubkey(&signing_key);
+        let (vault, vault_bump) = find_vault(&pubkey);
+        let vault_state = pinocchio_secp256r1_vault::find_vault_state(&vault).0;
+
+        Self {
+            svm,
+            payer,
+            signing_key,
+            pubkey,
+            vault,
+            vault_bump,
+            vault_state,
+        }
+    }
+
+    /// 已存入 DEPOSIT_AMOUNT 的 vault
+    pub fn deposited() -> Self {
+        let mut fixture = Self::new();
+        let result = fixture.send(&[fixture.deposit_ix(DEPOSIT_AMOUNT)]);
+        assert!(result.is_ok(), "Deposit 应成功: {:?}", result);
+        fixture
+    }
+
+    /// Deposit 指令：discriminator(0) + pubkey(33) + amount(8)
+    pub fn deposit_ix(&self, amount: u64) -> Instruction {
+        let mut data = vec![0u8];
+        data.extend_from_slice(&self.pubkey);
+        data.extend_from_slice(&amount.to_le_bytes());
+        Instruction {
+            program_id: vault_program_id(),
+            accounts: vec![
+                AccountMeta::new(self.payer.pubkey(), true),
+                AccountMeta::new(self.vault, false),
+                AccountMeta::new(self.vault_state, false),
+                AccountMeta::new_readonly(system_program_id(), false),
+            ],
+            data,
+        }
+    }
+
+    /// InitVaultState 指令：discriminator(2) + pubkey(33)
+    pub fn init_vault_state_ix(&self) -> Instruction {
+        let mut data = vec![2u8];
+        data.extend_from_slice(&self.pubkey);
+        Instruction {
+            program_id: vault_program_id(),
+            accounts: vec![
+                AccountMeta::new(self.payer.pubkey(), true),
+                AccountMeta::new_readonly(self.vault, false),
+                AccountMeta::new(self.vault_state, false),
+                AccountMeta::new_readonly(system_program_id(), false),
+            ],
+            data,
+        }
+    }
+
+    /// 本程序、本 vault、当前 nonce，`MESSAGE_TTL` 秒后过期的取款消息
+    pub fn message(&self, recipient: &Address, amount: u64) -> WithdrawMessage {
+        WithdrawMessage {
+            program_id: vault_program_id(),
+            vault: self.vault,
+            nonce: self.nonce(),
+            recipient: *recipient,
+            amount,
+            expiry: self.now() + MESSAGE_TTL,
+        }
+    }
+
+    /// Withdraw 指令：discriminator(1) + bump(1) + `extra`（passkey 断言时为消息与 clientDataJSON）
+    pub fn withdraw_ix(&self, recipient: &Address, extra: &[u8]) -> Instruction {
+        let mut data = vec![1u8, self.vault_bump];
+        data.extend_from_slice(extra);
+        Instruction {
+            program_id: vault_program_id(),
+            accounts: vec![
+                AccountMeta::new(self.payer.pubkey(), true),
+                AccountMeta::new(self.vault, false),
+                AccountMeta::new(self.vault_state, false),
+                AccountMeta::new(*recipient, false),
+                AccountMeta::new_readonly(instructions_sysvar_id(), false),
+                AccountMeta::new_readonly(system_program_id(), false),
+            ],
+            data,
+        }
+    }
+
+    /// 签名 `msg` 并提交：Withdraw 读取紧随其后的预编译指令
+    pub fn withdraw(&mut self, msg: &WithdrawMessage) -> TransactionResult {
+        let ixs = [
+            self.withdraw_ix(&msg.recipient, &[]),
+            secp256r1_ix(&self.signing_key, &msg.to_bytes()),
+        ];
+        self.send(&ixs)
+    }
+
+    /// 以 passkey 断言签名 `msg` 并提交：预编译程序验证
+    /// `authenticator_data || sha256(clientDataJSON)`，Withdraw 数据附带消息与 clientDataJSON
+    pub fn withdraw_with_passkey(
+        &mut self,
+        msg: &WithdrawMessage,
+        authenticator_data: &[u8],
+    ) -> TransactionResult {
+        let client_data_json = client_data_json(&challenge(msg));
+        let signed = signed_message(authenticator_data, &client_data_json);
+        let mut extra = msg.to_bytes().to_vec();
+        extra.extend_from_slice(client_data_json.as_bytes());
+        let ixs = [
+            self.withdraw_ix(&msg.recipient, &extra),
+            secp256r1_ix(&self.signing_key, &signed),
+        ];
+        self.send(&ixs)
+    }
+
+    /// 当前链上时间
+    pub fn now(&self) -> i64 {
+        self.svm.get_sysvar::<Clock>().unix_timestamp
+    }
+
+    /// 将时钟拨到 `unix_timestamp`
+    pub fn warp_to(&mut self, unix_timestamp: i64) {
+        let mut clock = self.svm.get_sysvar::<Clock>();
+        clock.unix_timestamp = unix_timestamp;
+        self.svm.set_sysvar(&clock);
+    }
+
+    /// 以 payer 为付费者和唯一签名者发送一笔交易
+    pub fn send(&mut self, ixs: &[Instruction]) -> TransactionResult {
+        self.svm.expire_blockhash();
+        let tx = Transaction::new_signed_with_payer(
+            ixs,
+            Some(&self.payer.pubkey()),
+            &[&self.payer],
+            self.svm.latest_blockhash(),
+        );
+        self.svm.send_transaction(tx)
+    }
+
+    pub fn lamports(&self, address: &Address) -> u64 {
+        self.svm
+            .get_account(address)
+            .map(|account| account.lamports())
+            .unwrap_or(0)
+    }
+
+    /// vault_state 当前的 nonce
+    pub fn nonce(&self) -> u64 {
+        let account = self
+            .svm
+            .get_account(&self.vault_state)
+            .expect("vault_state 应存在");
+        let data = account.data();
+        u64::from_le_bytes(
+            data[STATE_NONCE_OFFSET..STATE_NONCE_OFFSET + 8]
+                .try_into()
+                .unwrap(),
+        )
+    }
+}
+
+/// 断言交易在第 `index` 条指令因指定的 Secp256r1VaultError 失败
+pub fn assert_custom_error(result: TransactionResult, index: u8, code: u32) {
+    assert_instruction_error(result, index, InstructionError::Custom(code));
+}
+
+/// 断言交易在第 `index` 条指令因指定的 InstructionError 失败
+pub fn assert_instruction_error(result: TransactionResult, index: u8, expected: InstructionError) {
+    match result {
+        Err(failed) => assert_eq!(
+            failed.err,
+            TransactionError::InstructionError(index, expected),
+            "logs: {:#?}",
+            failed.meta.logs
+        ),
+        Ok(_) => panic!("交易应失败，期望 {:?}", expected),
+    }
+}
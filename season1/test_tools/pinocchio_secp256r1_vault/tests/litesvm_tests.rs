@@ -0,0 +1,40 @@
+//! LiteSVM 集成测试：pinocchio_secp256r1_vault 的 Deposit 与 nonce 状态
+//!
+//! 运行前请先在 season1/ 构建程序：`cargo build-programs`（输出到 target/deploy），
+//! 测试默认 ignored，用 `cargo test-programs` 运行。
+
+mod common;
+
+use common::*;
+use pinocchio_secp256r1_vault::state::VaultState;
+use solana_account::ReadableAccount;
+use solana_address::Address;
+use solana_instruction::error::InstructionError;
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_deposit_creates_vault_state() {
+    let mut fixture = Fixture::new();
+    let result = fixture.send(&[fixture.deposit_ix(DEPOSIT_AMOUNT)]);
+    assert!(result.is_ok(), "Deposit 应成功: {:?}", result);
+    assert_eq!(fixture.lamports(&fixture.vault), DEPOSIT_AMOUNT);
+
+    let state = fixture.svm.get_account(&fixture.vault_state).unwrap();
+    assert_eq!(state.owner(), &vault_program_id());
+    assert_eq!(state.data().len(), VaultState::LEN);
+    assert_eq!(
+        state.data()[STATE_DISCRIMINATOR_OFFSET],
+        VaultState::DISCRIMINATOR
+    );
+    assert_eq!(fixture.nonce(), 0);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_deposit_fail_wrong_vault_state() {
+    let mut fixture = Fixture::new();
+    let mut ix = fixture.deposit_ix(DEPOSIT_AMOUNT);
+    ix.accounts[2].pubkey = Address::new_unique();
+    let result = fixture.send(&[ix]);
+    assert_instruction_error(result, 0, InstructionError::InvalidSeeds);
+}
@@ -0,0 +1,51 @@
+//! pinocchio_secp256r1_vault 的 Withdraw 签名消息编码：不依赖程序二进制，直接在本机运行
+
+use pinocchio_secp256r1_vault::{WithdrawMessage, WITHDRAW_MESSAGE_LEN};
+use solana_address::Address;
+
+fn message() -> WithdrawMessage {
+    WithdrawMessage {
+        program_id: pinocchio_secp256r1_vault::ID,
+        vault: Address::new_unique(),
+        nonce: 3,
+        recipient: Address::new_unique(),
+        amount: 250_000_000,
+        expiry: 1_700_000_000,
+    }
+}
+
+#[test]
+fn test_message_layout() {
+    let msg = message();
+    let bytes = msg.to_bytes();
+    assert_eq!(bytes.len(), WITHDRAW_MESSAGE_LEN);
+    assert_eq!(&bytes[..32], pinocchio_secp256r1_vault::ID.as_ref());
+    assert_eq!(&bytes[32..64], msg.vault.as_ref());
+    assert_eq!(bytes[64..72], 3u64.to_le_bytes());
+    assert_eq!(&bytes[72..104], msg.recipient.as_ref());
+    assert_eq!(bytes[104..112], 250_000_000u64.to_le_bytes());
+    assert_eq!(bytes[112..], 1_700_000_000i64.to_le_bytes());
+}
+
+#[test]
+fn test_message_round_trip() {
+    let msg = message();
+    let parsed = WithdrawMessage::try_from(&msg.to_bytes()[..]).unwrap();
+    assert_eq!(parsed.program_id, msg.program_id);
+    assert_eq!(parsed.vault, msg.vault);
+    assert_eq!(parsed.nonce, msg.nonce);
+    assert_eq!(parsed.recipient, msg.recipient);
+    assert_eq!(parsed.amount, msg.amount);
+    assert_eq!(parsed.expiry, msg.expiry);
+}
+
+#[test]
+fn test_message_fail_wrong_length() {
+    let bytes = message().to_bytes();
+    // 旧格式 payer || expiry，以及截断 / 追加的消息都会被拒绝
+    assert!(WithdrawMessage::try_from(&bytes[..40]).is_err());
+    assert!(WithdrawMessage::try_from(&bytes[..WITHDRAW_MESSAGE_LEN - 1]).is_err());
+    let mut longer = bytes.to_vec();
+    longer.push(0);
+    assert!(WithdrawMessage::try_from(&longer[..]).is_err());
+}
@@ -0,0 +1,225 @@
+//! pinocchio_secp256r1_vault 的 passkey 断言校验：在本机用 p256 生成测试向量，不依赖程序二进制
+//!
+//! 向量按浏览器 `navigator.credentials.get()` 的输出构造：authenticatorData 以
+//! sha256(RP_ID) 开头，clientDataJSON 的 challenge 为 base64url(sha256(WithdrawMessage))，
+//! 签名覆盖 `authenticatorData || sha256(clientDataJSON)`。
+
+mod common;
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use common::{
+    authenticator_data, challenge, client_data_json, secp256r1_instruction_data, signed_message,
+    CHALLENGE_MISMATCH, INVALID_CLIENT_DATA, PASSKEY_FLAGS as FLAGS, RP_ID_MISMATCH,
+    USER_NOT_PRESENT,
+};
+use p256::ecdsa::signature::{Signer, Verifier};
+use p256::ecdsa::{Signature, SigningKey};
+use pinocchio::error::ProgramError;
+use pinocchio_secp256r1_instruction::Secp256r1Instruction;
+use pinocchio_secp256r1_vault::webauthn::{
+    base64url_encode_32, rp_id_hash, verify_assertion, ClientData, FLAG_USER_PRESENT, RP_ID,
+};
+use pinocchio_secp256r1_vault::{WithdrawInstructionData, WithdrawMessage};
+use solana_address::Address;
+use solana_sha256_hasher::hash;
+
+fn withdraw_message() -> WithdrawMessage {
+    WithdrawMessage {
+        program_id: pinocchio_secp256r1_vault::ID,
+        vault: Address::new_from_array([3; 32]),
+        nonce: 0,
+        recipient: Address::new_from_array([4; 32]),
+        amount: 250_000_000,
+        expiry: 1_700_000_000,
+    }
+}
+
+fn assert_custom(result: Result<(), ProgramError>, code: u32) {
+    assert_eq!(result, Err(ProgramError::Custom(code)));
+}
+
+#[test]
+fn test_rp_id_hash() {
+    assert_eq!(rp_id_hash(), hash(RP_ID.as_bytes()).to_bytes());
+    // 未设置 SECP256R1_VAULT_RP_ID 构建时的默认依赖方
+    if option_env!("SECP256R1_VAULT_RP_ID").is_none() {
+        assert_eq!(RP_ID, "localhost");
+        assert_eq!(
+            rp_id_hash(),
+            [
+                0x49, 0x96, 0x0d, 0xe5, 0x88, 0x0e, 0x8c, 0x68, 0x74, 0x34, 0x17, 0x0f, 0x64, 0x76,
+                0x60, 0x5b, 0x8f, 0xe4, 0xae, 0xb9, 0xa2, 0x86, 0x32, 0xc7, 0x99, 0x5c, 0xf3, 0xba,
+                0x83, 0x1d, 0x97, 0x63,
+            ]
+        );
+    }
+}
+
+#[test]
+fn test_base64url_matches_reference() {
+    for bytes in [[0u8; 32], [0xff; 32], challenge(&withdraw_message())] {
+        assert_eq!(
+            base64url_encode_32(&bytes).as_slice(),
+            URL_SAFE_NO_PAD.encode(bytes).as_bytes()
+        );
+    }
+}
+
+#[test]
+fn test_passkey_assertion_vector() {
+    let signing_key = SigningKey::from_bytes(&[0x11; 32].into()).unwrap();
+    let pubkey = signing_key.verifying_key().to_encoded_point(true);
+
+    let msg = withdraw_message();
+    let client_data_json = client_data_json(&challenge(&msg));
+    let signed = signed_message(&authenticator_data(RP_ID, FLAGS), &client_data_json);
+
+    // 预编译程序要求 low-S 签名
+    let signature: Signature = signing_key.sign(&signed);
+    let signature = signature.normalize_s().unwrap_or(signature);
+    signing_key
+        .verifying_key()
+        .verify(&signed, &signature)
+        .unwrap();
+
+    // 程序从预编译指令中读到的公钥与消息
+    let ix_data = secp256r1_instruction_data(pubkey.as_bytes(), &signature.to_bytes(), &signed);
+    let secp256r1_ix = Secp256r1Instruction::try_from(&ix_data[..]).unwrap();
+    assert_eq!(secp256r1_ix.num_signatures(), 1);
+    assert_eq!(secp256r1_ix.get_signer(0).unwrap(), pubkey.as_bytes());
+    let signed_in_ix = secp256r1_ix.get_message_data(0).unwrap();
+    assert_eq!(signed_in_ix, &signed[..]);
+
+    // Withdraw 指令数据：bump || WithdrawMessage || clientDataJSON
+    let mut withdraw_data = vec![255u8];
+    withdraw_data.extend_from_slice(&msg.to_bytes());
+    withdraw_data.extend_from_slice(client_data_json.as_bytes());
+    let parsed = WithdrawInstructionData::try_from(&withdraw_data[..]).unwrap();
+    let (parsed_msg, parsed_json) = parsed.webauthn.expect("应为 passkey 断言");
+    assert_eq!(parsed_msg.to_bytes(), msg.to_bytes());
+    assert_eq!(parsed_json, client_data_json.as_bytes());
+
+    assert_eq!(
+        verify_assertion(signed_in_ix, parsed_json, &challenge(&parsed_msg)),
+        Ok(())
+    );
+}
+
+#[test]
+fn test_raw_withdraw_data_has_no_assertion() {
+    let parsed = WithdrawInstructionData::try_from(&[254u8][..]).unwrap();
+    assert_eq!(parsed.bump, [254]);
+    assert!(parsed.webauthn.is_none());
+
+    // 只有消息、没有 clientDataJSON
+    let mut data = vec![254u8];
+    data.extend_from_slice(&withdraw_message().to_bytes());
+    assert!(WithdrawInstructionData::try_from(&data[..]).is_err());
+}
+
+#[test]
+fn test_assertion_fail_other_message() {
+    let msg = withdraw_message();
+    let client_data_json = client_data_json(&challenge(&msg));
+    let signed = signed_message(&authenticator_data(RP_ID, FLAGS), &client_data_json);
+
+    // 签名的挑战对应 250_000_000，提交的消息改成了更大的金额
+    let tampered = WithdrawMessage {
+        amount: 1_000_000_000,
+        ..withdraw_message()
+    };
+    assert_custom(
+        verify_assertion(&signed, client_data_json.as_bytes(), &challenge(&tampered)),
+        CHALLENGE_MISMATCH,
+    );
+}
+
+#[test]
+fn test_assertion_fail_other_rp() {
+    let expected = challenge(&withdraw_message());
+    let client_data_json = client_data_json(&expected);
+    let signed = signed_message(
+        &authenticator_data("evil.example", FLAGS),
+        &client_data_json,
+    );
+    assert_custom(
+        verify_assertion(&signed, client_data_json.as_bytes(), &expected),
+        RP_ID_MISMATCH,
+    );
+}
+
+#[test]
+fn test_assertion_fail_user_not_present() {
+    let expected = challenge(&withdraw_message());
+    let client_data_json = client_data_json(&expected);
+    let signed = signed_message(
+        &authenticator_data(RP_ID, FLAGS & !FLAG_USER_PRESENT),
+        &client_data_json,
+    );
+    assert_custom(
+        verify_assertion(&signed, client_data_json.as_bytes(), &expected),
+        USER_NOT_PRESENT,
+    );
+}
+
+#[test]
+fn test_assertion_fail_client_data() {
+    let expected = challenge(&withdraw_message());
+    let authenticator_data = authenticator_data(RP_ID, FLAGS);
+
+    // 提交的 clientDataJSON 与签名中的哈希不符
+    let signed = signed_message(&authenticator_data, &client_data_json(&expected));
+    let other = client_data_json(&challenge(&WithdrawMessage {
+        nonce: 1,
+        ..withdraw_message()
+    }));
+    assert_custom(
+        verify_assertion(&signed, other.as_bytes(), &expected),
+        INVALID_CLIENT_DATA,
+    );
+
+    // 注册（webauthn.create）产生的签名不能用于取款
+    let create = client_data_json(&expected).replace("webauthn.get", "webauthn.create");
+    let signed = signed_message(&authenticator_data, &create);
+    assert_custom(
+        verify_assertion(&signed, create.as_bytes(), &expected),
+        INVALID_CLIENT_DATA,
+    );
+
+    // authenticatorData 过短
+    assert_eq!(
+        verify_assertion(&signed[..36 + 32], create.as_bytes(), &expected),
+        Err(ProgramError::InvalidInstructionData)
+    );
+}
+
+#[test]
+fn test_client_data_parse() {
+    let challenge = URL_SAFE_NO_PAD.encode([9u8; 32]);
+    // 字段顺序任意，其它字段可以是嵌套对象、数组、转义字符串、数字或字面量
+    let json = format!(
+        " {{ \"origin\" : \"https://localhost\\\"\\\\\", \"tokenBinding\": {{\"status\":\"present\",\"id\":[1, -2.5e3, null]}},\n\
+         \"challenge\":\"{challenge}\", \"crossOrigin\": true, \"type\": \"webauthn.get\" }} "
+    );
+    let client_data = ClientData::parse(json.as_bytes()).unwrap();
+    assert_eq!(client_data.type_, b"webauthn.get");
+    assert_eq!(client_data.challenge, challenge.as_bytes());
+
+    for invalid in [
+        String::new(),
+        "{}".to_string(),
+        r#"{"type":"webauthn.get"}"#.to_string(),
+        format!(r#"{{"type":"webauthn.get","challenge":"{challenge}""#),
+        format!(r#"{{"type":"webauthn.get","challenge":"{challenge}"}} x"#),
+        format!(r#"{{"type":"webauthn.get","challenge":"{challenge}",}}"#),
+        format!(r#"{{"type":"webauthn.get","challenge":{challenge}}}"#),
+        // 重复的 challenge 不能用来绕过检查
+        format!(r#"{{"type":"webauthn.get","challenge":"{challenge}","challenge":"x"}}"#),
+    ] {
+        assert!(
+            ClientData::parse(invalid.as_bytes()).is_err(),
+            "应拒绝: {invalid}"
+        );
+    }
+}
@@ -0,0 +1,343 @@
+//! LiteSVM 集成测试：pinocchio_secp256r1_vault 的 Withdraw，签名由 secp256r1 预编译程序验证
+//!
+//! 运行前请先在 season1/ 构建程序：`cargo build-programs`（输出到 target/deploy），
+//! 测试默认 ignored，用 `cargo test-programs` 运行。
+
+mod common;
+
+use common::*;
+use litesvm::LiteSVM;
+use pinocchio_secp256r1_vault::webauthn::RP_ID;
+use pinocchio_secp256r1_vault::WithdrawMessage;
+use solana_address::Address;
+use solana_instruction::error::InstructionError;
+use solana_keypair::Keypair;
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+
+const AMOUNT: u64 = 250_000_000;
+
+#[test]
+fn test_precompile_accepts_signature() {
+    // 只验证测试构造的预编译指令本身，不需要程序二进制
+    let mut svm = LiteSVM::new();
+    let payer = Keypair::new();
+    svm.airdrop(&payer.pubkey(), 1_000_000_000).unwrap();
+    let signing_key = signing_key();
+
+    let mut ix = secp256r1_ix(&signing_key, b"withdraw");
+    let tx = Transaction::new_signed_with_payer(
+        std::slice::from_ref(&ix),
+        Some(&payer.pubkey()),
+        &[&payer],
+        svm.latest_blockhash(),
+    );
+    assert!(svm.send_transaction(tx).is_ok());
+
+    // 篡改消息后签名不再有效
+    *ix.data.last_mut().unwrap() ^= 1;
+    svm.expire_blockhash();
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&payer.pubkey()),
+        &[&payer],
+        svm.latest_blockhash(),
+    );
+    assert!(svm.send_transaction(tx).is_err());
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_withdraw_advances_nonce() {
+    let mut fixture = Fixture::deposited();
+    let payer = fixture.payer.pubkey();
+    let payer_before = fixture.lamports(&payer);
+
+    let msg = fixture.message(&payer, AMOUNT);
+    assert_eq!(msg.nonce, 0);
+    let result = fixture.withdraw(&msg);
+    assert!(result.is_ok(), "Withdraw 应成功: {:?}", result);
+    assert_eq!(fixture.nonce(), 1);
+    assert_eq!(fixture.lamports(&fixture.vault), DEPOSIT_AMOUNT - AMOUNT);
+    assert_eq!(
+        fixture.lamports(&payer),
+        payer_before + AMOUNT - WITHDRAW_FEE
+    );
+
+    // 下一条消息使用新的 nonce
+    let result = fixture.withdraw(&fixture.message(&payer, AMOUNT));
+    assert!(result.is_ok(), "第二次 Withdraw 应成功: {:?}", result);
+    assert_eq!(fixture.nonce(), 2);
+    assert_eq!(
+        fixture.lamports(&fixture.vault),
+        DEPOSIT_AMOUNT - 2 * AMOUNT
+    );
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_withdraw_fail_replay() {
+    let mut fixture = Fixture::deposited();
+    let payer = fixture.payer.pubkey();
+    let msg = fixture.message(&payer, AMOUNT);
+    let result = fixture.withdraw(&msg);
+    assert!(result.is_ok(), "Withdraw 应成功: {:?}", result);
+
+    // 同一签名消息再提交一次：nonce 已被消耗
+    let result = fixture.withdraw(&msg);
+    assert_custom_error(result, 0, INVALID_NONCE);
+
+    // 尚未轮到的 nonce 同样无效
+    let future = WithdrawMessage {
+        nonce: 2,
+        ..fixture.message(&payer, AMOUNT)
+    };
+    let result = fixture.withdraw(&future);
+    assert_custom_error(result, 0, INVALID_NONCE);
+    assert_eq!(fixture.nonce(), 1);
+    assert_eq!(fixture.lamports(&fixture.vault), DEPOSIT_AMOUNT - AMOUNT);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_withdraw_fail_domain_mismatch() {
+    let mut fixture = Fixture::deposited();
+    let payer = fixture.payer.pubkey();
+
+    // 为别的程序或别的 vault 签的消息不能在这里使用
+    for msg in [
+        WithdrawMessage {
+            program_id: Address::new_unique(),
+            ..fixture.message(&payer, AMOUNT)
+        },
+        WithdrawMessage {
+            vault: Address::new_unique(),
+            ..fixture.message(&payer, AMOUNT)
+        },
+    ] {
+        let result = fixture.withdraw(&msg);
+        assert_custom_error(result, 0, DOMAIN_MISMATCH);
+    }
+    assert_eq!(fixture.nonce(), 0);
+    assert_eq!(fixture.lamports(&fixture.vault), DEPOSIT_AMOUNT);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_withdraw_fail_expired() {
+    let mut fixture = Fixture::deposited();
+    let payer = fixture.payer.pubkey();
+    let msg = fixture.message(&payer, AMOUNT);
+
+    fixture.warp_to(msg.expiry + 1);
+    let result = fixture.withdraw(&msg);
+    assert_custom_error(result, 0, MESSAGE_EXPIRED);
+    assert_eq!(fixture.nonce(), 0);
+
+    // 恰好在 expiry 时仍然有效
+    fixture.warp_to(msg.expiry);
+    let result = fixture.withdraw(&msg);
+    assert!(result.is_ok(), "expiry 当刻 Withdraw 应成功: {:?}", result);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_withdraw_fail_without_precompile() {
+    let mut fixture = Fixture::deposited();
+    let payer = fixture.payer.pubkey();
+    let result = fixture.send(&[fixture.withdraw_ix(&payer, &[])]);
+    assert!(result.is_err());
+    assert_eq!(fixture.lamports(&fixture.vault), DEPOSIT_AMOUNT);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_deposit_with_prefunded_vault_state() {
+    let mut fixture = Fixture::new();
+    // 有人抢先往 state PDA 转了 lamports，Deposit 仍能创建状态
+    fixture.svm.airdrop(&fixture.vault_state, 1).unwrap();
+    let result = fixture.send(&[fixture.deposit_ix(DEPOSIT_AMOUNT)]);
+    assert!(result.is_ok(), "Deposit 应成功: {:?}", result);
+    assert_eq!(fixture.nonce(), 0);
+
+    let result = fixture.withdraw(&fixture.message(&fixture.payer.pubkey(), AMOUNT));
+    assert!(result.is_ok(), "Withdraw 应成功: {:?}", result);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_init_vault_state_for_funded_vault() {
+    let mut fixture = Fixture::new();
+    // 直接转账入金：vault 有余额但没有状态，Deposit 也不再接受
+    fixture.svm.airdrop(&fixture.vault, DEPOSIT_AMOUNT).unwrap();
+    let result = fixture.send(&[fixture.deposit_ix(DEPOSIT_AMOUNT)]);
+    assert_instruction_error(result, 0, InstructionError::InvalidAccountData);
+
+    let result = fixture.send(&[fixture.init_vault_state_ix()]);
+    assert!(result.is_ok(), "InitVaultState 应成功: {:?}", result);
+    assert_eq!(fixture.nonce(), 0);
+
+    let payer = fixture.payer.pubkey();
+    let result = fixture.withdraw(&fixture.message(&payer, AMOUNT));
+    assert!(result.is_ok(), "Withdraw 应成功: {:?}", result);
+    assert_eq!(fixture.nonce(), 1);
+
+    // 状态已存在时什么也不做，nonce 不会被重置
+    let result = fixture.send(&[fixture.init_vault_state_ix()]);
+    assert!(result.is_ok(), "重复 InitVaultState 应成功: {:?}", result);
+    assert_eq!(fixture.nonce(), 1);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_init_vault_state_fail_wrong_vault() {
+    let mut fixture = Fixture::new();
+    let mut ix = fixture.init_vault_state_ix();
+    ix.accounts[1].pubkey = Address::new_unique();
+    let result = fixture.send(&[ix]);
+    assert_instruction_error(result, 0, InstructionError::InvalidAccountOwner);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_partial_withdraw_to_other_recipient() {
+    let mut fixture = Fixture::deposited();
+    let payer = fixture.payer.pubkey();
+    let payer_before = fixture.lamports(&payer);
+
+    // 收款人由签名决定，payer 只代为提交并支付交易费
+    let recipient = Address::new_unique();
+    let result = fixture.withdraw(&fixture.message(&recipient, AMOUNT));
+    assert!(result.is_ok(), "Withdraw 应成功: {:?}", result);
+    assert_eq!(fixture.lamports(&recipient), AMOUNT);
+    assert_eq!(fixture.lamports(&fixture.vault), DEPOSIT_AMOUNT - AMOUNT);
+    assert_eq!(fixture.lamports(&payer), payer_before - WITHDRAW_FEE);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_withdraw_fail_recipient_mismatch() {
+    let mut fixture = Fixture::deposited();
+    let recipient = Address::new_unique();
+    let msg = fixture.message(&recipient, AMOUNT);
+
+    // 提交者把收款账户换成自己
+    let payer = fixture.payer.pubkey();
+    let ixs = [
+        fixture.withdraw_ix(&payer, &[]),
+        secp256r1_ix(&fixture.signing_key, &msg.to_bytes()),
+    ];
+    let result = fixture.send(&ixs);
+    assert_custom_error(result, 0, RECIPIENT_MISMATCH);
+    assert_eq!(fixture.lamports(&fixture.vault), DEPOSIT_AMOUNT);
+    assert_eq!(fixture.nonce(), 0);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_withdraw_fail_leaves_dust() {
+    let mut fixture = Fixture::deposited();
+    let payer = fixture.payer.pubkey();
+    let rent = fixture.svm.minimum_balance_for_rent_exemption(0);
+
+    // 剩余 1 lamport 低于免租下限
+    let msg = fixture.message(&payer, DEPOSIT_AMOUNT - 1);
+    let result = fixture.withdraw(&msg);
+    assert_custom_error(result, 0, BELOW_RENT_EXEMPTION);
+    let msg = fixture.message(&payer, DEPOSIT_AMOUNT - rent + 1);
+    let result = fixture.withdraw(&msg);
+    assert_custom_error(result, 0, BELOW_RENT_EXEMPTION);
+    assert_eq!(fixture.nonce(), 0);
+
+    // 恰好留下免租下限可以
+    let result = fixture.withdraw(&fixture.message(&payer, DEPOSIT_AMOUNT - rent));
+    assert!(result.is_ok(), "Withdraw 应成功: {:?}", result);
+    assert_eq!(fixture.lamports(&fixture.vault), rent);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_withdraw_exact_balance() {
+    let mut fixture = Fixture::deposited();
+    let payer = fixture.payer.pubkey();
+    let msg = fixture.message(&payer, DEPOSIT_AMOUNT);
+    let result = fixture.withdraw(&msg);
+    assert!(result.is_ok(), "取空 vault 应成功: {:?}", result);
+    assert_eq!(fixture.lamports(&fixture.vault), 0);
+    assert_eq!(fixture.nonce(), 1);
+
+    // 取空后再存款沿用原 nonce，旧签名不会重新生效
+    let result = fixture.send(&[fixture.deposit_ix(DEPOSIT_AMOUNT)]);
+    assert!(result.is_ok(), "再次 Deposit 应成功: {:?}", result);
+    assert_eq!(fixture.nonce(), 1);
+    let result = fixture.withdraw(&msg);
+    assert_custom_error(result, 0, INVALID_NONCE);
+    assert_eq!(fixture.lamports(&fixture.vault), DEPOSIT_AMOUNT);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_withdraw_fail_invalid_amount() {
+    let mut fixture = Fixture::deposited();
+    let payer = fixture.payer.pubkey();
+
+    let result = fixture.withdraw(&fixture.message(&payer, 0));
+    assert_custom_error(result, 0, INVALID_AMOUNT);
+
+    let result = fixture.withdraw(&fixture.message(&payer, DEPOSIT_AMOUNT + 1));
+    assert_instruction_error(result, 0, InstructionError::InsufficientFunds);
+    assert_eq!(fixture.nonce(), 0);
+    assert_eq!(fixture.lamports(&fixture.vault), DEPOSIT_AMOUNT);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_withdraw_with_passkey() {
+    let mut fixture = Fixture::deposited();
+    let recipient = Address::new_unique();
+    let msg = fixture.message(&recipient, AMOUNT);
+
+    let result = fixture.withdraw_with_passkey(&msg, &authenticator_data(RP_ID, PASSKEY_FLAGS));
+    assert!(result.is_ok(), "passkey Withdraw 应成功: {:?}", result);
+    assert_eq!(fixture.lamports(&recipient), AMOUNT);
+    assert_eq!(fixture.lamports(&fixture.vault), DEPOSIT_AMOUNT - AMOUNT);
+    assert_eq!(fixture.nonce(), 1);
+
+    // 同一断言不能重放
+    let result = fixture.withdraw_with_passkey(&msg, &authenticator_data(RP_ID, PASSKEY_FLAGS));
+    assert_custom_error(result, 0, INVALID_NONCE);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_withdraw_with_passkey_fail_assertion() {
+    let mut fixture = Fixture::deposited();
+    let payer = fixture.payer.pubkey();
+    let msg = fixture.message(&payer, AMOUNT);
+
+    // 其它依赖方的 passkey，或未经用户确认的断言
+    let result =
+        fixture.withdraw_with_passkey(&msg, &authenticator_data("evil.example", PASSKEY_FLAGS));
+    assert_custom_error(result, 0, RP_ID_MISMATCH);
+    let result = fixture.withdraw_with_passkey(&msg, &authenticator_data(RP_ID, 0));
+    assert_custom_error(result, 0, USER_NOT_PRESENT);
+
+    // 断言签的是原消息，提交时换成更大的金额
+    let client_data_json = client_data_json(&challenge(&msg));
+    let signed = signed_message(&authenticator_data(RP_ID, PASSKEY_FLAGS), &client_data_json);
+    let tampered = WithdrawMessage {
+        amount: 2 * AMOUNT,
+        ..fixture.message(&payer, AMOUNT)
+    };
+    let mut extra = tampered.to_bytes().to_vec();
+    extra.extend_from_slice(client_data_json.as_bytes());
+    let ixs = [
+        fixture.withdraw_ix(&payer, &extra),
+        secp256r1_ix(&fixture.signing_key, &signed),
+    ];
+    let result = fixture.send(&ixs);
+    assert_custom_error(result, 0, CHALLENGE_MISMATCH);
+
+    assert_eq!(fixture.nonce(), 0);
+    assert_eq!(fixture.lamports(&fixture.vault), DEPOSIT_AMOUNT);
+}
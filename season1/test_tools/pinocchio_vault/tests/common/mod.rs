@@ -0,0 +1,399 @@
+//! pinocchio_vault LiteSVM 测试的公共代码：账户准备与指令构造
+//!
+//! 运行前请先在 season1/ 构建程序：`cargo build-programs`，测试默认 ignored，用 `cargo test-programs` 运行。
+
+#![allow(dead_code, clippy::result_large_err)]
+
+use litesvm::types::TransactionResult;
+use litesvm::LiteSVM;
+use solana_address::Address;
+use solana_clock::Clock;
+use solana_instruction::error::InstructionError;
+use solana_instruction::{AccountMeta, Instruction};
+use solana_keypair::Keypair;
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+use solana_transaction_error::TransactionError;
+use test_support::{
+    associated_token_address, associated_token_program_id, mint_account, svm_with_programs,
+    system_program_id, token_account, token_program_id,
+};
+
+/// owner 初始 lamports
+pub const OWNER_BALANCE: u64 = 10_000_000_000;
+/// 每个签名的交易费
+pub const SIGNATURE_FEE: u64 = 5_000;
+
+pub fn vault_program_id() -> Address {
+    pinocchio_vault::ID
+}
+
+/// 返回 owner 的 (vault_pda, bump)
+pub fn find_vault_pda(owner: &Address) -> (Address, u8) {
+    Address::find_program_address(&[b"vault", owner.as_ref()], &vault_program_id())
+}
+
+/// 返回 owner 的 (vault_state_pda, bump)
+pub fn find_vault_state_pda(owner: &Address) -> (Address, u8) {
+    Address::find_program_address(&[b"vault_state", owner.as_ref()], &vault_program_id())
+}
+
+/// 返回 owner 在 mint 下的 (token vault authority, bump)
+pub fn find_token_vault_authority(owner: &Address, mint: &Address) -> (Address, u8) {
+    Address::find_program_address(
+        &[b"vault", owner.as_ref(), mint.as_ref()],
+        &vault_program_id(),
+    )
+}
+
+/// 返回 (owner, delegate) 的 (allowance_pda, bump)
+pub fn find_allowance_pda(owner: &Address, delegate: &Address) -> (Address, u8) {
+    Address::find_program_address(
+        &[b"allowance", owner.as_ref(), delegate.as_ref()],
+        &vault_program_id(),
+    )
+}
+
+/// 测试 mint 的精度与 owner 的初始 token 余额
+pub const MINT_DECIMALS: u8 = 6;
+pub const TOKEN_BALANCE: u64 = 1_000_000;
+
+/// owner 持有某个 mint 时的 token 相关地址
+pub struct TokenVault {
+    pub mint: Address,
+    pub owner_ata: Address,
+    pub authority: Address,
+    pub vault_ata: Address,
+}
+
+/// 加载 pinocchio_vault 并准备 owner；未构建二进制时 panic
+pub struct Fixture {
+    pub svm: LiteSVM,
+    pub owner: Keypair,
+    pub vault: Address,
+    pub vault_state: Address,
+}
+
+impl Fixture {
+    pub fn new() -> Self {
+        let mut svm = svm_with_programs(&[(vault_program_id(), "pinocchio_vault")]);
+        let owner = Keypair::new();
+        svm.airdrop(&owner.pubkey(), OWNER_BALANCE).unwrap();
+        let (vault, _) = find_vault_pda(&owner.pubkey());
+        let (vault_state, _) = find_vault_state_pda(&owner.pubkey());
+        Self {
+            svm,
+            owner,
+            vault,
+            vault_state,
+        }
+    }
+
+    /// 零数据系统账户的租金豁免下限
+    pub fn rent_exempt_minimum(&self) -> u64 {
+        self.svm.minimum_balance_for_rent_exemption(0)
+    }
+
+    /// Deposit 指令：discriminator(0) + amount(8)
+    pub fn deposit_ix(&self, amount: u64) -> Instruction {
+        let mut data = vec![0u8];
+        data.extend_from_slice(&amount.to_le_bytes());
+        Instruction {
+            program_id: vault_program_id(),
+            accounts: vec![
+                AccountMeta::new(self.owner.pubkey(), true),
+                AccountMeta::new(self.vault, false),
+                AccountMeta::new_readonly(system_program_id(), false),
+            ],
+            data,
+        }
+    }
+
+    /// Withdraw 指令：discriminator(1) + 可选 amount(8)，省略时取出全部
+    pub fn withdraw_ix(&self, amount: Option<u64>) -> Instruction {
+        let mut data = vec![1u8];
+        if let Some(amount) = amount {
+            data.extend_from_slice(&amount.to_le_bytes());
+        }
+        Instruction {
+            program_id: vault_program_id(),
+            accounts: vec![
+                AccountMeta::new(self.owner.pubkey(), true),
+                AccountMeta::new(self.vault, false),
+                AccountMeta::new_readonly(self.vault_state, false),
+                AccountMeta::new_readonly(system_program_id(), false),
+            ],
+            data,
+        }
+    }
+
+    /// SetTimelock 指令：discriminator(2) + unlock_at(8) + cooldown(8)
+    pub fn set_timelock_ix(&self, unlock_at: i64, cooldown: i64) -> Instruction {
+        let mut data = vec![2u8];
+        data.extend_from_slice(&unlock_at.to_le_bytes());
+        data.extend_from_slice(&cooldown.to_le_bytes());
+        Instruction {
+            program_id: vault_program_id(),
+            accounts: vec![
+                AccountMeta::new(self.owner.pubkey(), true),
+                AccountMeta::new(self.vault_state, false),
+                AccountMeta::new_readonly(system_program_id(), false),
+            ],
+            data,
+        }
+    }
+
+    /// RequestWithdraw 指令：discriminator(3) + amount(8)
+    pub fn request_withdraw_ix(&self, amount: u64) -> Instruction {
+        let mut data = vec![3u8];
+        data.extend_from_slice(&amount.to_le_bytes());
+        Instruction {
+            program_id: vault_program_id(),
+            accounts: vec![
+                AccountMeta::new(self.owner.pubkey(), true),
+                AccountMeta::new_readonly(self.vault, false),
+                AccountMeta::new(self.vault_state, false),
+            ],
+            data,
+        }
+    }
+
+    /// ExecuteWithdraw 指令：discriminator(4)
+    pub fn execute_withdraw_ix(&self) -> Instruction {
+        Instruction {
+            program_id: vault_program_id(),
+            accounts: vec![
+                AccountMeta::new(self.owner.pubkey(), true),
+                AccountMeta::new(self.vault, false),
+                AccountMeta::new(self.vault_state, false),
+                AccountMeta::new_readonly(system_program_id(), false),
+            ],
+            data: vec![4u8],
+        }
+    }
+
+    /// CancelWithdraw 指令：discriminator(5)
+    pub fn cancel_withdraw_ix(&self) -> Instruction {
+        Instruction {
+            program_id: vault_program_id(),
+            accounts: vec![
+                AccountMeta::new(self.owner.pubkey(), true),
+                AccountMeta::new(self.vault_state, false),
+            ],
+            data: vec![5u8],
+        }
+    }
+
+    /// 新建一个 mint，并给 owner 的 ATA 写入 `TOKEN_BALANCE`
+    pub fn token_vault(&mut self) -> TokenVault {
+        let mint = Address::new_unique();
+        self.svm
+            .set_account(mint, mint_account(MINT_DECIMALS, TOKEN_BALANCE))
+            .unwrap();
+        let owner_ata = associated_token_address(&self.owner.pubkey(), &mint);
+        self.svm
+            .set_account(
+                owner_ata,
+                token_account(&mint, &self.owner.pubkey(), TOKEN_BALANCE),
+            )
+            .unwrap();
+        let (authority, _) = find_token_vault_authority(&self.owner.pubkey(), &mint);
+        TokenVault {
+            mint,
+            owner_ata,
+            authority,
+            vault_ata: associated_token_address(&authority, &mint),
+        }
+    }
+
+    /// DepositToken 指令：discriminator(6) + amount(8)
+    pub fn deposit_token_ix(&self, token: &TokenVault, amount: u64) -> Instruction {
+        let mut data = vec![6u8];
+        data.extend_from_slice(&amount.to_le_bytes());
+        Instruction {
+            program_id: vault_program_id(),
+            accounts: vec![
+                AccountMeta::new(self.owner.pubkey(), true),
+                AccountMeta::new_readonly(token.mint, false),
+                AccountMeta::new(token.owner_ata, false),
+                AccountMeta::new_readonly(token.authority, false),
+                AccountMeta::new(token.vault_ata, false),
+                AccountMeta::new_readonly(system_program_id(), false),
+                AccountMeta::new_readonly(token_program_id(), false),
+                AccountMeta::new_readonly(associated_token_program_id(), false),
+            ],
+            data,
+        }
+    }
+
+    /// WithdrawToken 指令：discriminator(7) + 可选 amount(8)，省略时取出全部并关闭 vault
+    pub fn withdraw_token_ix(&self, token: &TokenVault, amount: Option<u64>) -> Instruction {
+        let mut data = vec![7u8];
+        if let Some(amount) = amount {
+            data.extend_from_slice(&amount.to_le_bytes());
+        }
+        Instruction {
+            program_id: vault_program_id(),
+            accounts: vec![
+                AccountMeta::new(self.owner.pubkey(), true),
+                AccountMeta::new_readonly(token.mint, false),
+                AccountMeta::new(token.owner_ata, false),
+                AccountMeta::new_readonly(token.authority, false),
+                AccountMeta::new(token.vault_ata, false),
+                AccountMeta::new_readonly(self.vault_state, false),
+                AccountMeta::new_readonly(token_program_id(), false),
+            ],
+            data,
+        }
+    }
+
+    /// 针对 token vault 的 RequestWithdraw：vault 位置换成 authority，末尾追加 mint 与 vault ATA
+    pub fn request_token_withdraw_ix(&self, token: &TokenVault, amount: u64) -> Instruction {
+        let mut ix = self.request_withdraw_ix(amount);
+        ix.accounts[1].pubkey = token.authority;
+        ix.accounts.extend([
+            AccountMeta::new_readonly(token.mint, false),
+            AccountMeta::new_readonly(token.vault_ata, false),
+        ]);
+        ix
+    }
+
+    /// 针对 token vault 的 ExecuteWithdraw：system program 换成 token program，
+    /// 末尾追加 mint、owner ATA 与 vault ATA
+    pub fn execute_token_withdraw_ix(&self, token: &TokenVault) -> Instruction {
+        let mut ix = self.execute_withdraw_ix();
+        ix.accounts[1] = AccountMeta::new_readonly(token.authority, false);
+        ix.accounts[3].pubkey = token_program_id();
+        ix.accounts.extend([
+            AccountMeta::new_readonly(token.mint, false),
+            AccountMeta::new(token.owner_ata, false),
+            AccountMeta::new(token.vault_ata, false),
+        ]);
+        ix
+    }
+
+    /// ApproveDelegate 指令：discriminator(8) + amount(8) + expires_at(8) + period(8) + period_limit(8)
+    pub fn approve_delegate_ix(
+        &self,
+        delegate: &Address,
+        amount: u64,
+        expires_at: i64,
+        period: i64,
+        period_limit: u64,
+    ) -> Instruction {
+        let mut data = vec![8u8];
+        data.extend_from_slice(&amount.to_le_bytes());
+        data.extend_from_slice(&expires_at.to_le_bytes());
+        data.extend_from_slice(&period.to_le_bytes());
+        data.extend_from_slice(&period_limit.to_le_bytes());
+        let (allowance, _) = find_allowance_pda(&self.owner.pubkey(), delegate);
+        Instruction {
+            program_id: vault_program_id(),
+            accounts: vec![
+                AccountMeta::new(self.owner.pubkey(), true),
+                AccountMeta::new_readonly(*delegate, false),
+                AccountMeta::new(allowance, false),
+                AccountMeta::new_readonly(system_program_id(), false),
+            ],
+            data,
+        }
+    }
+
+    /// WithdrawAsDelegate 指令：discriminator(9) + amount(8)，由 delegate 签名
+    pub fn withdraw_as_delegate_ix(&self, delegate: &Address, amount: u64) -> Instruction {
+        let mut data = vec![9u8];
+        data.extend_from_slice(&amount.to_le_bytes());
+        let (allowance, _) = find_allowance_pda(&self.owner.pubkey(), delegate);
+        Instruction {
+            program_id: vault_program_id(),
+            accounts: vec![
+                AccountMeta::new(*delegate, true),
+                AccountMeta::new_readonly(self.owner.pubkey(), false),
+                AccountMeta::new(self.vault, false),
+                AccountMeta::new(allowance, false),
+                AccountMeta::new_readonly(self.vault_state, false),
+                AccountMeta::new_readonly(system_program_id(), false),
+            ],
+            data,
+        }
+    }
+
+    /// RevokeDelegate 指令：discriminator(10)
+    pub fn revoke_delegate_ix(&self, delegate: &Address) -> Instruction {
+        let (allowance, _) = find_allowance_pda(&self.owner.pubkey(), delegate);
+        Instruction {
+            program_id: vault_program_id(),
+            accounts: vec![
+                AccountMeta::new(self.owner.pubkey(), true),
+                AccountMeta::new(allowance, false),
+            ],
+            data: vec![10u8],
+        }
+    }
+
+    pub fn token_balance(&self, address: &Address) -> u64 {
+        self.svm
+            .get_account(address)
+            .map(|account| test_support::token_account_amount(&account.data))
+            .unwrap_or(0)
+    }
+
+    /// 当前链上时间
+    pub fn now(&self) -> i64 {
+        self.svm.get_sysvar::<Clock>().unix_timestamp
+    }
+
+    /// 将时钟拨到 `unix_timestamp`
+    pub fn warp_to(&mut self, unix_timestamp: i64) {
+        let mut clock = self.svm.get_sysvar::<Clock>();
+        clock.unix_timestamp = unix_timestamp;
+        self.svm.set_sysvar(&clock);
+    }
+
+    /// 以 `signer` 为 payer 发送单条指令
+    pub fn send(&mut self, ix: Instruction, signer: &Keypair) -> TransactionResult {
+        self.svm.expire_blockhash();
+        let tx = Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&signer.pubkey()),
+            &[signer],
+            self.svm.latest_blockhash(),
+        );
+        self.svm.send_transaction(tx)
+    }
+
+    /// owner 发送指令
+    pub fn send_as_owner(&mut self, ix: Instruction) -> TransactionResult {
+        let owner = self.owner.insecure_clone();
+        self.send(ix, &owner)
+    }
+
+    pub fn owner_address(&self) -> Address {
+        self.owner.pubkey()
+    }
+
+    pub fn lamports(&self, address: &Address) -> u64 {
+        self.svm
+            .get_account(address)
+            .map(|account| account.lamports)
+            .unwrap_or(0)
+    }
+}
+
+/// 断言交易因指定的 InstructionError 失败（第 0 条指令）
+pub fn assert_instruction_error(result: TransactionResult, expected: InstructionError) {
+    match result {
+        Err(failed) => assert_eq!(
+            failed.err,
+            TransactionError::InstructionError(0, expected),
+            "logs: {:#?}",
+            failed.meta.logs
+        ),
+        Ok(_) => panic!("交易应失败，期望 {:?}", expected),
+    }
+}
+
+/// 断言交易因指定的 VaultError 自定义错误码失败
+pub fn assert_custom_error(result: TransactionResult, code: u32) {
+    assert_instruction_error(result, InstructionError::Custom(code));
+}
@@ -0,0 +1,203 @@
+//! LiteSVM 集成测试：pinocchio_vault 的委托取款额度
+//!
+//! 运行前请先在 season1/ 构建程序：`cargo build-programs`（输出到 target/deploy），
+//! 测试默认 ignored，用 `cargo test-programs` 运行。
+
+mod common;
+
+use common::*;
+use solana_instruction::error::InstructionError;
+use solana_keypair::Keypair;
+use solana_signer::Signer;
+
+/// VaultError 错误码
+const VAULT_LOCKED: u32 = 6002;
+const INVALID_ALLOWANCE: u32 = 6008;
+const ALLOWANCE_EXPIRED: u32 = 6009;
+const ALLOWANCE_EXCEEDED: u32 = 6010;
+const PERIOD_LIMIT_EXCEEDED: u32 = 6011;
+
+const DEPOSIT_AMOUNT: u64 = 5_000_000_000;
+const CAP: u64 = 1_000_000_000;
+const DAY: i64 = 86_400;
+
+/// 已存入的金库和一个有 SOL 付手续费的 delegate
+fn setup() -> (Fixture, Keypair) {
+    let mut fixture = Fixture::new();
+    let result = fixture.send_as_owner(fixture.deposit_ix(DEPOSIT_AMOUNT));
+    assert!(result.is_ok(), "Deposit 应成功: {:?}", result);
+    let delegate = Keypair::new();
+    fixture
+        .svm
+        .airdrop(&delegate.pubkey(), OWNER_BALANCE)
+        .unwrap();
+    (fixture, delegate)
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_delegate_withdraws_within_cap() {
+    let (mut fixture, delegate) = setup();
+    let result =
+        fixture.send_as_owner(fixture.approve_delegate_ix(&delegate.pubkey(), CAP, 0, 0, 0));
+    assert!(result.is_ok(), "ApproveDelegate 应成功: {:?}", result);
+
+    let before = fixture.lamports(&delegate.pubkey());
+    let result = fixture.send(
+        fixture.withdraw_as_delegate_ix(&delegate.pubkey(), 600_000_000),
+        &delegate,
+    );
+    assert!(result.is_ok(), "WithdrawAsDelegate 应成功: {:?}", result);
+    assert_eq!(
+        fixture.lamports(&delegate.pubkey()),
+        before + 600_000_000 - SIGNATURE_FEE
+    );
+    assert_eq!(
+        fixture.lamports(&fixture.vault),
+        DEPOSIT_AMOUNT - 600_000_000
+    );
+
+    // 总额度只剩 400_000_000
+    let result = fixture.send(
+        fixture.withdraw_as_delegate_ix(&delegate.pubkey(), 400_000_001),
+        &delegate,
+    );
+    assert_custom_error(result, ALLOWANCE_EXCEEDED);
+    let result = fixture.send(
+        fixture.withdraw_as_delegate_ix(&delegate.pubkey(), 400_000_000),
+        &delegate,
+    );
+    assert!(result.is_ok(), "用完剩余额度应成功: {:?}", result);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_period_limit_rolls_over() {
+    let (mut fixture, delegate) = setup();
+    let result = fixture.send_as_owner(fixture.approve_delegate_ix(
+        &delegate.pubkey(),
+        CAP,
+        0,
+        DAY,
+        300_000_000,
+    ));
+    assert!(result.is_ok(), "ApproveDelegate 应成功: {:?}", result);
+
+    let start = fixture.now();
+    let result = fixture.send(
+        fixture.withdraw_as_delegate_ix(&delegate.pubkey(), 200_000_000),
+        &delegate,
+    );
+    assert!(result.is_ok(), "WithdrawAsDelegate 应成功: {:?}", result);
+    let result = fixture.send(
+        fixture.withdraw_as_delegate_ix(&delegate.pubkey(), 100_000_001),
+        &delegate,
+    );
+    assert_custom_error(result, PERIOD_LIMIT_EXCEEDED);
+
+    // 新的周期重新计算
+    fixture.warp_to(start + DAY);
+    let result = fixture.send(
+        fixture.withdraw_as_delegate_ix(&delegate.pubkey(), 300_000_000),
+        &delegate,
+    );
+    assert!(result.is_ok(), "新周期内取款应成功: {:?}", result);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_expired_allowance() {
+    let (mut fixture, delegate) = setup();
+    let expires_at = fixture.now() + DAY;
+    let result = fixture.send_as_owner(fixture.approve_delegate_ix(
+        &delegate.pubkey(),
+        CAP,
+        expires_at,
+        0,
+        0,
+    ));
+    assert!(result.is_ok(), "ApproveDelegate 应成功: {:?}", result);
+
+    fixture.warp_to(expires_at);
+    let result = fixture.send(
+        fixture.withdraw_as_delegate_ix(&delegate.pubkey(), 1),
+        &delegate,
+    );
+    assert_custom_error(result, ALLOWANCE_EXPIRED);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_invalid_terms() {
+    let (mut fixture, delegate) = setup();
+    let result = fixture.send_as_owner(fixture.approve_delegate_ix(&delegate.pubkey(), 0, 0, 0, 0));
+    assert_custom_error(result, INVALID_ALLOWANCE);
+    // 设置了周期就必须有周期额度
+    let result =
+        fixture.send_as_owner(fixture.approve_delegate_ix(&delegate.pubkey(), CAP, 0, DAY, 0));
+    assert_custom_error(result, INVALID_ALLOWANCE);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_revoke_closes_allowance() {
+    let (mut fixture, delegate) = setup();
+    let result =
+        fixture.send_as_owner(fixture.approve_delegate_ix(&delegate.pubkey(), CAP, 0, 0, 0));
+    assert!(result.is_ok(), "ApproveDelegate 应成功: {:?}", result);
+
+    let (allowance, _) = find_allowance_pda(&fixture.owner_address(), &delegate.pubkey());
+    let rent = fixture.lamports(&allowance);
+    let owner_before = fixture.lamports(&fixture.owner_address());
+    let result = fixture.send_as_owner(fixture.revoke_delegate_ix(&delegate.pubkey()));
+    assert!(result.is_ok(), "RevokeDelegate 应成功: {:?}", result);
+    assert_eq!(fixture.lamports(&allowance), 0);
+    assert_eq!(
+        fixture.lamports(&fixture.owner_address()),
+        owner_before + rent - SIGNATURE_FEE
+    );
+
+    let result = fixture.send(
+        fixture.withdraw_as_delegate_ix(&delegate.pubkey(), 1),
+        &delegate,
+    );
+    assert_instruction_error(result, InstructionError::InvalidAccountOwner);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_other_key_cannot_use_allowance() {
+    let (mut fixture, delegate) = setup();
+    let result =
+        fixture.send_as_owner(fixture.approve_delegate_ix(&delegate.pubkey(), CAP, 0, 0, 0));
+    assert!(result.is_ok(), "ApproveDelegate 应成功: {:?}", result);
+
+    // 攻击者拿着 delegate 的 allowance 账户自己签名
+    let attacker = Keypair::new();
+    fixture
+        .svm
+        .airdrop(&attacker.pubkey(), OWNER_BALANCE)
+        .unwrap();
+    let mut ix = fixture.withdraw_as_delegate_ix(&delegate.pubkey(), 1);
+    ix.accounts[0].pubkey = attacker.pubkey();
+    let result = fixture.send(ix, &attacker);
+    assert_instruction_error(result, InstructionError::InvalidAccountData);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_timelock_applies_to_delegate() {
+    let (mut fixture, delegate) = setup();
+    let result =
+        fixture.send_as_owner(fixture.approve_delegate_ix(&delegate.pubkey(), CAP, 0, 0, 0));
+    assert!(result.is_ok(), "ApproveDelegate 应成功: {:?}", result);
+    let unlock_at = fixture.now() + DAY;
+    let result = fixture.send_as_owner(fixture.set_timelock_ix(unlock_at, 0));
+    assert!(result.is_ok(), "SetTimelock 应成功: {:?}", result);
+
+    let result = fixture.send(
+        fixture.withdraw_as_delegate_ix(&delegate.pubkey(), 1),
+        &delegate,
+    );
+    assert_custom_error(result, VAULT_LOCKED);
+}
@@ -0,0 +1,98 @@
+//! LiteSVM 集成测试：pinocchio_vault 的存入、追加存入与部分取出
+//!
+//! 运行前请先在 season1/ 构建程序：`cargo build-programs`（输出到 target/deploy），
+//! 测试默认 ignored，用 `cargo test-programs` 运行。
+
+mod common;
+
+use common::*;
+use solana_signer::Signer;
+
+/// VaultError 错误码
+const INVALID_AMOUNT: u32 = 6000;
+const BELOW_RENT_EXEMPTION: u32 = 6001;
+
+const DEPOSIT_AMOUNT: u64 = 1_000_000_000;
+
+fn deposited() -> Fixture {
+    let mut fixture = Fixture::new();
+    let result = fixture.send_as_owner(fixture.deposit_ix(DEPOSIT_AMOUNT));
+    assert!(result.is_ok(), "Deposit 应成功: {:?}", result);
+    fixture
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_deposit_on_top() {
+    let mut fixture = deposited();
+    assert_eq!(fixture.lamports(&fixture.vault), DEPOSIT_AMOUNT);
+
+    // 非空金库可以继续存入
+    let result = fixture.send_as_owner(fixture.deposit_ix(1));
+    assert!(result.is_ok(), "追加存入应成功: {:?}", result);
+    assert_eq!(fixture.lamports(&fixture.vault), DEPOSIT_AMOUNT + 1);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_deposit_fail_below_rent_exemption() {
+    let mut fixture = Fixture::new();
+    let amount = fixture.rent_exempt_minimum() - 1;
+    let result = fixture.send_as_owner(fixture.deposit_ix(amount));
+    assert_custom_error(result, BELOW_RENT_EXEMPTION);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_partial_withdraw() {
+    let mut fixture = deposited();
+    let owner_before = fixture.lamports(&fixture.owner.pubkey());
+
+    let result = fixture.send_as_owner(fixture.withdraw_ix(Some(DEPOSIT_AMOUNT / 4)));
+    assert!(result.is_ok(), "部分取出应成功: {:?}", result);
+    assert_eq!(fixture.lamports(&fixture.vault), DEPOSIT_AMOUNT * 3 / 4);
+    assert_eq!(
+        fixture.lamports(&fixture.owner.pubkey()),
+        owner_before + DEPOSIT_AMOUNT / 4 - SIGNATURE_FEE
+    );
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_withdraw_fail_leaving_dust() {
+    let mut fixture = deposited();
+    // 剩余低于租金豁免下限但不为零
+    let amount = DEPOSIT_AMOUNT - fixture.rent_exempt_minimum() + 1;
+    let result = fixture.send_as_owner(fixture.withdraw_ix(Some(amount)));
+    assert_custom_error(result, BELOW_RENT_EXEMPTION);
+    assert_eq!(fixture.lamports(&fixture.vault), DEPOSIT_AMOUNT);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_withdraw_exact_balance_empties_vault() {
+    let mut fixture = deposited();
+    let result = fixture.send_as_owner(fixture.withdraw_ix(Some(DEPOSIT_AMOUNT)));
+    assert!(result.is_ok(), "全部取出应成功: {:?}", result);
+    assert_eq!(fixture.lamports(&fixture.vault), 0);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_withdraw_without_amount_sweeps_vault() {
+    let mut fixture = deposited();
+    let result = fixture.send_as_owner(fixture.withdraw_ix(None));
+    assert!(result.is_ok(), "取出全部应成功: {:?}", result);
+    assert_eq!(fixture.lamports(&fixture.vault), 0);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_withdraw_fail_more_than_balance() {
+    let mut fixture = deposited();
+    let result = fixture.send_as_owner(fixture.withdraw_ix(Some(DEPOSIT_AMOUNT + 1)));
+    assert_custom_error(result, INVALID_AMOUNT);
+
+    let result = fixture.send_as_owner(fixture.withdraw_ix(Some(0)));
+    assert_custom_error(result, INVALID_AMOUNT);
+}
@@ -0,0 +1,154 @@
+//! LiteSVM 集成测试：pinocchio_vault 的时间锁与两阶段取出
+//!
+//! 运行前请先在 season1/ 构建程序：`cargo build-programs`（输出到 target/deploy），
+//! 测试默认 ignored，用 `cargo test-programs` 运行。
+
+mod common;
+
+use common::*;
+
+/// VaultError 错误码
+const VAULT_LOCKED: u32 = 6002;
+const WITHDRAWAL_REQUEST_REQUIRED: u32 = 6003;
+const WITHDRAWAL_NOT_READY: u32 = 6004;
+const NO_PENDING_WITHDRAWAL: u32 = 6005;
+const PENDING_WITHDRAWAL_EXISTS: u32 = 6006;
+const INVALID_TIMELOCK: u32 = 6007;
+
+const DEPOSIT_AMOUNT: u64 = 1_000_000_000;
+const LOCK_SECONDS: i64 = 3_600;
+const COOLDOWN: i64 = 600;
+
+fn deposited() -> Fixture {
+    let mut fixture = Fixture::new();
+    let result = fixture.send_as_owner(fixture.deposit_ix(DEPOSIT_AMOUNT));
+    assert!(result.is_ok(), "Deposit 应成功: {:?}", result);
+    fixture
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_withdraw_blocked_until_unlock() {
+    let mut fixture = deposited();
+    let unlock_at = fixture.now() + LOCK_SECONDS;
+    let result = fixture.send_as_owner(fixture.set_timelock_ix(unlock_at, 0));
+    assert!(result.is_ok(), "SetTimelock 应成功: {:?}", result);
+
+    let result = fixture.send_as_owner(fixture.withdraw_ix(None));
+    assert_custom_error(result, VAULT_LOCKED);
+
+    // 到达解锁时间后即可直接取出
+    fixture.warp_to(unlock_at);
+    let result = fixture.send_as_owner(fixture.withdraw_ix(None));
+    assert!(result.is_ok(), "解锁后 Withdraw 应成功: {:?}", result);
+    assert_eq!(fixture.lamports(&fixture.vault), 0);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_timelock_only_tightens() {
+    let mut fixture = deposited();
+    let unlock_at = fixture.now() + LOCK_SECONDS;
+    let result = fixture.send_as_owner(fixture.set_timelock_ix(unlock_at, COOLDOWN));
+    assert!(result.is_ok(), "SetTimelock 应成功: {:?}", result);
+
+    // 锁定期内不能提前解锁时间
+    let result = fixture.send_as_owner(fixture.set_timelock_ix(unlock_at - 1, COOLDOWN));
+    assert_custom_error(result, INVALID_TIMELOCK);
+
+    // 冷却期不能缩短
+    let result = fixture.send_as_owner(fixture.set_timelock_ix(unlock_at, COOLDOWN - 1));
+    assert_custom_error(result, INVALID_TIMELOCK);
+
+    // 负值无效
+    let result = fixture.send_as_owner(fixture.set_timelock_ix(-1, COOLDOWN));
+    assert_custom_error(result, INVALID_TIMELOCK);
+
+    // 延长是允许的
+    let result = fixture.send_as_owner(fixture.set_timelock_ix(unlock_at + 1, COOLDOWN + 1));
+    assert!(result.is_ok(), "延长锁定应成功: {:?}", result);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_two_phase_withdraw() {
+    let mut fixture = deposited();
+    let result = fixture.send_as_owner(fixture.set_timelock_ix(0, COOLDOWN));
+    assert!(result.is_ok(), "SetTimelock 应成功: {:?}", result);
+
+    // 有冷却期时不能直接取出
+    let result = fixture.send_as_owner(fixture.withdraw_ix(None));
+    assert_custom_error(result, WITHDRAWAL_REQUEST_REQUIRED);
+
+    let result = fixture.send_as_owner(fixture.execute_withdraw_ix());
+    assert_custom_error(result, NO_PENDING_WITHDRAWAL);
+
+    let start = fixture.now();
+    let result = fixture.send_as_owner(fixture.request_withdraw_ix(DEPOSIT_AMOUNT));
+    assert!(result.is_ok(), "RequestWithdraw 应成功: {:?}", result);
+
+    let result = fixture.send_as_owner(fixture.request_withdraw_ix(1));
+    assert_custom_error(result, PENDING_WITHDRAWAL_EXISTS);
+
+    let result = fixture.send_as_owner(fixture.execute_withdraw_ix());
+    assert_custom_error(result, WITHDRAWAL_NOT_READY);
+
+    fixture.warp_to(start + COOLDOWN);
+    let owner_before = fixture.lamports(&fixture.owner_address());
+    let result = fixture.send_as_owner(fixture.execute_withdraw_ix());
+    assert!(result.is_ok(), "ExecuteWithdraw 应成功: {:?}", result);
+    assert_eq!(fixture.lamports(&fixture.vault), 0);
+    assert_eq!(
+        fixture.lamports(&fixture.owner_address()),
+        owner_before + DEPOSIT_AMOUNT - SIGNATURE_FEE
+    );
+
+    // 请求已被消费
+    let result = fixture.send_as_owner(fixture.execute_withdraw_ix());
+    assert_custom_error(result, NO_PENDING_WITHDRAWAL);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_execute_respects_unlock_time() {
+    let mut fixture = deposited();
+    let unlock_at = fixture.now() + LOCK_SECONDS;
+    let result = fixture.send_as_owner(fixture.set_timelock_ix(unlock_at, COOLDOWN));
+    assert!(result.is_ok(), "SetTimelock 应成功: {:?}", result);
+
+    let result = fixture.send_as_owner(fixture.request_withdraw_ix(DEPOSIT_AMOUNT));
+    assert!(result.is_ok(), "RequestWithdraw 应成功: {:?}", result);
+
+    // 冷却期已过但仍在锁定期内
+    fixture.warp_to(unlock_at - 1);
+    let result = fixture.send_as_owner(fixture.execute_withdraw_ix());
+    assert_custom_error(result, VAULT_LOCKED);
+
+    fixture.warp_to(unlock_at);
+    let result = fixture.send_as_owner(fixture.execute_withdraw_ix());
+    assert!(result.is_ok(), "ExecuteWithdraw 应成功: {:?}", result);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_cancel_withdraw() {
+    let mut fixture = deposited();
+    let result = fixture.send_as_owner(fixture.set_timelock_ix(0, COOLDOWN));
+    assert!(result.is_ok(), "SetTimelock 应成功: {:?}", result);
+
+    let result = fixture.send_as_owner(fixture.cancel_withdraw_ix());
+    assert_custom_error(result, NO_PENDING_WITHDRAWAL);
+
+    let start = fixture.now();
+    let result = fixture.send_as_owner(fixture.request_withdraw_ix(DEPOSIT_AMOUNT));
+    assert!(result.is_ok(), "RequestWithdraw 应成功: {:?}", result);
+
+    let result = fixture.send_as_owner(fixture.cancel_withdraw_ix());
+    assert!(result.is_ok(), "CancelWithdraw 应成功: {:?}", result);
+
+    // 取消后冷却结束也无法执行
+    fixture.warp_to(start + COOLDOWN);
+    let result = fixture.send_as_owner(fixture.execute_withdraw_ix());
+    assert_custom_error(result, NO_PENDING_WITHDRAWAL);
+    assert_eq!(fixture.lamports(&fixture.vault), DEPOSIT_AMOUNT);
+}
@@ -0,0 +1,242 @@
+//! LiteSVM 集成测试：pinocchio_vault 的 SPL token 存取与冷却期下的两阶段取出
+//!
+//! 运行前请先在 season1/ 构建程序：`cargo build-programs`（输出到 target/deploy），
+//! 测试默认 ignored，用 `cargo test-programs` 运行。
+
+mod common;
+
+use common::*;
+use solana_instruction::error::InstructionError;
+use solana_signer::Signer;
+use test_support::token_2022_mint;
+
+/// VaultError 错误码
+const INVALID_AMOUNT: u32 = 6000;
+const VAULT_LOCKED: u32 = 6002;
+const WITHDRAWAL_REQUEST_REQUIRED: u32 = 6003;
+const WITHDRAWAL_NOT_READY: u32 = 6004;
+const NO_PENDING_WITHDRAWAL: u32 = 6005;
+const UNSUPPORTED_MINT: u32 = 6012;
+
+const DEPOSIT_AMOUNT: u64 = 400_000;
+const COOLDOWN: i64 = 600;
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_deposit_creates_token_vault() {
+    let mut fixture = Fixture::new();
+    let token = fixture.token_vault();
+
+    let result = fixture.send_as_owner(fixture.deposit_token_ix(&token, DEPOSIT_AMOUNT));
+    assert!(result.is_ok(), "DepositToken 应成功: {:?}", result);
+    assert_eq!(fixture.token_balance(&token.vault_ata), DEPOSIT_AMOUNT);
+    assert_eq!(
+        fixture.token_balance(&token.owner_ata),
+        TOKEN_BALANCE - DEPOSIT_AMOUNT
+    );
+
+    // vault ATA 已存在时继续存入
+    let result = fixture.send_as_owner(fixture.deposit_token_ix(&token, 1));
+    assert!(result.is_ok(), "追加存入应成功: {:?}", result);
+    assert_eq!(fixture.token_balance(&token.vault_ata), DEPOSIT_AMOUNT + 1);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_partial_then_full_withdraw_closes_vault() {
+    let mut fixture = Fixture::new();
+    let token = fixture.token_vault();
+    let result = fixture.send_as_owner(fixture.deposit_token_ix(&token, DEPOSIT_AMOUNT));
+    assert!(result.is_ok(), "DepositToken 应成功: {:?}", result);
+
+    let result = fixture.send_as_owner(fixture.withdraw_token_ix(&token, Some(100_000)));
+    assert!(result.is_ok(), "部分取出应成功: {:?}", result);
+    assert_eq!(
+        fixture.token_balance(&token.vault_ata),
+        DEPOSIT_AMOUNT - 100_000
+    );
+
+    // 取出剩余全部：vault ATA 关闭，租金退回 owner
+    let vault_rent = fixture.lamports(&token.vault_ata);
+    let owner_before = fixture.lamports(&fixture.owner_address());
+    let result = fixture.send_as_owner(fixture.withdraw_token_ix(&token, None));
+    assert!(result.is_ok(), "全部取出应成功: {:?}", result);
+    assert_eq!(fixture.lamports(&token.vault_ata), 0);
+    assert_eq!(fixture.token_balance(&token.owner_ata), TOKEN_BALANCE);
+    assert_eq!(
+        fixture.lamports(&fixture.owner_address()),
+        owner_before + vault_rent - SIGNATURE_FEE
+    );
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_withdraw_fail_over_balance() {
+    let mut fixture = Fixture::new();
+    let token = fixture.token_vault();
+    let result = fixture.send_as_owner(fixture.deposit_token_ix(&token, DEPOSIT_AMOUNT));
+    assert!(result.is_ok(), "DepositToken 应成功: {:?}", result);
+
+    let result = fixture.send_as_owner(fixture.withdraw_token_ix(&token, Some(DEPOSIT_AMOUNT + 1)));
+    assert_custom_error(result, INVALID_AMOUNT);
+
+    let result = fixture.send_as_owner(fixture.deposit_token_ix(&token, 0));
+    assert_custom_error(result, INVALID_AMOUNT);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_vaults_are_per_owner_and_mint() {
+    let mut fixture = Fixture::new();
+    let token = fixture.token_vault();
+    let other = fixture.token_vault();
+    let result = fixture.send_as_owner(fixture.deposit_token_ix(&token, DEPOSIT_AMOUNT));
+    assert!(result.is_ok(), "DepositToken 应成功: {:?}", result);
+
+    // 另一个 mint 的 authority 不能冒充
+    let forged = TokenVault {
+        authority: other.authority,
+        ..token
+    };
+    let result = fixture.send_as_owner(fixture.withdraw_token_ix(&forged, None));
+    assert_instruction_error(result, InstructionError::InvalidSeeds);
+
+    // 其他人用自己的签名取不到 owner 的 token vault
+    let attacker = solana_keypair::Keypair::new();
+    fixture
+        .svm
+        .airdrop(&attacker.pubkey(), OWNER_BALANCE)
+        .unwrap();
+    let mut ix = fixture.withdraw_token_ix(&token, None);
+    ix.accounts[0].pubkey = attacker.pubkey();
+    let result = fixture.send(ix, &attacker);
+    assert_instruction_error(result, InstructionError::InvalidSeeds);
+    assert_eq!(fixture.token_balance(&token.vault_ata), DEPOSIT_AMOUNT);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_withdraw_blocked_until_unlock() {
+    let mut fixture = Fixture::new();
+    let token = fixture.token_vault();
+    let result = fixture.send_as_owner(fixture.deposit_token_ix(&token, DEPOSIT_AMOUNT));
+    assert!(result.is_ok(), "DepositToken 应成功: {:?}", result);
+
+    let unlock_at = fixture.now() + 3_600;
+    let result = fixture.send_as_owner(fixture.set_timelock_ix(unlock_at, 0));
+    assert!(result.is_ok(), "SetTimelock 应成功: {:?}", result);
+
+    let result = fixture.send_as_owner(fixture.withdraw_token_ix(&token, None));
+    assert_custom_error(result, VAULT_LOCKED);
+    assert_eq!(fixture.token_balance(&token.vault_ata), DEPOSIT_AMOUNT);
+
+    // 省略或替换 state 账户不能绕过时间锁
+    let mut ix = fixture.withdraw_token_ix(&token, None);
+    ix.accounts[5].pubkey = solana_address::Address::new_unique();
+    let result = fixture.send_as_owner(ix);
+    assert_instruction_error(result, InstructionError::InvalidSeeds);
+
+    fixture.warp_to(unlock_at);
+    let result = fixture.send_as_owner(fixture.withdraw_token_ix(&token, None));
+    assert!(result.is_ok(), "解锁后 WithdrawToken 应成功: {:?}", result);
+    assert_eq!(fixture.token_balance(&token.owner_ata), TOKEN_BALANCE);
+}
+
+/// 存入后设置冷却期
+fn deposited_with_cooldown() -> (Fixture, TokenVault) {
+    let mut fixture = Fixture::new();
+    let token = fixture.token_vault();
+    let result = fixture.send_as_owner(fixture.deposit_token_ix(&token, DEPOSIT_AMOUNT));
+    assert!(result.is_ok(), "DepositToken 应成功: {:?}", result);
+    let result = fixture.send_as_owner(fixture.set_timelock_ix(0, COOLDOWN));
+    assert!(result.is_ok(), "SetTimelock 应成功: {:?}", result);
+    (fixture, token)
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_withdraw_fail_under_cooldown() {
+    let (mut fixture, token) = deposited_with_cooldown();
+
+    // 有冷却期时泄露的 owner 私钥不能直接取走 token
+    let result = fixture.send_as_owner(fixture.withdraw_token_ix(&token, Some(1)));
+    assert_custom_error(result, WITHDRAWAL_REQUEST_REQUIRED);
+    let result = fixture.send_as_owner(fixture.withdraw_token_ix(&token, None));
+    assert_custom_error(result, WITHDRAWAL_REQUEST_REQUIRED);
+    assert_eq!(fixture.token_balance(&token.vault_ata), DEPOSIT_AMOUNT);
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_two_phase_token_withdraw() {
+    let (mut fixture, token) = deposited_with_cooldown();
+
+    let result =
+        fixture.send_as_owner(fixture.request_token_withdraw_ix(&token, DEPOSIT_AMOUNT + 1));
+    assert_custom_error(result, INVALID_AMOUNT);
+
+    let result = fixture.send_as_owner(fixture.request_token_withdraw_ix(&token, 100_000));
+    assert!(result.is_ok(), "RequestWithdraw 应成功: {:?}", result);
+    let ready_at = fixture.now() + COOLDOWN;
+
+    let result = fixture.send_as_owner(fixture.execute_token_withdraw_ix(&token));
+    assert_custom_error(result, WITHDRAWAL_NOT_READY);
+
+    // 请求绑定 mint：不能用来取 lamports 或其他 mint 的 token
+    fixture.warp_to(ready_at);
+    let result = fixture.send_as_owner(fixture.execute_withdraw_ix());
+    assert_custom_error(result, NO_PENDING_WITHDRAWAL);
+    let other = fixture.token_vault();
+    let result = fixture.send_as_owner(fixture.deposit_token_ix(&other, DEPOSIT_AMOUNT));
+    assert!(result.is_ok(), "DepositToken 应成功: {:?}", result);
+    let result = fixture.send_as_owner(fixture.execute_token_withdraw_ix(&other));
+    assert_custom_error(result, NO_PENDING_WITHDRAWAL);
+
+    let result = fixture.send_as_owner(fixture.execute_token_withdraw_ix(&token));
+    assert!(result.is_ok(), "ExecuteWithdraw 应成功: {:?}", result);
+    assert_eq!(
+        fixture.token_balance(&token.vault_ata),
+        DEPOSIT_AMOUNT - 100_000
+    );
+    assert_eq!(
+        fixture.token_balance(&token.owner_ata),
+        TOKEN_BALANCE - DEPOSIT_AMOUNT + 100_000
+    );
+
+    // 请求剩余全部：执行后 vault ATA 关闭，租金退回 owner
+    let result =
+        fixture.send_as_owner(fixture.request_token_withdraw_ix(&token, DEPOSIT_AMOUNT - 100_000));
+    assert!(result.is_ok(), "RequestWithdraw 应成功: {:?}", result);
+    fixture.warp_to(fixture.now() + COOLDOWN);
+    let vault_rent = fixture.lamports(&token.vault_ata);
+    let owner_before = fixture.lamports(&fixture.owner_address());
+    let result = fixture.send_as_owner(fixture.execute_token_withdraw_ix(&token));
+    assert!(result.is_ok(), "ExecuteWithdraw 应成功: {:?}", result);
+    assert_eq!(fixture.lamports(&token.vault_ata), 0);
+    assert_eq!(fixture.token_balance(&token.owner_ata), TOKEN_BALANCE);
+    assert_eq!(
+        fixture.lamports(&fixture.owner_address()),
+        owner_before + vault_rent - SIGNATURE_FEE
+    );
+}
+
+#[test]
+#[ignore = "需要程序二进制：先在 season1/ 运行 cargo build-programs"]
+fn test_deposit_fail_token_2022_mint() {
+    let mut fixture = Fixture::new();
+    let token = fixture.token_vault();
+    // 同一地址换成 Token-2022 mint：即使传入旧版 token program 也被拒绝
+    fixture
+        .svm
+        .set_account(
+            token.mint,
+            token_2022_mint(MINT_DECIMALS, TOKEN_BALANCE, &[]),
+        )
+        .unwrap();
+
+    let result = fixture.send_as_owner(fixture.deposit_token_ix(&token, DEPOSIT_AMOUNT));
+    assert_custom_error(result, UNSUPPORTED_MINT);
+    let result = fixture.send_as_owner(fixture.withdraw_token_ix(&token, None));
+    assert_custom_error(result, UNSUPPORTED_MINT);
+    assert_eq!(fixture.lamports(&token.vault_ata), 0);
+}
@@ -0,0 +1,259 @@
+//! season1 程序的 LiteSVM 测试公共代码
+//!
+//! 程序二进制统一由 workspace 根目录的 `cargo build-programs`（见 `.cargo/config.toml`）
+//! 构建到 `season1/target/deploy/<crate>.so`，测试通过 [`program_path`] 定位；
+//! 设置 `SBF_OUT_DIR` 可改用其它目录。
+//!
+//! 加载程序的测试都标记为 `#[ignore]`，普通 `cargo test` 只显示为 ignored；
+//! 先 `cargo build-programs` 再 `cargo test-programs` 运行它们，缺少二进制时直接失败。
+
+use std::path::PathBuf;
+
+use litesvm::LiteSVM;
+use solana_account::Account;
+use solana_address::Address;
+use solana_program::program_option::COption;
+use solana_program::program_pack::Pack;
+use solana_program::pubkey::Pubkey as SolanaProgramPubkey;
+use solana_program::rent::Rent;
+
+/// SPL Token 账户 amount 在 offset 64
+pub const TOKEN_ACCOUNT_AMOUNT_OFFSET: usize = 64;
+/// SPL Token 账户长度
+pub const TOKEN_ACCOUNT_LEN: usize = 165;
+/// SPL Mint supply 在 offset 36
+pub const MINT_SUPPLY_OFFSET: usize = 36;
+/// SPL Mint decimals 在 offset 44
+pub const MINT_DECIMALS_OFFSET: usize = 44;
+/// SPL Mint 长度
+pub const MINT_LEN: usize = 82;
+/// Mint 账户租金豁免余额
+pub const MINT_RENT: u64 = 1_461_600;
+/// Token 账户租金豁免余额
+pub const TOKEN_ACCOUNT_RENT: u64 = 2_039_280;
+
+/// `.so` 输出目录：`SBF_OUT_DIR`，否则为 workspace 的 `target/deploy`
+pub fn deploy_dir() -> PathBuf {
+    if let Some(dir) = std::env::var_os("SBF_OUT_DIR") {
+        return PathBuf::from(dir);
+    }
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../target/deploy")
+}
+
+/// 程序二进制路径，`name` 为 crate 名（如 `pinocchio_amm`）
+pub fn program_path(name: &str) -> PathBuf {
+    deploy_dir().join(format!("{name}.so"))
+}
+
+/// 创建 LiteSVM 并加载所有程序；任一二进制缺失时 panic，避免测试在没有程序时空跑通过
+pub fn svm_with_programs(programs: &[(Address, &str)]) -> LiteSVM {
+    let mut svm = LiteSVM::new();
+    for (program_id, name) in programs {
+        let path = program_path(name);
+        assert!(
+            path.exists(),
+            "未找到程序二进制 {:?}，请先在 season1/ 运行 cargo build-programs",
+            path
+        );
+        svm.add_program_from_file(*program_id, path).unwrap();
+    }
+    svm
+}
+
+/// SPL Token 程序 ID
+pub fn token_program_id() -> Address {
+    Address::from(spl_token::id().to_bytes())
+}
+
+/// Token-2022 程序 ID
+pub fn token_2022_program_id() -> Address {
+    Address::from_str_const("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb")
+}
+
+/// System 程序 ID
+pub fn system_program_id() -> Address {
+    solana_system_program::id()
+}
+
+/// Associated Token 程序 ID
+pub fn associated_token_program_id() -> Address {
+    Address::from(spl_associated_token_account::id().to_bytes())
+}
+
+/// 将 solana_address::Address 转换为 spl 系列 crate 使用的 solana_program::pubkey::Pubkey
+pub fn to_program_pubkey(address: &Address) -> SolanaProgramPubkey {
+    SolanaProgramPubkey::new_from_array(address.to_bytes())
+}
+
+/// 将 solana_program::pubkey::Pubkey 转换回 solana_address::Address
+pub fn from_program_pubkey(pubkey: &SolanaProgramPubkey) -> Address {
+    Address::from(pubkey.to_bytes())
+}
+
+/// wallet 在 mint 下的 ATA 地址（SPL Token 程序）
+pub fn associated_token_address(wallet: &Address, mint: &Address) -> Address {
+    from_program_pubkey(&spl_associated_token_account::get_associated_token_address(
+        &to_program_pubkey(wallet),
+        &to_program_pubkey(mint),
+    ))
+}
+
+/// wallet 在 mint 下的 ATA 地址（指定 token 程序，用于 Token-2022）
+pub fn associated_token_address_with_program(
+    wallet: &Address,
+    mint: &Address,
+    token_program: &Address,
+) -> Address {
+    from_program_pubkey(
+        &spl_associated_token_account::get_associated_token_address_with_program_id(
+            &to_program_pubkey(wallet),
+            &to_program_pubkey(mint),
+            &to_program_pubkey(token_program),
+        ),
+    )
+}
+
+/// 读取 SPL Token 账户 amount
+pub fn token_account_amount(data: &[u8]) -> u64 {
+    u64::from_le_bytes(
+        data[TOKEN_ACCOUNT_AMOUNT_OFFSET..TOKEN_ACCOUNT_AMOUNT_OFFSET + 8]
+            .try_into()
+            .unwrap(),
+    )
+}
+
+/// 读取 SPL Mint supply
+pub fn mint_supply(data: &[u8]) -> u64 {
+    u64::from_le_bytes(
+        data[MINT_SUPPLY_OFFSET..MINT_SUPPLY_OFFSET + 8]
+            .try_into()
+            .unwrap(),
+    )
+}
+
+/// 读取 SPL Mint decimals
+pub fn mint_decimals(data: &[u8]) -> u8 {
+    data[MINT_DECIMALS_OFFSET]
+}
+
+/// 构造一个 SPL Token 账户（165 字节，仅填充 amount），用于直接写入 LiteSVM
+pub fn token_account_with_amount(amount: u64) -> Account {
+    let mut data = vec![0u8; TOKEN_ACCOUNT_LEN];
+    data[TOKEN_ACCOUNT_AMOUNT_OFFSET..TOKEN_ACCOUNT_AMOUNT_OFFSET + 8]
+        .copy_from_slice(&amount.to_le_bytes());
+    Account {
+        lamports: 2_039_280,
+        data,
+        owner: token_program_id(),
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+/// 构造一个已初始化的 SPL Mint（无 mint/freeze authority），用于直接写入 LiteSVM
+pub fn mint_account(decimals: u8, supply: u64) -> Account {
+    let mut data = vec![0u8; MINT_LEN];
+    spl_token::state::Mint {
+        mint_authority: COption::None,
+        supply,
+        decimals,
+        is_initialized: true,
+        freeze_authority: COption::None,
+    }
+    .pack_into_slice(&mut data);
+    Account {
+        lamports: MINT_RENT,
+        data,
+        owner: token_program_id(),
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+/// 构造一个已初始化的 SPL Token 账户（mint / owner / amount），用于直接写入 LiteSVM
+pub fn token_account(mint: &Address, owner: &Address, amount: u64) -> Account {
+    let mut data = vec![0u8; TOKEN_ACCOUNT_LEN];
+    spl_token::state::Account {
+        mint: to_program_pubkey(mint),
+        owner: to_program_pubkey(owner),
+        amount,
+        delegate: COption::None,
+        state: spl_token::state::AccountState::Initialized,
+        is_native: COption::None,
+        delegated_amount: 0,
+        close_authority: COption::None,
+    }
+    .pack_into_slice(&mut data);
+    Account {
+        lamports: TOKEN_ACCOUNT_RENT,
+        data,
+        owner: token_program_id(),
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+/// Token-2022 账户类型字节：基础数据（mint 补零）到 165 字节，之后是类型与 TLV 扩展
+pub const TOKEN_2022_ACCOUNT_TYPE_OFFSET: usize = 165;
+pub const TOKEN_2022_ACCOUNT_TYPE_MINT: u8 = 1;
+pub const TOKEN_2022_ACCOUNT_TYPE_ACCOUNT: u8 = 2;
+
+/// 在基础数据后追加 Token-2022 类型字节与 TLV 扩展，`extensions` 为 (类型, 值)
+fn with_token_2022_extensions(
+    mut account: Account,
+    account_type: u8,
+    extensions: &[(u16, Vec<u8>)],
+) -> Account {
+    if !extensions.is_empty() {
+        account.data.resize(TOKEN_2022_ACCOUNT_TYPE_OFFSET, 0);
+        account.data.push(account_type);
+        for (extension, value) in extensions {
+            account.data.extend_from_slice(&extension.to_le_bytes());
+            account
+                .data
+                .extend_from_slice(&(value.len() as u16).to_le_bytes());
+            account.data.extend_from_slice(value);
+        }
+    }
+    account.lamports = Rent::default().minimum_balance(account.data.len());
+    account.owner = token_2022_program_id();
+    account
+}
+
+/// 构造一个已初始化的 Token-2022 Mint（无 authority），带给定扩展
+pub fn token_2022_mint(decimals: u8, supply: u64, extensions: &[(u16, Vec<u8>)]) -> Account {
+    with_token_2022_extensions(
+        mint_account(decimals, supply),
+        TOKEN_2022_ACCOUNT_TYPE_MINT,
+        extensions,
+    )
+}
+
+/// 构造一个已初始化的 Token-2022 Token 账户，带给定扩展
+pub fn token_2022_account(
+    mint: &Address,
+    owner: &Address,
+    amount: u64,
+    extensions: &[(u16, Vec<u8>)],
+) -> Account {
+    with_token_2022_extensions(
+        token_account(mint, owner, amount),
+        TOKEN_2022_ACCOUNT_TYPE_ACCOUNT,
+        extensions,
+    )
+}
+
+/// 读取 Token-2022 账户中某个扩展的值；没有扩展或未找到时返回 None
+pub fn token_2022_extension(data: &[u8], extension: u16) -> Option<&[u8]> {
+    let mut offset = TOKEN_2022_ACCOUNT_TYPE_OFFSET + 1;
+    while offset + 4 <= data.len() {
+        let ext_type = u16::from_le_bytes([data[offset], data[offset + 1]]);
+        let ext_len = u16::from_le_bytes([data[offset + 2], data[offset + 3]]) as usize;
+        let value = data.get(offset + 4..offset + 4 + ext_len)?;
+        if ext_type == extension {
+            return Some(value);
+        }
+        offset += 4 + ext_len;
+    }
+    None
+}